@@ -8,7 +8,8 @@ import java.util.function.Consumer;
 public abstract class AbstractOpaquePtr implements AutoCloseable {
     private static final Cleaner cleaner = Cleaner.create();
 
-    private final long ptr;
+    private long ptr;
+    private final Freedom freedom;
     private final Cleaner.Cleanable cleanable;
 
     /**
@@ -17,7 +18,8 @@ public abstract class AbstractOpaquePtr implements AutoCloseable {
      */
     protected AbstractOpaquePtr(long ptr, Consumer<Long> free) {
         this.ptr = ptr;
-        this.cleanable = cleaner.register(this, new Freedom(ptr, free));
+        this.freedom = new Freedom(ptr, free);
+        this.cleanable = cleaner.register(this, this.freedom);
 
         if (this.ptr == 0) {
             throw new NullPointerException(
@@ -28,6 +30,7 @@ public abstract class AbstractOpaquePtr implements AutoCloseable {
     private static class Freedom implements Runnable {
         private final long ptr;
         private final Consumer<Long> free;
+        private volatile boolean taken = false;
 
         Freedom(long ptr, Consumer<Long> free) {
             this.ptr = ptr;
@@ -35,7 +38,8 @@ public abstract class AbstractOpaquePtr implements AutoCloseable {
         }
 
         public void run() {
-            this.free.accept(this.ptr);
+            if (!this.taken)
+                this.free.accept(this.ptr);
         }
     }
 
@@ -48,6 +52,18 @@ public abstract class AbstractOpaquePtr implements AutoCloseable {
         return this.ptr;
     }
 
+    /**
+     * Transfers ownership of the pointer, e.g. to a native method that consumes
+     * it, the pointer will not be freed and this object can no longer be used.
+     */
+    protected long takePtr() {
+        long ptr = this.getPtr();
+        this.freedom.taken = true;
+        this.cleanable.clean();
+        this.ptr = 0;
+        return ptr;
+    }
+
     @Override
     public void close() {
         this.cleanable.clean();
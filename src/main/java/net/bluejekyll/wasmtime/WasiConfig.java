@@ -0,0 +1,145 @@
+package net.bluejekyll.wasmtime;
+
+import javax.annotation.concurrent.NotThreadSafe;
+
+import java.io.File;
+import java.io.InputStream;
+import java.io.OutputStream;
+
+/**
+ * The WASI context for a WasmStore, see
+ * {@link WasmEngine#newStore(WasiConfig)}.
+ * <p>
+ * By default the WASM module has no access to the host, there are no args, no
+ * env vars, stdio is discarded and no directories are available.
+ */
+@NotThreadSafe
+public class WasiConfig extends AbstractOpaquePtr {
+    /** Access granted to the WASM module for a preopened directory */
+    public enum DirMode {
+        READ_ONLY,
+        READ_WRITE,
+    }
+
+    public WasiConfig() {
+        super(newWasiConfigNtv(), WasiConfig::freeWasiConfig);
+    }
+
+    private static native long newWasiConfigNtv();
+
+    private static native void freeWasiConfig(long ptr);
+
+    private static native void argNtv(long ptr, String arg) throws WasmtimeException;
+
+    private static native void envNtv(long ptr, String key, String value) throws WasmtimeException;
+
+    private static native void preopenDirNtv(long ptr, String hostPath, String guestPath, boolean readOnly)
+            throws WasmtimeException;
+
+    private static native void inheritStdioNtv(long ptr, boolean inherit);
+
+    private static native void stdinStreamNtv(long ptr, InputStream stream) throws WasmtimeException;
+
+    private static native void stdinBytesNtv(long ptr, byte[] bytes) throws WasmtimeException;
+
+    private static native void stdoutStreamNtv(long ptr, OutputStream stream) throws WasmtimeException;
+
+    private static native void stdoutBufferNtv(long ptr);
+
+    private static native void stderrStreamNtv(long ptr, OutputStream stream) throws WasmtimeException;
+
+    private static native void stderrBufferNtv(long ptr);
+
+    /** Append an argument, by convention the first is the program name */
+    public WasiConfig arg(String arg) throws WasmtimeException {
+        argNtv(this.getPtr(), arg);
+        return this;
+    }
+
+    public WasiConfig args(String... args) throws WasmtimeException {
+        for (String arg : args) {
+            this.arg(arg);
+        }
+        return this;
+    }
+
+    /** Add an environment variable, the host environment is never inherited */
+    public WasiConfig env(String key, String value) throws WasmtimeException {
+        envNtv(this.getPtr(), key, value);
+        return this;
+    }
+
+    /**
+     * Make a host directory available to the WASM module, the directory is opened
+     * when the store is created.
+     *
+     * @param hostDir   directory on the host
+     * @param guestPath the path the WASM module will see the directory at
+     * @param mode      the access granted to the WASM module
+     */
+    public WasiConfig preopenDir(File hostDir, String guestPath, DirMode mode) throws WasmtimeException {
+        preopenDirNtv(this.getPtr(), hostDir.getAbsolutePath(), guestPath, mode == DirMode.READ_ONLY);
+        return this;
+    }
+
+    /**
+     * Share the stdin, stdout and stderr of the host process with the WASM
+     * module. If false, stdio is discarded, replacing any redirection.
+     */
+    public WasiConfig inheritStdio(boolean inherit) {
+        inheritStdioNtv(this.getPtr(), inherit);
+        return this;
+    }
+
+    /**
+     * Read stdin of the WASM module from the stream, the stream is read on the
+     * thread calling into WASM and is shared by all stores created from this
+     * config.
+     */
+    public WasiConfig stdin(InputStream stream) throws WasmtimeException {
+        stdinStreamNtv(this.getPtr(), stream);
+        return this;
+    }
+
+    /** Read stdin of the WASM module from a copy of the bytes */
+    public WasiConfig stdin(byte[] bytes) throws WasmtimeException {
+        stdinBytesNtv(this.getPtr(), bytes);
+        return this;
+    }
+
+    /**
+     * Write stdout of the WASM module to the stream, the stream is written to on
+     * the thread calling into WASM and is shared by all stores created from this
+     * config.
+     */
+    public WasiConfig stdout(OutputStream stream) throws WasmtimeException {
+        stdoutStreamNtv(this.getPtr(), stream);
+        return this;
+    }
+
+    /**
+     * Capture stdout of the WASM module in memory, each store gets its own buffer
+     *
+     * @see WasmStore#readStdout()
+     */
+    public WasiConfig stdoutBuffer() {
+        stdoutBufferNtv(this.getPtr());
+        return this;
+    }
+
+    /** @see #stdout(OutputStream) */
+    public WasiConfig stderr(OutputStream stream) throws WasmtimeException {
+        stderrStreamNtv(this.getPtr(), stream);
+        return this;
+    }
+
+    /**
+     * Capture stderr of the WASM module in memory, each store gets its own buffer
+     *
+     * @see WasmStore#readStderr()
+     */
+    public WasiConfig stderrBuffer() {
+        stderrBufferNtv(this.getPtr());
+        return this;
+    }
+}
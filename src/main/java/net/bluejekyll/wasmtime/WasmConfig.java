@@ -0,0 +1,165 @@
+package net.bluejekyll.wasmtime;
+
+import javax.annotation.concurrent.NotThreadSafe;
+
+import java.io.File;
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+
+/**
+ * Configuration for a WasmEngine, all settings must be applied before the
+ * engine is created with {@link Wasmtime#newWasmEngine(WasmConfig)}.
+ */
+@NotThreadSafe
+public class WasmConfig extends AbstractOpaquePtr {
+    /**
+     * Cranelift optimization levels, the ordinals must match those in the native
+     * bindings
+     */
+    public enum OptLevel {
+        NONE,
+        SPEED,
+        SPEED_AND_SIZE,
+    }
+
+    public WasmConfig() {
+        super(newConfigNtv(), WasmConfig::freeConfig);
+    }
+
+    private static native long newConfigNtv();
+
+    private static native void freeConfig(long ptr);
+
+    private static native void consumeFuelNtv(long ptr, boolean enable);
+
+    private static native void epochInterruptionNtv(long ptr, boolean enable);
+
+    private static native void wasmSimdNtv(long ptr, boolean enable);
+
+    private static native void wasmMultiMemoryNtv(long ptr, boolean enable);
+
+    private static native void wasmMultiValueNtv(long ptr, boolean enable);
+
+    private static native void wasmBulkMemoryNtv(long ptr, boolean enable);
+
+    private static native void wasmReferenceTypesNtv(long ptr, boolean enable);
+
+    private static native void craneliftOptLevelNtv(long ptr, int level) throws WasmtimeException;
+
+    private static native void debugInfoNtv(long ptr, boolean enable);
+
+    private static native void maxWasmStackNtv(long ptr, long size) throws WasmtimeException;
+
+    private static native void cacheConfigLoadNtv(long ptr, String path) throws WasmtimeException;
+
+    /**
+     * Instrument the compiled code to consume fuel, stores created from the engine
+     * will then need fuel added before any functions can be called.
+     */
+    public WasmConfig consumeFuel(boolean enable) {
+        consumeFuelNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    /**
+     * Allow for executing WASM to be interrupted, this is required for deadlines
+     * on stores.
+     * <p>
+     * Wasmtime 0.31 has no epochs, this compiles the code as interruptable, the
+     * deadlines are enforced through the store's interrupt handle by a ticker
+     * thread in the native library.
+     */
+    public WasmConfig epochInterruption(boolean enable) {
+        epochInterruptionNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig wasmSimd(boolean enable) {
+        wasmSimdNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig wasmMultiMemory(boolean enable) {
+        wasmMultiMemoryNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig wasmMultiValue(boolean enable) {
+        wasmMultiValueNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig wasmBulkMemory(boolean enable) {
+        wasmBulkMemoryNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig wasmReferenceTypes(boolean enable) {
+        wasmReferenceTypesNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    public WasmConfig craneliftOptLevel(OptLevel level) throws WasmtimeException {
+        craneliftOptLevelNtv(this.getPtr(), level.ordinal());
+        return this;
+    }
+
+    /** Generate DWARF debug information for the compiled code */
+    public WasmConfig debugInfo(boolean enable) {
+        debugInfoNtv(this.getPtr(), enable);
+        return this;
+    }
+
+    /** The maximum stack size, in bytes, that WASM code is allowed to consume */
+    public WasmConfig maxWasmStack(long size) throws WasmtimeException {
+        maxWasmStackNtv(this.getPtr(), size);
+        return this;
+    }
+
+    /**
+     * Enable the compilation cache for modules with a Wasmtime cache config file,
+     * see https://bytecodealliance.github.io/wasmtime/cli-cache.html
+     *
+     * Cache hit and miss statistics are not reported, Wasmtime 0.31 does not
+     * expose its cache counters outside of the crate.
+     */
+    public WasmConfig cacheConfigLoad(File configFile) throws WasmtimeException {
+        cacheConfigLoadNtv(this.getPtr(), configFile.getAbsolutePath());
+        return this;
+    }
+
+    /**
+     * Enable the compilation cache for modules, storing compiled modules in the
+     * directory with the default cache settings.
+     *
+     * See {@link #cacheConfigLoad(File)}, hit and miss statistics are not
+     * reported.
+     */
+    public WasmConfig cacheDirectory(File directory) throws WasmtimeException {
+        // TOML literal strings have no escapes, which would otherwise be needed for
+        // Windows paths
+        String path = directory.getAbsolutePath();
+        if (path.contains("'")) {
+            throw new WasmtimeException(String.format("unsupported cache directory: %s", path));
+        }
+
+        String toml = String.format("[cache]%nenabled = true%ndirectory = '%s'%n", path);
+
+        Path configFile = null;
+        try {
+            configFile = Files.createTempFile("wasmtime-cache", ".toml");
+            Files.write(configFile, toml.getBytes(StandardCharsets.UTF_8));
+
+            // the config file is read immediately
+            return this.cacheConfigLoad(configFile.toFile());
+        } catch (IOException e) {
+            throw new WasmtimeException("failed to write cache config", e);
+        } finally {
+            if (configFile != null) {
+                configFile.toFile().delete();
+            }
+        }
+    }
+}
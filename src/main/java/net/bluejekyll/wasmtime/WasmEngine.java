@@ -7,6 +7,7 @@ import java.io.FileInputStream;
 import java.io.IOException;
 import java.io.InputStream;
 import java.nio.ByteBuffer;
+import java.util.Optional;
 
 // TODO implement cloneable and clone the underlying engine between threads
 @NotThreadSafe
@@ -19,8 +20,17 @@ public class WasmEngine extends AbstractOpaquePtr implements Cloneable {
 
     private static native long newStoreNtv(long engine_ptr);
 
+    private static native long newStoreWithWasiNtv(long engine_ptr, long wasi_config_ptr) throws WasmtimeException;
+
     private static native long newModuleNtv(long engine_ptr, ByteBuffer wasm_bytes) throws WasmtimeException;
 
+    private static native WasmValidationError validateNtv(long engine_ptr, byte[] wasm_bytes)
+            throws WasmtimeException;
+
+    private static native long deserializeModuleNtv(long engine_ptr, byte[] serialized) throws WasmtimeException;
+
+    private static native long deserializeModuleFileNtv(long engine_ptr, String path) throws WasmtimeException;
+
     // takes a pointer to an engine
     private static native long newLinker(long engine_ptr) throws WasmtimeException;
 
@@ -32,6 +42,14 @@ public class WasmEngine extends AbstractOpaquePtr implements Cloneable {
         return new WasmStore(storePtr);
     }
 
+    /**
+     * @param wasiConfig the WASI context for the store, the config may be reused
+     *                   for other stores after this call
+     */
+    public WasmStore newStore(WasiConfig wasiConfig) throws WasmtimeException {
+        return new WasmStore(newStoreWithWasiNtv(super.getPtr(), wasiConfig.getPtr()));
+    }
+
     public WasmModule newModule(ByteBuffer wasm_bytes) throws WasmtimeException {
         if (!wasm_bytes.isDirect())
             throw new WasmtimeException("passed in buffer must be direct");
@@ -52,6 +70,41 @@ public class WasmEngine extends AbstractOpaquePtr implements Cloneable {
         }
     }
 
+    /**
+     * Check that the bytes are a valid WASM binary for this engine's
+     * configuration, without compiling them. The WAT text format is not
+     * accepted.
+     *
+     * @return the reason the binary is invalid, empty if it is valid
+     */
+    public Optional<WasmValidationError> validate(byte[] wasm_bytes) throws WasmtimeException {
+        return Optional.ofNullable(validateNtv(super.getPtr(), wasm_bytes));
+    }
+
+    /**
+     * Load a module that was precompiled with {@link WasmModule#serialize()}.
+     * <p>
+     * The bytes must only come from a trusted source, they are executable code
+     * for the host. A WasmtimeException is thrown if the module was serialized by
+     * a different version of Wasmtime or an engine with a different
+     * configuration.
+     */
+    public WasmModule deserializeModule(byte[] serialized) throws WasmtimeException {
+        return new WasmModule(deserializeModuleNtv(super.getPtr(), serialized));
+    }
+
+    /**
+     * Load a module from a file containing the output of
+     * {@link WasmModule#serialize()}, the file must not be modified while the
+     * module is in use. Failing to read the file is reported as such, not as an
+     * incompatible module.
+     *
+     * @see #deserializeModule(byte[])
+     */
+    public WasmModule deserializeModuleFile(File path) throws WasmtimeException {
+        return new WasmModule(deserializeModuleFileNtv(super.getPtr(), path.getAbsolutePath()));
+    }
+
     public WasmLinker newLinker() throws WasmtimeException {
         long ptr = newLinker(this.getPtr());
         return new WasmLinker(ptr);
@@ -2,9 +2,15 @@ package net.bluejekyll.wasmtime;
 
 import javax.annotation.concurrent.NotThreadSafe;
 import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
 import java.lang.reflect.Parameter;
+import java.nio.ByteBuffer;
+import java.time.Duration;
 import java.util.ArrayList;
+import java.util.Arrays;
 import java.util.List;
+import java.util.Objects;
+import java.util.Optional;
 import net.bluejekyll.wasmtime.ty.*;
 
 @NotThreadSafe
@@ -16,13 +22,23 @@ public class WasmFunction extends AbstractOpaquePtr {
     private static native void freeFunc(long ptr);
 
     private static native long createFunc(long store_ptr, Method method, Object obj,
-            Class<? extends WasmType> returnType,
-            List<Class<? extends WasmType>> paramTypes) throws WasmtimeException;
+            Class<?> returnType,
+            List<Class<?>> paramTypes) throws WasmtimeException;
 
-    private static native WasmType callNtv(long func_ptr, long instance_pointer, long store_ptr,
-            Class<? extends WasmType> returnType, WasmType... args)
+    private static native long createMultiFunc(long store_ptr, Method method, Object obj,
+            List<Class<? extends WasmType>> resultTypes,
+            List<Class<?>> paramTypes) throws WasmtimeException;
+
+    private static native long createDirectFunc(long store_ptr, Class<?> clazz, String name, String descriptor,
+            Object obj) throws WasmtimeException;
+
+    private static native Object callNtv(long func_ptr, long instance_pointer, long store_ptr,
+            Class<?> returnType, Object... args)
             throws WasmtimeException;
 
+    private static native WasmType[] callMultiNtv(long func_ptr, long instance_pointer, long store_ptr,
+            WasmType... args) throws WasmtimeException;
+
     public static WasmFunction newFunc(WasmStore store, Object target, String methodName,
             Class<? extends WasmType>... args)
             throws WasmtimeException, NoSuchMethodException {
@@ -30,30 +46,120 @@ public class WasmFunction extends AbstractOpaquePtr {
         return WasmFunction.newFunc(store, method, target);
     }
 
-    public static WasmFunction newFunc(WasmStore store, Method method, Object obj) throws WasmtimeException {
-        List<Class<? extends WasmType>> parameters = new ArrayList<>(5);
+    private static final List<Class<?>> BOXED_TYPES = List.of(Boolean.class, Byte.class, Character.class,
+            Short.class, Integer.class, Long.class, Float.class, Double.class);
+
+    private static final List<Class<?>> ARRAY_TYPES = List.of(byte[].class, int[].class, long[].class,
+            float[].class, double[].class);
+
+    /**
+     * WasmTypes, the Java primitives other than void along with their boxed
+     * types, arrays of byte, int, long, float and double, and ByteBuffers are
+     * supported as parameters and results.
+     */
+    private static boolean isSupportedType(Class<?> ty) {
+        return WasmType.class.isAssignableFrom(ty) || (ty.isPrimitive() && ty != Void.TYPE)
+                || BOXED_TYPES.contains(ty) || ARRAY_TYPES.contains(ty) || ty == ByteBuffer.class;
+    }
+
+    private static List<Class<?>> parameterTypes(Method method) {
+        List<Class<?>> parameters = new ArrayList<>(5);
         for (Parameter param : method.getParameters()) {
             Class<?> paramType = param.getType();
-            if (!WasmType.class.isAssignableFrom(paramType))
+            if (!isSupportedType(paramType))
                 throw new RuntimeException(
-                        String.format("Only WasmType parameters supported: %s", paramType.getName()));
-            Class<? extends WasmType> ty = (Class<WasmType>) paramType;
+                        String.format("Only WasmType and primitive parameters supported: %s", paramType.getName()));
+
+            parameters.add(paramType);
+        }
+
+        return parameters;
+    }
+
+    /**
+     * The JNI descriptor of the method, if the parameters and result are only
+     * primitives, or void for the result.
+     */
+    private static Optional<String> primitiveDescriptor(Method method) {
+        StringBuilder descriptor = new StringBuilder("(");
+        for (Class<?> paramType : method.getParameterTypes()) {
+            char ty = primitiveDescriptor(paramType);
+            if (ty == 0 || ty == 'V')
+                return Optional.empty();
+            descriptor.append(ty);
+        }
+
+        char ret = primitiveDescriptor(method.getReturnType());
+        if (ret == 0)
+            return Optional.empty();
+
+        return Optional.of(descriptor.append(')').append(ret).toString());
+    }
+
+    private static char primitiveDescriptor(Class<?> ty) {
+        if (ty == Boolean.TYPE)
+            return 'Z';
+        if (ty == Byte.TYPE)
+            return 'B';
+        if (ty == Character.TYPE)
+            return 'C';
+        if (ty == Short.TYPE)
+            return 'S';
+        if (ty == Integer.TYPE)
+            return 'I';
+        if (ty == Long.TYPE)
+            return 'J';
+        if (ty == Float.TYPE)
+            return 'F';
+        if (ty == Double.TYPE)
+            return 'D';
+        if (ty == Void.TYPE)
+            return 'V';
+        return 0;
+    }
 
-            parameters.add(ty);
+    /**
+     * Methods with only primitive parameters and results (or void) are called
+     * directly from WASM with the primitive values, otherwise the method is called
+     * through reflection. WasmType, primitive and boxed primitive
+     * parameters and results are supported, boolean, byte, char and short are
+     * passed as an i32.
+     * <p>
+     * Arrays of byte, int, long, float and double are passed as the offset and
+     * the number of elements of a copy of the array in the module's memory, the
+     * elements are little-endian. Arrays are returned by reference, through a
+     * pointer to the offset and number of elements passed as the last parameter.
+     * <p>
+     * A ByteBuffer parameter is a view of the module's memory, it is only valid
+     * during the call. A returned ByteBuffer is passed like a byte[] of the bytes
+     * from its position to its limit, the position is not changed.
+     *
+     * @param obj the object to call the method on, ignored for static methods
+     */
+    public static WasmFunction newFunc(WasmStore store, Method method, Object obj) throws WasmtimeException {
+        Optional<String> descriptor = primitiveDescriptor(method);
+        if (descriptor.isPresent()) {
+            Object target = Modifier.isStatic(method.getModifiers()) ? null
+                    : Objects.requireNonNull(obj, "obj is required for instance methods");
+            long ptr = createDirectFunc(store.getPtr(), method.getDeclaringClass(), method.getName(),
+                    descriptor.get(), target);
+            return new WasmFunction(ptr);
         }
 
+        List<Class<?>> parameters = parameterTypes(method);
+
         // validate that the type is something we support
-        final Class<? extends WasmType> returnType;
+        final Class<?> returnType;
         Class<?> javaReturnType = method.getReturnType();
-        if (WasmType.class.isAssignableFrom(javaReturnType)) {
-            returnType = (Class<WasmType>) javaReturnType;
-        } else if (Void.TYPE.isAssignableFrom(javaReturnType)
+        if (Void.TYPE.isAssignableFrom(javaReturnType)
                 || Void.class.isAssignableFrom(javaReturnType)) {
             // We'll allow standard void and Void as well.
             returnType = WasmVoid.class;
+        } else if (isSupportedType(javaReturnType)) {
+            returnType = javaReturnType;
         } else {
             throw new RuntimeException(
-                    String.format("Only WasmType return values supported: %s",
+                    String.format("Only WasmType and primitive return values supported: %s",
                             javaReturnType.getName()));
         }
 
@@ -61,6 +167,28 @@ public class WasmFunction extends AbstractOpaquePtr {
         return new WasmFunction(ptr);
     }
 
+    /**
+     * A function returning multiple values to WASM, the method must return a
+     * WasmType[] with the values in the order of the resultTypes.
+     *
+     * @param resultTypes the types of the values returned by the method, only
+     *                    WASM value types are supported, e.g. I32 or F64
+     */
+    @SafeVarargs
+    public static WasmFunction newMultiFunc(WasmStore store, Method method, Object obj,
+            Class<? extends WasmType>... resultTypes) throws WasmtimeException {
+        List<Class<?>> parameters = parameterTypes(method);
+
+        if (!WasmType[].class.isAssignableFrom(method.getReturnType())) {
+            throw new RuntimeException(
+                    String.format("Only WasmType[] return values supported for multiple results: %s",
+                            method.getReturnType().getName()));
+        }
+
+        long ptr = createMultiFunc(store.getPtr(), method, obj, Arrays.asList(resultTypes), parameters);
+        return new WasmFunction(ptr);
+    }
+
     /**
      * 
      * @param instance   the linked and compiled instance to call this function
@@ -80,6 +208,38 @@ public class WasmFunction extends AbstractOpaquePtr {
         return (T) callNtv(this.getPtr(), instance.getPtr(), store.getPtr(), returnType, args);
     }
 
+    /**
+     * Call the function with Java values, the args may be WasmTypes or boxed
+     * primitives, e.g. Integer, which are passed as their natural WASM type. A
+     * Boolean, Byte, Character or Short is passed as an i32. Arrays are copied
+     * into the module's memory, see
+     * {@link #newFunc(WasmStore, Method, Object)}. For a ByteBuffer, heap or
+     * direct, the bytes from its position to its limit are copied, the position
+     * is not changed. A returned ByteBuffer is a heap buffer with a copy of the
+     * bytes.
+     *
+     * @param returnType the class of the result, a WasmType, a primitive or boxed
+     *                   primitive class, an array, ByteBuffer, or void for no result
+     * @return the result, primitives are returned boxed, or null for no result
+     * @throws WasmtimeException If any exception is thrown by the underlying
+     *                           function
+     * @throws IllegalArgumentException If any of the args is null
+     */
+    public Object invoke(WasmInstance instance, WasmStore store, Class<?> returnType, Object... args)
+            throws WasmtimeException {
+        if (Void.TYPE.equals(returnType) || Void.class.equals(returnType))
+            returnType = WasmVoid.class;
+        if (!WasmVoid.class.equals(returnType) && !isSupportedType(returnType))
+            throw new IllegalArgumentException(
+                    String.format("Only WasmType and primitive results supported: %s", returnType.getName()));
+        for (int i = 0; i < args.length; i++) {
+            if (args[i] == null)
+                throw new IllegalArgumentException(String.format("null argument at index: %d", i));
+        }
+
+        return callNtv(this.getPtr(), instance.getPtr(), store.getPtr(), returnType, args);
+    }
+
     /**
      *
      * @param instance the linked and compiled instance to call this function agains
@@ -94,6 +254,39 @@ public class WasmFunction extends AbstractOpaquePtr {
         callNtv(this.getPtr(), instance.getPtr(), store.getPtr(), WasmVoid.class, args);
     }
 
+    /**
+     * Call a function returning any number of results, e.g. a function with
+     * multiple return values.
+     *
+     * @param instance the linked and compiled instance to call this function
+     *                 against
+     * @param args     list of arguments for the function
+     * @return all of the results of the function, in order, as WASM value types
+     * @throws WasmtimeException If any exception is thrown by the underlying
+     *                           function
+     */
+    public WasmType[] callMulti(WasmInstance instance, WasmStore store, WasmType... args) throws WasmtimeException {
+        return callMultiNtv(this.getPtr(), instance.getPtr(), store.getPtr(), args);
+    }
+
+    /**
+     * Call the function, interrupting it if it has not completed before the
+     * timeout. Epoch interruption must be enabled in the WasmConfig.
+     *
+     * @param timeout the maximum time the function is allowed to execute
+     * @throws WasmInterruptedException if the timeout was reached
+     * @see #call(WasmInstance, WasmStore, Class, WasmType...)
+     */
+    public <T extends WasmType> T callWithTimeout(WasmInstance instance, WasmStore store, Duration timeout,
+            Class<T> returnType, WasmType... args) throws WasmtimeException {
+        store.setDeadline(timeout);
+        try {
+            return this.call(instance, store, returnType, args);
+        } finally {
+            store.clearEpochDeadline();
+        }
+    }
+
     /**
      * WARNING: this is really only useful in tests, Instance will be null in the
      * native call, which is bad for any non-native types, like Strings arrays or
@@ -0,0 +1,41 @@
+package net.bluejekyll.wasmtime;
+
+import net.bluejekyll.wasmtime.ty.GlobalType;
+import net.bluejekyll.wasmtime.ty.WasmType;
+
+/**
+ * A global variable exported from a WASM instance.
+ * <p>
+ * Values are one of the WasmTypes I32, I64, F32 or F64 matching the type of
+ * the global.
+ */
+public class WasmGlobal extends AbstractOpaquePtr {
+    WasmGlobal(long ptr) {
+        super(ptr, WasmGlobal::freeGlobal);
+    }
+
+    private static native void freeGlobal(long ptr);
+
+    private static native GlobalType getTypeNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native WasmType getNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void setNtv(long ptr, long store_ptr, WasmType value) throws WasmtimeException;
+
+    /** @return the type of the value and the mutability of the global */
+    public GlobalType getType(WasmStore store) throws WasmtimeException {
+        return getTypeNtv(this.getPtr(), store.getPtr());
+    }
+
+    public WasmType get(WasmStore store) throws WasmtimeException {
+        return getNtv(this.getPtr(), store.getPtr());
+    }
+
+    /**
+     * Set the value of the global, this fails if the global is not mutable or the
+     * value is not of the global's type.
+     */
+    public void set(WasmStore store, WasmType value) throws WasmtimeException {
+        setNtv(this.getPtr(), store.getPtr(), value);
+    }
+}
@@ -11,6 +11,12 @@ public class WasmInstance extends AbstractOpaquePtr {
 
     private static native long getFunctionNtv(long ptr, long store_ptr, String name);
 
+    private static native long getMemoryNtv(long ptr, long store_ptr, String name);
+
+    private static native long getGlobalNtv(long ptr, long store_ptr, String name);
+
+    private static native long getTableNtv(long ptr, long store_ptr, String name);
+
     public Optional<WasmFunction> getFunction(WasmStore store, String name) {
         long func = WasmInstance.getFunctionNtv(this.getPtr(), store.getPtr(), name);
         if (func == 0) {
@@ -19,4 +25,31 @@ public class WasmInstance extends AbstractOpaquePtr {
             return Optional.of(new WasmFunction(func));
         }
     }
+
+    public Optional<WasmMemory> getMemory(WasmStore store, String name) {
+        long memory = WasmInstance.getMemoryNtv(this.getPtr(), store.getPtr(), name);
+        if (memory == 0) {
+            return Optional.empty();
+        } else {
+            return Optional.of(new WasmMemory(memory));
+        }
+    }
+
+    public Optional<WasmGlobal> getGlobal(WasmStore store, String name) {
+        long global = WasmInstance.getGlobalNtv(this.getPtr(), store.getPtr(), name);
+        if (global == 0) {
+            return Optional.empty();
+        } else {
+            return Optional.of(new WasmGlobal(global));
+        }
+    }
+
+    public Optional<WasmTable> getTable(WasmStore store, String name) {
+        long table = WasmInstance.getTableNtv(this.getPtr(), store.getPtr(), name);
+        if (table == 0) {
+            return Optional.empty();
+        } else {
+            return Optional.of(new WasmTable(table));
+        }
+    }
 }
@@ -0,0 +1,15 @@
+package net.bluejekyll.wasmtime;
+
+/**
+ * Thrown when WASM execution was interrupted, for example when the deadline
+ * set on the WasmStore was reached
+ */
+public class WasmInterruptedException extends WasmtimeException {
+    public WasmInterruptedException(String msg) {
+        super(msg);
+    }
+
+    public WasmInterruptedException(String msg, Throwable e) {
+        super(msg, e);
+    }
+}
@@ -4,6 +4,7 @@ import java.util.List;
 
 import net.bluejekyll.wasmtime.proxy.WasmExportable;
 import net.bluejekyll.wasmtime.proxy.WasmFunctionDef;
+import net.bluejekyll.wasmtime.ty.WasmType;
 
 public class WasmLinker extends AbstractOpaquePtr {
     WasmLinker(long ptr) {
@@ -14,6 +15,9 @@ public class WasmLinker extends AbstractOpaquePtr {
 
     private static native void defineFunc(long ptr, String module, String name, long func_ptr);
 
+    private static native void defineGlobalNtv(long ptr, long store_ptr, String module, String name, WasmType value,
+            boolean mutable) throws WasmtimeException;
+
     private static native long instantiateNtv(long linker_ptr, long store_ptr, long module_ptr)
             throws WasmtimeException;
 
@@ -34,6 +38,21 @@ public class WasmLinker extends AbstractOpaquePtr {
         }
     }
 
+    /**
+     * Define a global for modules to import, e.g. to pass configuration constants
+     * to the module. The global belongs to the store and may only be used by
+     * instances in that store.
+     *
+     * @param module  name of the module in which this global should be defined
+     * @param name    for the global to use
+     * @param value   initial value of the global, one of I32, I64, F32 or F64
+     * @param mutable if the global can be changed by the WASM module
+     */
+    public void defineGlobal(WasmStore store, String module, String name, WasmType value, boolean mutable)
+            throws WasmtimeException {
+        WasmLinker.defineGlobalNtv(this.getPtr(), store.getPtr(), module, name, value, mutable);
+    }
+
     public WasmInstance instantiate(WasmStore store, WasmModule module) throws WasmtimeException {
         return new WasmInstance(WasmLinker.instantiateNtv(this.getPtr(), store.getPtr(), module.getPtr()));
     }
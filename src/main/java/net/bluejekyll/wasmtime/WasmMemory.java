@@ -0,0 +1,122 @@
+package net.bluejekyll.wasmtime;
+
+import java.nio.ByteBuffer;
+
+/**
+ * A linear memory exported from a WASM instance.
+ * <p>
+ * All offsets are in bytes, reads and writes outside of the memory throw a
+ * WasmtimeException. Multi-byte values are little-endian, as in WASM.
+ */
+public class WasmMemory extends AbstractOpaquePtr {
+    /** The size of a WASM page in bytes */
+    public static final long PAGE_SIZE = 65536;
+
+    WasmMemory(long ptr) {
+        super(ptr, WasmMemory::freeMemory);
+    }
+
+    private static native void freeMemory(long ptr);
+
+    private static native long sizeNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native long dataSizeNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native long growNtv(long ptr, long store_ptr, long deltaPages) throws WasmtimeException;
+
+    private static native byte[] readNtv(long ptr, long store_ptr, long offset, int length) throws WasmtimeException;
+
+    private static native void writeNtv(long ptr, long store_ptr, long offset, byte[] bytes) throws WasmtimeException;
+
+    private static native ByteBuffer directViewNtv(long ptr, long store_ptr, long offset, int length)
+            throws WasmtimeException;
+
+    private static native int readI32Ntv(long ptr, long store_ptr, long offset) throws WasmtimeException;
+
+    private static native long readI64Ntv(long ptr, long store_ptr, long offset) throws WasmtimeException;
+
+    private static native float readF32Ntv(long ptr, long store_ptr, long offset) throws WasmtimeException;
+
+    private static native double readF64Ntv(long ptr, long store_ptr, long offset) throws WasmtimeException;
+
+    private static native void writeI32Ntv(long ptr, long store_ptr, long offset, int val) throws WasmtimeException;
+
+    private static native void writeI64Ntv(long ptr, long store_ptr, long offset, long val) throws WasmtimeException;
+
+    private static native void writeF32Ntv(long ptr, long store_ptr, long offset, float val) throws WasmtimeException;
+
+    private static native void writeF64Ntv(long ptr, long store_ptr, long offset, double val)
+            throws WasmtimeException;
+
+    /** @return the size of the memory in pages, see {@link #PAGE_SIZE} */
+    public long size(WasmStore store) throws WasmtimeException {
+        return sizeNtv(this.getPtr(), store.getPtr());
+    }
+
+    /** @return the size of the memory in bytes */
+    public long dataSize(WasmStore store) throws WasmtimeException {
+        return dataSizeNtv(this.getPtr(), store.getPtr());
+    }
+
+    /**
+     * Grow the memory, this may fail if it would exceed the maximum of the memory
+     * or the limits of the store.
+     *
+     * @return the previous size of the memory in pages
+     */
+    public long grow(WasmStore store, long deltaPages) throws WasmtimeException {
+        return growNtv(this.getPtr(), store.getPtr(), deltaPages);
+    }
+
+    /** @return a copy of the bytes in the range */
+    public byte[] read(WasmStore store, long offset, int length) throws WasmtimeException {
+        return readNtv(this.getPtr(), store.getPtr(), offset, length);
+    }
+
+    public void write(WasmStore store, long offset, byte[] bytes) throws WasmtimeException {
+        writeNtv(this.getPtr(), store.getPtr(), offset, bytes);
+    }
+
+    /**
+     * A view that aliases the memory without copying, see
+     * {@link WasmMemoryView} for when the view becomes invalid.
+     */
+    public WasmMemoryView directView(WasmStore store, long offset, int length) throws WasmtimeException {
+        ByteBuffer generation = store.getMemoryGeneration();
+        ByteBuffer buffer = directViewNtv(this.getPtr(), store.getPtr(), offset, length);
+
+        return new WasmMemoryView(store, buffer, generation);
+    }
+
+    public int readI32(WasmStore store, long offset) throws WasmtimeException {
+        return readI32Ntv(this.getPtr(), store.getPtr(), offset);
+    }
+
+    public long readI64(WasmStore store, long offset) throws WasmtimeException {
+        return readI64Ntv(this.getPtr(), store.getPtr(), offset);
+    }
+
+    public float readF32(WasmStore store, long offset) throws WasmtimeException {
+        return readF32Ntv(this.getPtr(), store.getPtr(), offset);
+    }
+
+    public double readF64(WasmStore store, long offset) throws WasmtimeException {
+        return readF64Ntv(this.getPtr(), store.getPtr(), offset);
+    }
+
+    public void writeI32(WasmStore store, long offset, int val) throws WasmtimeException {
+        writeI32Ntv(this.getPtr(), store.getPtr(), offset, val);
+    }
+
+    public void writeI64(WasmStore store, long offset, long val) throws WasmtimeException {
+        writeI64Ntv(this.getPtr(), store.getPtr(), offset, val);
+    }
+
+    public void writeF32(WasmStore store, long offset, float val) throws WasmtimeException {
+        writeF32Ntv(this.getPtr(), store.getPtr(), offset, val);
+    }
+
+    public void writeF64(WasmStore store, long offset, double val) throws WasmtimeException {
+        writeF64Ntv(this.getPtr(), store.getPtr(), offset, val);
+    }
+}
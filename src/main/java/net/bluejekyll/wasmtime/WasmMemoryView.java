@@ -0,0 +1,121 @@
+package net.bluejekyll.wasmtime;
+
+import javax.annotation.concurrent.NotThreadSafe;
+
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+
+/**
+ * A direct view of a range of WASM memory, reads and writes are not copied.
+ * <p>
+ * Growing any memory in the store, from WASM or Java, may move the memory. The
+ * view is then invalid and all access throws an IllegalStateException, a new
+ * view must be created. For the same reason the buffer returned by
+ * {@link #buffer()} must not be retained. Once the store is closed the view
+ * is invalid and all access throws an IllegalStateException.
+ * <p>
+ * Multi-byte values are little-endian, as in WASM.
+ */
+@NotThreadSafe
+public class WasmMemoryView {
+    // keeps the store reachable while the view is in use, the memory is freed
+    // with the store
+    private final WasmStore store;
+    private final ByteBuffer buffer;
+    private final ByteBuffer generation;
+    private final long viewGeneration;
+
+    WasmMemoryView(WasmStore store, ByteBuffer buffer, ByteBuffer generation) {
+        this.store = store;
+        this.buffer = buffer.order(ByteOrder.LITTLE_ENDIAN);
+        this.generation = generation;
+        this.viewGeneration = generation.getLong(0);
+    }
+
+    /**
+     * @return false if a memory in the store has grown since the view was
+     *         created, or the store is closed
+     */
+    public boolean isValid() {
+        // the generation is freed with the store, it must not be read after close
+        return !this.store.isClosed() && this.generation.getLong(0) == this.viewGeneration;
+    }
+
+    private ByteBuffer checked() {
+        if (this.store.isClosed()) {
+            throw new IllegalStateException("the store is closed, the view is invalid");
+        }
+        if (!this.isValid()) {
+            throw new IllegalStateException("memory has grown since the view was created, the view is stale");
+        }
+
+        return this.buffer;
+    }
+
+    /**
+     * The buffer aliasing the memory, it must not be used after any memory in the
+     * store grows. Prefer the accessors on the view, which are checked.
+     */
+    public ByteBuffer buffer() {
+        return this.checked();
+    }
+
+    /** @return the length of the view in bytes */
+    public int length() {
+        return this.buffer.capacity();
+    }
+
+    public byte getByte(int index) {
+        return this.checked().get(index);
+    }
+
+    public void putByte(int index, byte val) {
+        this.checked().put(index, val);
+    }
+
+    public int getInt(int index) {
+        return this.checked().getInt(index);
+    }
+
+    public void putInt(int index, int val) {
+        this.checked().putInt(index, val);
+    }
+
+    public long getLong(int index) {
+        return this.checked().getLong(index);
+    }
+
+    public void putLong(int index, long val) {
+        this.checked().putLong(index, val);
+    }
+
+    public float getFloat(int index) {
+        return this.checked().getFloat(index);
+    }
+
+    public void putFloat(int index, float val) {
+        this.checked().putFloat(index, val);
+    }
+
+    public double getDouble(int index) {
+        return this.checked().getDouble(index);
+    }
+
+    public void putDouble(int index, double val) {
+        this.checked().putDouble(index, val);
+    }
+
+    /** Copy bytes from the view into dst, starting at index */
+    public void get(int index, byte[] dst) {
+        ByteBuffer buffer = this.checked().duplicate();
+        buffer.position(index);
+        buffer.get(dst);
+    }
+
+    /** Copy all of src into the view, starting at index */
+    public void put(int index, byte[] src) {
+        ByteBuffer buffer = this.checked().duplicate();
+        buffer.position(index);
+        buffer.put(src);
+    }
+}
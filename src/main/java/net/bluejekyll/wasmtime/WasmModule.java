@@ -1,9 +1,42 @@
 package net.bluejekyll.wasmtime;
 
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+
+import net.bluejekyll.wasmtime.ty.ExportType;
+import net.bluejekyll.wasmtime.ty.ImportType;
+
 public class WasmModule extends AbstractOpaquePtr {
     WasmModule(long ptr) {
         super(ptr, WasmModule::freeModule);
     }
 
     private static native void freeModule(long ptr);
+
+    private static native byte[] serializeNtv(long ptr) throws WasmtimeException;
+
+    private static native ImportType[] getImportsNtv(long ptr) throws WasmtimeException;
+
+    private static native ExportType[] getExportsNtv(long ptr) throws WasmtimeException;
+
+    /** The items that must be provided to instantiate the module, in order */
+    public List<ImportType> getImports() throws WasmtimeException {
+        return Collections.unmodifiableList(Arrays.asList(getImportsNtv(this.getPtr())));
+    }
+
+    /** The items available from an instance of the module */
+    public List<ExportType> getExports() throws WasmtimeException {
+        return Collections.unmodifiableList(Arrays.asList(getExportsNtv(this.getPtr())));
+    }
+
+    /**
+     * The precompiled artifact for this module, which can be loaded without
+     * recompiling with {@link WasmEngine#deserializeModule(byte[])}. The artifact
+     * is only compatible with engines of the same Wasmtime version and
+     * configuration.
+     */
+    public byte[] serialize() throws WasmtimeException {
+        return serializeNtv(this.getPtr());
+    }
 }
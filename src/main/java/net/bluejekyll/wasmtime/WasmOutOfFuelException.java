@@ -0,0 +1,15 @@
+package net.bluejekyll.wasmtime;
+
+/**
+ * Thrown when WASM execution has consumed all of the fuel that was added to
+ * the WasmStore
+ */
+public class WasmOutOfFuelException extends WasmtimeException {
+    public WasmOutOfFuelException(String msg) {
+        super(msg);
+    }
+
+    public WasmOutOfFuelException(String msg, Throwable e) {
+        super(msg, e);
+    }
+}
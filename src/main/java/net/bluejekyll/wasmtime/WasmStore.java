@@ -1,11 +1,184 @@
 package net.bluejekyll.wasmtime;
 
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.time.Duration;
+import java.util.OptionalLong;
+
 public class WasmStore extends AbstractOpaquePtr {
     // Store is !Send and !Sync in Rust, we will enforce that with a ThreadLocal
 
+    private ByteBuffer memoryGeneration;
+    private boolean closed = false;
+
     WasmStore(long ptr) {
         super(ptr, WasmStore::freeStore);
     }
 
     private static native void freeStore(long ptr);
+
+    private static native void addFuelNtv(long ptr, long fuel) throws WasmtimeException;
+
+    private static native long fuelConsumedNtv(long ptr) throws WasmtimeException;
+
+    private static native long fuelRemainingNtv(long ptr) throws WasmtimeException;
+
+    private static native void setEpochDeadlineNtv(long ptr, long ticksBeyondCurrent) throws WasmtimeException;
+
+    private static native void setDeadlineNtv(long ptr, long timeoutMillis) throws WasmtimeException;
+
+    private static native void clearEpochDeadlineNtv(long ptr);
+
+    private static native void setMemorySizeLimitNtv(long ptr, long bytes) throws WasmtimeException;
+
+    private static native void setTableElementsLimitNtv(long ptr, long elements) throws WasmtimeException;
+
+    private static native void setInstancesLimitNtv(long ptr, long instances) throws WasmtimeException;
+
+    private static native void setMemoriesLimitNtv(long ptr, long memories) throws WasmtimeException;
+
+    private static native ByteBuffer memoryGenerationNtv(long ptr) throws WasmtimeException;
+
+    private static native byte[] readStdoutNtv(long ptr) throws WasmtimeException;
+
+    private static native byte[] readStderrNtv(long ptr) throws WasmtimeException;
+
+    /**
+     * Adds fuel to the store, fuel consumption must be enabled in the WasmConfig
+     * for the engine.
+     */
+    public void addFuel(long fuel) throws WasmtimeException {
+        addFuelNtv(this.getPtr(), fuel);
+    }
+
+    /**
+     * @return the fuel consumed by WASM execution so far, empty if fuel
+     *         consumption is not enabled
+     */
+    public OptionalLong fuelConsumed() throws WasmtimeException {
+        long consumed = fuelConsumedNtv(this.getPtr());
+        if (consumed < 0) {
+            return OptionalLong.empty();
+        } else {
+            return OptionalLong.of(consumed);
+        }
+    }
+
+    /**
+     * @return the fuel remaining in the store before WASM execution will trap,
+     *         empty if fuel consumption is not enabled
+     */
+    public OptionalLong fuelRemaining() throws WasmtimeException {
+        long remaining = fuelRemainingNtv(this.getPtr());
+        if (remaining < 0) {
+            return OptionalLong.empty();
+        } else {
+            return OptionalLong.of(remaining);
+        }
+    }
+
+    /**
+     * Interrupt WASM execution in this store after the number of epoch ticks has
+     * elapsed. The epoch is incremented by a background thread in the native
+     * library, epoch interruption must be enabled in the WasmConfig.
+     *
+     * If the deadline passes while no WASM is executing, the next call into WASM
+     * will be interrupted.
+     */
+    public void setEpochDeadline(long ticksBeyondCurrent) throws WasmtimeException {
+        setEpochDeadlineNtv(this.getPtr(), ticksBeyondCurrent);
+    }
+
+    /**
+     * Interrupt WASM execution in this store after the timeout, rounded up to the
+     * next epoch tick.
+     *
+     * @see #setEpochDeadline(long)
+     */
+    public void setDeadline(Duration timeout) throws WasmtimeException {
+        setDeadlineNtv(this.getPtr(), timeout.toMillis());
+    }
+
+    /**
+     * Removes any deadline that was set on this store. If the deadline was
+     * reached after WASM returned, the pending interrupt is discarded, it will
+     * not interrupt the next call.
+     */
+    public void clearEpochDeadline() {
+        clearEpochDeadlineNtv(this.getPtr());
+    }
+
+    /**
+     * Limit the size of each linear memory in this store, growing a memory beyond
+     * the limit fails in WASM, i.e. `memory.grow` returns -1. Instantiating a
+     * module whose initial memory is larger than the limit will fail.
+     */
+    public void limitMemorySize(long maxBytes) throws WasmtimeException {
+        setMemorySizeLimitNtv(this.getPtr(), maxBytes);
+    }
+
+    /**
+     * Limit the number of elements of each table in this store, growing a table
+     * beyond the limit fails in WASM.
+     */
+    public void limitTableElements(long maxElements) throws WasmtimeException {
+        setTableElementsLimitNtv(this.getPtr(), maxElements);
+    }
+
+    /** Limit the number of instances that may be created in this store */
+    public void limitInstances(long maxInstances) throws WasmtimeException {
+        setInstancesLimitNtv(this.getPtr(), maxInstances);
+    }
+
+    /** Limit the number of linear memories that may be created in this store */
+    public void limitMemories(long maxMemories) throws WasmtimeException {
+        setMemoriesLimitNtv(this.getPtr(), maxMemories);
+    }
+
+    /**
+     * Returns the stdout written by WASM since the last read, stdout must be
+     * captured with {@link WasiConfig#stdoutBuffer()}
+     */
+    public byte[] readStdout() throws WasmtimeException {
+        return readStdoutNtv(this.getPtr());
+    }
+
+    /**
+     * Returns the stderr written by WASM since the last read, stderr must be
+     * captured with {@link WasiConfig#stderrBuffer()}
+     */
+    public byte[] readStderr() throws WasmtimeException {
+        return readStderrNtv(this.getPtr());
+    }
+
+    /**
+     * The generation is incremented each time a memory in the store grows, which
+     * invalidates views of the memory. The buffer aliases the native counter.
+     */
+    ByteBuffer getMemoryGeneration() throws WasmtimeException {
+        if (this.closed) {
+            throw new WasmtimeException("the store is closed");
+        }
+
+        if (this.memoryGeneration == null) {
+            this.memoryGeneration = memoryGenerationNtv(this.getPtr()).order(ByteOrder.nativeOrder());
+        }
+
+        return this.memoryGeneration;
+    }
+
+    /**
+     * Views of the memory check this before any access, the memory and the
+     * generation are freed with the store.
+     */
+    boolean isClosed() {
+        return this.closed;
+    }
+
+    @Override
+    public void close() {
+        this.closed = true;
+        this.memoryGeneration = null;
+        super.close();
+    }
 }
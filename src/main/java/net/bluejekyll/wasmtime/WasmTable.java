@@ -0,0 +1,73 @@
+package net.bluejekyll.wasmtime;
+
+import net.bluejekyll.wasmtime.ty.FuncRef;
+import net.bluejekyll.wasmtime.ty.TableType;
+import net.bluejekyll.wasmtime.ty.WasmType;
+
+/**
+ * A table exported from a WASM instance, e.g. the table of functions used for
+ * indirect calls.
+ * <p>
+ * Elements are FuncRefs for funcref tables, accessing an index outside of the
+ * table throws a WasmtimeException.
+ */
+public class WasmTable extends AbstractOpaquePtr {
+    WasmTable(long ptr) {
+        super(ptr, WasmTable::freeTable);
+    }
+
+    private static native void freeTable(long ptr);
+
+    private static native TableType getTypeNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native long sizeNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native WasmType getNtv(long ptr, long store_ptr, long index) throws WasmtimeException;
+
+    private static native void setNtv(long ptr, long store_ptr, long index, WasmType value) throws WasmtimeException;
+
+    private static native long growNtv(long ptr, long store_ptr, long delta, WasmType init) throws WasmtimeException;
+
+    /** @return the element type and the limits of the table */
+    public TableType getType(WasmStore store) throws WasmtimeException {
+        return getTypeNtv(this.getPtr(), store.getPtr());
+    }
+
+    /** @return the number of elements in the table */
+    public long size(WasmStore store) throws WasmtimeException {
+        return sizeNtv(this.getPtr(), store.getPtr());
+    }
+
+    public WasmType get(WasmStore store, long index) throws WasmtimeException {
+        return getNtv(this.getPtr(), store.getPtr(), index);
+    }
+
+    /** @see #get(WasmStore, long) */
+    public FuncRef getFuncRef(WasmStore store, long index) throws WasmtimeException {
+        WasmType element = this.get(store, index);
+        if (!(element instanceof FuncRef)) {
+            throw new WasmtimeException(String.format("table element is not a funcref: %s", element));
+        }
+
+        return (FuncRef) element;
+    }
+
+    /**
+     * Replace the element at the index, e.g. to install a host function for
+     * indirect calls.
+     */
+    public void set(WasmStore store, long index, WasmType value) throws WasmtimeException {
+        setNtv(this.getPtr(), store.getPtr(), index, value);
+    }
+
+    /**
+     * Grow the table, this may fail if it would exceed the maximum of the table or
+     * the limits of the store.
+     *
+     * @param init the value for all of the new elements
+     * @return the previous size of the table
+     */
+    public long grow(WasmStore store, long delta, WasmType init) throws WasmtimeException {
+        return growNtv(this.getPtr(), store.getPtr(), delta, init);
+    }
+}
@@ -0,0 +1,206 @@
+package net.bluejekyll.wasmtime;
+
+import javax.annotation.concurrent.NotThreadSafe;
+
+/**
+ * Functions with a fixed signature, for calls in hot loops.
+ * <p>
+ * The type of the function is checked once when it is converted, calls then
+ * pass primitives directly to WASM, without the WasmType objects and the
+ * conversions of {@link WasmFunction#call(WasmInstance, WasmStore, Class,
+ * net.bluejekyll.wasmtime.ty.WasmType...)}.
+ *
+ * <pre>
+ * WasmTypedFunction.I32I32ToI32 add = WasmTypedFunction.I32I32ToI32.of(store, function);
+ * int sum = add.call(store, 1, 2);
+ * </pre>
+ */
+public final class WasmTypedFunction {
+    private WasmTypedFunction() {
+    }
+
+    private static native long typedVoidToVoidNtv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeVoidToVoidNtv(long ptr);
+
+    private static native void callVoidToVoidNtv(long ptr, long store_ptr) throws WasmtimeException;
+
+    private static native long typedI32ToVoidNtv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeI32ToVoidNtv(long ptr);
+
+    private static native void callI32ToVoidNtv(long ptr, long store_ptr, int a) throws WasmtimeException;
+
+    private static native long typedI32ToI32Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeI32ToI32Ntv(long ptr);
+
+    private static native int callI32ToI32Ntv(long ptr, long store_ptr, int a) throws WasmtimeException;
+
+    private static native long typedI32I32ToI32Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeI32I32ToI32Ntv(long ptr);
+
+    private static native int callI32I32ToI32Ntv(long ptr, long store_ptr, int a, int b) throws WasmtimeException;
+
+    private static native long typedI64ToI64Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeI64ToI64Ntv(long ptr);
+
+    private static native long callI64ToI64Ntv(long ptr, long store_ptr, long a) throws WasmtimeException;
+
+    private static native long typedI64I64ToI64Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeI64I64ToI64Ntv(long ptr);
+
+    private static native long callI64I64ToI64Ntv(long ptr, long store_ptr, long a, long b) throws WasmtimeException;
+
+    private static native long typedF64ToF64Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeF64ToF64Ntv(long ptr);
+
+    private static native double callF64ToF64Ntv(long ptr, long store_ptr, double a) throws WasmtimeException;
+
+    private static native long typedF64F64ToF64Ntv(long func_ptr, long store_ptr) throws WasmtimeException;
+
+    private static native void freeF64F64ToF64Ntv(long ptr);
+
+    private static native double callF64F64ToF64Ntv(long ptr, long store_ptr, double a, double b)
+            throws WasmtimeException;
+
+    /** {@code () -> ()} */
+    @NotThreadSafe
+    public static final class VoidToVoid extends AbstractOpaquePtr {
+        private VoidToVoid(long ptr) {
+            super(ptr, WasmTypedFunction::freeVoidToVoidNtv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code () -> ()} */
+        public static VoidToVoid of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new VoidToVoid(typedVoidToVoidNtv(function.getPtr(), store.getPtr()));
+        }
+
+        public void call(WasmStore store) throws WasmtimeException {
+            callVoidToVoidNtv(this.getPtr(), store.getPtr());
+        }
+    }
+
+    /** {@code (i32) -> ()} */
+    @NotThreadSafe
+    public static final class I32ToVoid extends AbstractOpaquePtr {
+        private I32ToVoid(long ptr) {
+            super(ptr, WasmTypedFunction::freeI32ToVoidNtv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (i32) -> ()} */
+        public static I32ToVoid of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new I32ToVoid(typedI32ToVoidNtv(function.getPtr(), store.getPtr()));
+        }
+
+        public void call(WasmStore store, int a) throws WasmtimeException {
+            callI32ToVoidNtv(this.getPtr(), store.getPtr(), a);
+        }
+    }
+
+    /** {@code (i32) -> i32} */
+    @NotThreadSafe
+    public static final class I32ToI32 extends AbstractOpaquePtr {
+        private I32ToI32(long ptr) {
+            super(ptr, WasmTypedFunction::freeI32ToI32Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (i32) -> i32} */
+        public static I32ToI32 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new I32ToI32(typedI32ToI32Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public int call(WasmStore store, int a) throws WasmtimeException {
+            return callI32ToI32Ntv(this.getPtr(), store.getPtr(), a);
+        }
+    }
+
+    /** {@code (i32, i32) -> i32} */
+    @NotThreadSafe
+    public static final class I32I32ToI32 extends AbstractOpaquePtr {
+        private I32I32ToI32(long ptr) {
+            super(ptr, WasmTypedFunction::freeI32I32ToI32Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (i32, i32) -> i32} */
+        public static I32I32ToI32 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new I32I32ToI32(typedI32I32ToI32Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public int call(WasmStore store, int a, int b) throws WasmtimeException {
+            return callI32I32ToI32Ntv(this.getPtr(), store.getPtr(), a, b);
+        }
+    }
+
+    /** {@code (i64) -> i64} */
+    @NotThreadSafe
+    public static final class I64ToI64 extends AbstractOpaquePtr {
+        private I64ToI64(long ptr) {
+            super(ptr, WasmTypedFunction::freeI64ToI64Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (i64) -> i64} */
+        public static I64ToI64 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new I64ToI64(typedI64ToI64Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public long call(WasmStore store, long a) throws WasmtimeException {
+            return callI64ToI64Ntv(this.getPtr(), store.getPtr(), a);
+        }
+    }
+
+    /** {@code (i64, i64) -> i64} */
+    @NotThreadSafe
+    public static final class I64I64ToI64 extends AbstractOpaquePtr {
+        private I64I64ToI64(long ptr) {
+            super(ptr, WasmTypedFunction::freeI64I64ToI64Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (i64, i64) -> i64} */
+        public static I64I64ToI64 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new I64I64ToI64(typedI64I64ToI64Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public long call(WasmStore store, long a, long b) throws WasmtimeException {
+            return callI64I64ToI64Ntv(this.getPtr(), store.getPtr(), a, b);
+        }
+    }
+
+    /** {@code (f64) -> f64} */
+    @NotThreadSafe
+    public static final class F64ToF64 extends AbstractOpaquePtr {
+        private F64ToF64(long ptr) {
+            super(ptr, WasmTypedFunction::freeF64ToF64Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (f64) -> f64} */
+        public static F64ToF64 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new F64ToF64(typedF64ToF64Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public double call(WasmStore store, double a) throws WasmtimeException {
+            return callF64ToF64Ntv(this.getPtr(), store.getPtr(), a);
+        }
+    }
+
+    /** {@code (f64, f64) -> f64} */
+    @NotThreadSafe
+    public static final class F64F64ToF64 extends AbstractOpaquePtr {
+        private F64F64ToF64(long ptr) {
+            super(ptr, WasmTypedFunction::freeF64F64ToF64Ntv);
+        }
+
+        /** @throws WasmtimeException if the function is not {@code (f64, f64) -> f64} */
+        public static F64F64ToF64 of(WasmStore store, WasmFunction function) throws WasmtimeException {
+            return new F64F64ToF64(typedF64F64ToF64Ntv(function.getPtr(), store.getPtr()));
+        }
+
+        public double call(WasmStore store, double a, double b) throws WasmtimeException {
+            return callF64F64ToF64Ntv(this.getPtr(), store.getPtr(), a, b);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+package net.bluejekyll.wasmtime;
+
+/**
+ * The reason a WASM binary is invalid, see {@link WasmEngine#validate(byte[])}
+ */
+public class WasmValidationError {
+    private final long offset;
+    private final String message;
+
+    WasmValidationError(long offset, String message) {
+        this.offset = offset;
+        this.message = message;
+    }
+
+    /** Byte offset in the binary of the error, -1 if unknown */
+    public long getOffset() {
+        return this.offset;
+    }
+
+    public String getMessage() {
+        return this.message;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("%s (at offset %d)", this.message, this.offset);
+    }
+}
@@ -99,7 +99,17 @@ public class Wasmtime {
 
     private static native long newWasmEngineNtv();
 
+    private static native long newWasmEngineWithConfigNtv(long config_ptr) throws WasmtimeException;
+
     public WasmEngine newWasmEngine() {
         return new WasmEngine(newWasmEngineNtv());
     }
+
+    /**
+     * @param config settings to use for the engine, the config is consumed by this
+     *               call, even if it fails, and can not be used afterward
+     */
+    public WasmEngine newWasmEngine(WasmConfig config) throws WasmtimeException {
+        return new WasmEngine(newWasmEngineWithConfigNtv(config.takePtr()));
+    }
 }
@@ -14,7 +14,6 @@ import net.bluejekyll.wasmtime.WasmFunction;
 import net.bluejekyll.wasmtime.WasmInstance;
 import net.bluejekyll.wasmtime.WasmStore;
 import net.bluejekyll.wasmtime.WasmtimeException;
-import net.bluejekyll.wasmtime.ty.WasmType;
 
 @NotThreadSafe
 public class WasmImportProxy {
@@ -38,8 +37,9 @@ public class WasmImportProxy {
             WasmFunction function = functions.get(method.getName());
 
             if (function != null) {
-                WasmType[] wasmArgs = (WasmType[]) args;
-                return function.call(this.instance, this.store, (Class<WasmType>) method.getReturnType(), wasmArgs);
+                // args is null for methods without parameters
+                Object[] wasmArgs = args != null ? args : new Object[0];
+                return function.invoke(this.instance, this.store, method.getReturnType(), wasmArgs);
             }
 
             // TODO: add this back with Java 1.16
@@ -0,0 +1,25 @@
+package net.bluejekyll.wasmtime.ty;
+
+/** An item a module makes available to the host once instantiated */
+public class ExportType {
+    private final String name;
+    private final ExternType type;
+
+    ExportType(String name, ExternType type) {
+        this.name = name;
+        this.type = type;
+    }
+
+    public String getName() {
+        return this.name;
+    }
+
+    public ExternType getType() {
+        return this.type;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("export %s: %s", this.name, this.type);
+    }
+}
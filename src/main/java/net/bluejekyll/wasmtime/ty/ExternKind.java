@@ -0,0 +1,9 @@
+package net.bluejekyll.wasmtime.ty;
+
+/** The kind of item imported or exported by a module */
+public enum ExternKind {
+    FUNC,
+    GLOBAL,
+    TABLE,
+    MEMORY,
+}
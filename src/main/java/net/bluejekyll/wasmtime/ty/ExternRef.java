@@ -1,15 +1,36 @@
 package net.bluejekyll.wasmtime.ty;
 
 /**
- * This is only a placeholder for now, to be implemented in the future
+ * An opaque reference to a Java object, the WASM module can hold and pass the
+ * reference around but can not look inside of it. The same object is given
+ * back when the reference is returned to Java. A null reference has no value.
  */
 public class ExternRef implements WasmType {
-    private ExternRef() {
-        throw new UnsupportedOperationException();
+    /** The null reference */
+    public static final ExternRef NULL = new ExternRef(null);
+
+    // this is read directly by the native bindings
+    private final Object value;
+
+    public ExternRef(Object value) {
+        this.value = value;
     }
 
     @Override
-    public Integer getField() {
-        throw new UnsupportedOperationException();
+    public Object getField() {
+        return this.value;
+    }
+
+    /**
+     * @param type expected class of the value
+     * @return the value, or null for a null reference
+     * @throws ClassCastException if the value is not of the type
+     */
+    public <T> T getValue(Class<T> type) {
+        return type.cast(this.value);
+    }
+
+    public boolean isNull() {
+        return this.value == null;
     }
 }
@@ -0,0 +1,19 @@
+package net.bluejekyll.wasmtime.ty;
+
+/** The type of an item imported or exported by a module */
+public abstract class ExternType {
+    private final ExternKind kind;
+
+    ExternType(ExternKind kind) {
+        this.kind = kind;
+    }
+
+    public ExternKind getKind() {
+        return this.kind;
+    }
+
+    /** ValType ordinals are passed from the native bindings */
+    static ValType valType(int ordinal) {
+        return ValType.values()[ordinal];
+    }
+}
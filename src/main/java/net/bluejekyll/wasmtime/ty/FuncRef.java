@@ -1,15 +1,38 @@
 package net.bluejekyll.wasmtime.ty;
 
+import java.util.Optional;
+
+import net.bluejekyll.wasmtime.WasmFunction;
+
 /**
- * This is only here as a placeholder, it's not usable yet
+ * A reference to a function, as stored in tables or passed to and from
+ * functions. A null reference has no function.
  */
 public class FuncRef implements WasmType {
-    private FuncRef() {
-        throw new UnsupportedOperationException();
+    /** The null reference */
+    public static final FuncRef NULL = new FuncRef(null);
+
+    // this is read directly by the native bindings
+    private final WasmFunction function;
+
+    /**
+     * @param function the function to reference, null for a null reference, the
+     *                 function must belong to the same store it is used with
+     */
+    public FuncRef(WasmFunction function) {
+        this.function = function;
     }
 
     @Override
-    public Integer getField() {
-        throw new UnsupportedOperationException();
+    public WasmFunction getField() {
+        return this.function;
+    }
+
+    public Optional<WasmFunction> getFunction() {
+        return Optional.ofNullable(this.function);
+    }
+
+    public boolean isNull() {
+        return this.function == null;
     }
 }
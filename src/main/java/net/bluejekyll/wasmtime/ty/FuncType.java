@@ -0,0 +1,35 @@
+package net.bluejekyll.wasmtime.ty;
+
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+import java.util.stream.Collectors;
+
+public class FuncType extends ExternType {
+    private final List<ValType> params;
+    private final List<ValType> results;
+
+    FuncType(int[] params, int[] results) {
+        super(ExternKind.FUNC);
+        this.params = toValTypes(params);
+        this.results = toValTypes(results);
+    }
+
+    private static List<ValType> toValTypes(int[] ordinals) {
+        return Collections
+                .unmodifiableList(Arrays.stream(ordinals).mapToObj(ExternType::valType).collect(Collectors.toList()));
+    }
+
+    public List<ValType> getParams() {
+        return this.params;
+    }
+
+    public List<ValType> getResults() {
+        return this.results;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("func%s -> %s", this.params, this.results);
+    }
+}
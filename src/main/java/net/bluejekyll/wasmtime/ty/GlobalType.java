@@ -0,0 +1,25 @@
+package net.bluejekyll.wasmtime.ty;
+
+public class GlobalType extends ExternType {
+    private final ValType content;
+    private final boolean mutable;
+
+    GlobalType(int content, boolean mutable) {
+        super(ExternKind.GLOBAL);
+        this.content = valType(content);
+        this.mutable = mutable;
+    }
+
+    public ValType getContent() {
+        return this.content;
+    }
+
+    public boolean isMutable() {
+        return this.mutable;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("global %s%s", this.mutable ? "mut " : "", this.content);
+    }
+}
@@ -0,0 +1,31 @@
+package net.bluejekyll.wasmtime.ty;
+
+/** An item a module requires to be instantiated */
+public class ImportType {
+    private final String module;
+    private final String name;
+    private final ExternType type;
+
+    ImportType(String module, String name, ExternType type) {
+        this.module = module;
+        this.name = name;
+        this.type = type;
+    }
+
+    public String getModule() {
+        return this.module;
+    }
+
+    public String getName() {
+        return this.name;
+    }
+
+    public ExternType getType() {
+        return this.type;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("import %s::%s: %s", this.module, this.name, this.type);
+    }
+}
@@ -0,0 +1,37 @@
+package net.bluejekyll.wasmtime.ty;
+
+import java.util.OptionalLong;
+
+public class MemoryType extends ExternType {
+    private final long minimum;
+    private final OptionalLong maximum;
+    private final boolean is64;
+
+    /** A negative maximum is unbounded */
+    MemoryType(long minimum, long maximum, boolean is64) {
+        super(ExternKind.MEMORY);
+        this.minimum = minimum;
+        this.maximum = maximum < 0 ? OptionalLong.empty() : OptionalLong.of(maximum);
+        this.is64 = is64;
+    }
+
+    /** The minimum number of 64KiB pages */
+    public long getMinimum() {
+        return this.minimum;
+    }
+
+    /** The maximum number of 64KiB pages, empty if unbounded */
+    public OptionalLong getMaximum() {
+        return this.maximum;
+    }
+
+    /** True if the memory is indexed with 64-bit addresses */
+    public boolean is64() {
+        return this.is64;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("memory%s [%d, %s]", this.is64 ? "64" : "", this.minimum, this.maximum);
+    }
+}
@@ -0,0 +1,36 @@
+package net.bluejekyll.wasmtime.ty;
+
+import java.util.OptionalLong;
+
+public class TableType extends ExternType {
+    private final ValType element;
+    private final long minimum;
+    private final OptionalLong maximum;
+
+    /** A negative maximum is unbounded */
+    TableType(int element, long minimum, long maximum) {
+        super(ExternKind.TABLE);
+        this.element = valType(element);
+        this.minimum = minimum;
+        this.maximum = maximum < 0 ? OptionalLong.empty() : OptionalLong.of(maximum);
+    }
+
+    public ValType getElement() {
+        return this.element;
+    }
+
+    /** The minimum number of elements */
+    public long getMinimum() {
+        return this.minimum;
+    }
+
+    /** The maximum number of elements, empty if unbounded */
+    public OptionalLong getMaximum() {
+        return this.maximum;
+    }
+
+    @Override
+    public String toString() {
+        return String.format("table %s [%d, %s]", this.element, this.minimum, this.maximum);
+    }
+}
@@ -0,0 +1,56 @@
+package net.bluejekyll.wasmtime.ty;
+
+/**
+ * An unsigned 32 bit integer, passed to and from WASM as an i32 with the same
+ * bits. The value is held in a long, so it is never negative.
+ */
+public class U32 implements WasmType {
+    /** The largest value of a U32 */
+    public static final long MAX_VALUE = 0xFFFF_FFFFL;
+
+    // this is read directly by the native bindings
+    public final long field;
+
+    /** @param field a value from 0 to {@link #MAX_VALUE} */
+    public U32(long field) {
+        if (field < 0 || field > MAX_VALUE) {
+            throw new IllegalArgumentException(String.format("value is out of range for u32: %d", field));
+        }
+
+        this.field = field;
+    }
+
+    /** @param bits the bits of an i32, e.g. -1 is {@link #MAX_VALUE} */
+    public static U32 fromBits(int bits) {
+        return new U32(Integer.toUnsignedLong(bits));
+    }
+
+    @Override
+    public Long getField() {
+        return this.field;
+    }
+
+    public long longValue() {
+        return this.field;
+    }
+
+    /** @return the bits of the value as an i32, as seen in WASM */
+    public int intValue() {
+        return (int) this.field;
+    }
+
+    @Override
+    public boolean equals(Object obj) {
+        return obj instanceof U32 && ((U32) obj).field == this.field;
+    }
+
+    @Override
+    public int hashCode() {
+        return Long.hashCode(this.field);
+    }
+
+    @Override
+    public String toString() {
+        return Long.toString(this.field);
+    }
+}
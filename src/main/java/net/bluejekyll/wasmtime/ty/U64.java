@@ -0,0 +1,60 @@
+package net.bluejekyll.wasmtime.ty;
+
+import java.math.BigInteger;
+
+/**
+ * An unsigned 64 bit integer, passed to and from WASM as an i64 with the same
+ * bits.
+ */
+public class U64 implements WasmType {
+    /** The largest value of a U64 */
+    public static final BigInteger MAX_VALUE = BigInteger.ONE.shiftLeft(64).subtract(BigInteger.ONE);
+
+    // this is read directly by the native bindings
+    /** The bits of the value, values above Long.MAX_VALUE are negative */
+    public final long field;
+
+    /** @param bits the bits of an i64, e.g. -1 is {@link #MAX_VALUE} */
+    public U64(long bits) {
+        this.field = bits;
+    }
+
+    /** @param field a value from 0 to {@link #MAX_VALUE} */
+    public U64(BigInteger field) {
+        if (field.signum() < 0 || field.bitLength() > 64) {
+            throw new IllegalArgumentException(String.format("value is out of range for u64: %s", field));
+        }
+
+        this.field = field.longValue();
+    }
+
+    @Override
+    public BigInteger getField() {
+        return this.bigIntegerValue();
+    }
+
+    public BigInteger bigIntegerValue() {
+        BigInteger value = BigInteger.valueOf(this.field & Long.MAX_VALUE);
+        return this.field < 0 ? value.setBit(63) : value;
+    }
+
+    /** @return the bits of the value as an i64, as seen in WASM */
+    public long longValue() {
+        return this.field;
+    }
+
+    @Override
+    public boolean equals(Object obj) {
+        return obj instanceof U64 && ((U64) obj).field == this.field;
+    }
+
+    @Override
+    public int hashCode() {
+        return Long.hashCode(this.field);
+    }
+
+    @Override
+    public String toString() {
+        return Long.toUnsignedString(this.field);
+    }
+}
@@ -1,17 +1,81 @@
 package net.bluejekyll.wasmtime.ty;
 
 import java.math.BigInteger;
+import java.util.Arrays;
 
+/**
+ * A 128 bit SIMD vector, stored as 16 bytes in little-endian order, as in WASM
+ * memory. The lanes of the vector are interpreted by the instructions using
+ * it, e.g. lane 0 of an i32x4 is bytes 0 through 3.
+ */
 public class V128 implements WasmType {
-    // TODO: maybe this should be an array of bytes...
-    public final BigInteger field;
+    /** The size of a V128 in bytes */
+    public static final int BYTES = 16;
 
+    // this is read directly by the native bindings
+    private final byte[] bytes;
+
+    /** @param bytes the 16 bytes of the vector in little-endian order */
+    public V128(byte[] bytes) {
+        if (bytes.length != BYTES) {
+            throw new IllegalArgumentException(
+                    String.format("V128 requires %d bytes, got: %d", BYTES, bytes.length));
+        }
+
+        this.bytes = bytes.clone();
+    }
+
+    /**
+     * @param field the value of the vector as a 128 bit integer, negative values
+     *              are stored in two's complement
+     */
     public V128(BigInteger field) {
-        this.field = field;
+        int maxBits = field.signum() < 0 ? 127 : 128;
+        if (field.bitLength() > maxBits) {
+            throw new IllegalArgumentException(String.format("value does not fit in 128 bits: %s", field));
+        }
+
+        // BigInteger is big-endian
+        byte[] bigEndian = field.toByteArray();
+        this.bytes = new byte[BYTES];
+        Arrays.fill(this.bytes, field.signum() < 0 ? (byte) 0xFF : 0);
+        for (int i = 0; i < BYTES && i < bigEndian.length; i++) {
+            this.bytes[i] = bigEndian[bigEndian.length - 1 - i];
+        }
+    }
+
+    /** @return a copy of the bytes in little-endian order */
+    @Override
+    public byte[] getField() {
+        return this.bytes.clone();
+    }
+
+    /** @return the vector as an unsigned 128 bit integer */
+    public BigInteger toBigInteger() {
+        byte[] bigEndian = new byte[BYTES];
+        for (int i = 0; i < BYTES; i++) {
+            bigEndian[i] = this.bytes[BYTES - 1 - i];
+        }
+
+        return new BigInteger(1, bigEndian);
+    }
+
+    @Override
+    public boolean equals(Object obj) {
+        if (!(obj instanceof V128)) {
+            return false;
+        }
+
+        return Arrays.equals(this.bytes, ((V128) obj).bytes);
+    }
+
+    @Override
+    public int hashCode() {
+        return Arrays.hashCode(this.bytes);
     }
 
     @Override
-    public BigInteger getField() {
-        return this.field;
+    public String toString() {
+        return String.format("v128 0x%032x", this.toBigInteger());
     }
 }
@@ -2,6 +2,8 @@ package net.bluejekyll.wasmtime.ty;
 
 import java.math.BigInteger;
 
+import net.bluejekyll.wasmtime.WasmFunction;
+
 public class WasmTypeUtil {
     public static I32 i32(int val) {
         return new I32(val);
@@ -11,6 +13,18 @@ public class WasmTypeUtil {
         return new I64(val);
     };
 
+    public static U32 u32(long val) {
+        return new U32(val);
+    };
+
+    public static U64 u64(long val) {
+        return new U64(val);
+    };
+
+    public static U64 u64(BigInteger val) {
+        return new U64(val);
+    };
+
     public static F32 f32(float val) {
         return new F32(val);
     };
@@ -23,6 +37,15 @@ public class WasmTypeUtil {
         return new V128(val);
     };
 
-    // FuncRef,
-    // ExternRef;
+    public static V128 v128(byte[] val) {
+        return new V128(val);
+    };
+
+    public static FuncRef funcRef(WasmFunction val) {
+        return new FuncRef(val);
+    };
+
+    public static ExternRef externRef(Object val) {
+        return new ExternRef(val);
+    };
 }
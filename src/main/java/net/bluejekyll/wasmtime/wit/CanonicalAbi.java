@@ -0,0 +1,716 @@
+package net.bluejekyll.wasmtime.wit;
+
+import static net.bluejekyll.wasmtime.ty.WasmTypeUtil.f32;
+import static net.bluejekyll.wasmtime.ty.WasmTypeUtil.f64;
+import static net.bluejekyll.wasmtime.ty.WasmTypeUtil.i32;
+import static net.bluejekyll.wasmtime.ty.WasmTypeUtil.i64;
+
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.charset.StandardCharsets;
+import java.util.ArrayList;
+import java.util.Iterator;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+import net.bluejekyll.wasmtime.WasmtimeException;
+import net.bluejekyll.wasmtime.ty.F32;
+import net.bluejekyll.wasmtime.ty.F64;
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.I64;
+import net.bluejekyll.wasmtime.ty.ValType;
+import net.bluejekyll.wasmtime.ty.WasmType;
+
+/**
+ * Lowers Java values to WASM values and lifts them back with the canonical ABI
+ * used by wit-bindgen.
+ * <p>
+ * Values are passed as flat lists of core WASM values, strings and lists are
+ * copied into buffers allocated in the module with
+ * {@code canonical_abi_realloc}. Buffers lifted from results are owned by the
+ * caller and are released with {@code canonical_abi_free} once the call is
+ * complete.
+ */
+final class CanonicalAbi {
+    /** Above this the params are passed in memory */
+    static final int MAX_FLAT_PARAMS = 16;
+    /** Above this the callee returns a pointer to the results in memory */
+    static final int MAX_FLAT_RESULTS = 1;
+
+    /** The memory and allocator of the module */
+    interface Memory {
+        int alloc(int size, int align) throws WasmtimeException;
+
+        void free(int ptr, int size, int align) throws WasmtimeException;
+
+        byte[] read(int ptr, int length) throws WasmtimeException;
+
+        void write(int ptr, byte[] bytes) throws WasmtimeException;
+    }
+
+    private final Memory memory;
+    /** ptr, size and align of each buffer lifted from results */
+    private final List<int[]> lifted = new ArrayList<>();
+    /** ptr, size and align of each buffer lowered for params not yet passed */
+    private final List<int[]> lowered = new ArrayList<>();
+
+    CanonicalAbi(Memory memory) {
+        this.memory = memory;
+    }
+
+    static int alignTo(int offset, int align) {
+        return (offset + align - 1) / align * align;
+    }
+
+    static int align(WitType type) {
+        switch (type.getKind()) {
+            case BOOL:
+            case S8:
+            case U8:
+                return 1;
+            case S16:
+            case U16:
+                return 2;
+            case S32:
+            case U32:
+            case F32:
+            case CHAR:
+            case STRING:
+            case LIST:
+                return 4;
+            case S64:
+            case U64:
+            case F64:
+                return 8;
+            case RECORD:
+                int align = 1;
+                for (WitType.Field field : type.getFields()) {
+                    align = Math.max(align, align(field.getType()));
+                }
+                return align;
+            case VARIANT:
+                return Math.max(discriminantSize(type), maxCaseAlign(type));
+            default:
+                throw new IllegalStateException("unresolved type: " + type);
+        }
+    }
+
+    static int size(WitType type) {
+        switch (type.getKind()) {
+            case STRING:
+            case LIST:
+                return 8;
+            case RECORD:
+                int offset = 0;
+                for (WitType.Field field : type.getFields()) {
+                    offset = alignTo(offset, align(field.getType())) + size(field.getType());
+                }
+                return alignTo(offset, align(type));
+            case VARIANT:
+                int caseSize = 0;
+                for (WitType.Case c : type.getCases()) {
+                    caseSize = Math.max(caseSize, c.getType().map(CanonicalAbi::size).orElse(0));
+                }
+                return alignTo(payloadOffset(type) + caseSize, align(type));
+            default:
+                return align(type);
+        }
+    }
+
+    private static int discriminantSize(WitType variant) {
+        int cases = variant.getCases().size();
+        if (cases <= 1 << 8)
+            return 1;
+        if (cases <= 1 << 16)
+            return 2;
+        return 4;
+    }
+
+    private static int maxCaseAlign(WitType variant) {
+        int align = 1;
+        for (WitType.Case c : variant.getCases()) {
+            align = Math.max(align, c.getType().map(CanonicalAbi::align).orElse(1));
+        }
+        return align;
+    }
+
+    private static int payloadOffset(WitType variant) {
+        return alignTo(discriminantSize(variant), maxCaseAlign(variant));
+    }
+
+    static List<ValType> flatten(WitType type) {
+        List<ValType> flat = new ArrayList<>();
+        flatten(type, flat);
+        return flat;
+    }
+
+    private static void flatten(WitType type, List<ValType> flat) {
+        switch (type.getKind()) {
+            case S64:
+            case U64:
+                flat.add(ValType.I64);
+                break;
+            case F32:
+                flat.add(ValType.F32);
+                break;
+            case F64:
+                flat.add(ValType.F64);
+                break;
+            case STRING:
+            case LIST:
+                flat.add(ValType.I32);
+                flat.add(ValType.I32);
+                break;
+            case RECORD:
+                for (WitType.Field field : type.getFields()) {
+                    flatten(field.getType(), flat);
+                }
+                break;
+            case VARIANT:
+                flat.add(ValType.I32);
+                flat.addAll(flattenPayload(type));
+                break;
+            default:
+                flat.add(ValType.I32);
+        }
+    }
+
+    /** Each case shares the same values, the type at each position is wide enough for all of the cases */
+    private static List<ValType> flattenPayload(WitType variant) {
+        List<ValType> joined = new ArrayList<>();
+        for (WitType.Case c : variant.getCases()) {
+            if (!c.getType().isPresent())
+                continue;
+
+            List<ValType> flat = flatten(c.getType().get());
+            for (int i = 0; i < flat.size(); i++) {
+                if (i < joined.size()) {
+                    joined.set(i, join(joined.get(i), flat.get(i)));
+                } else {
+                    joined.add(flat.get(i));
+                }
+            }
+        }
+        return joined;
+    }
+
+    private static ValType join(ValType a, ValType b) {
+        if (a == b)
+            return a;
+        if ((a == ValType.I32 && b == ValType.F32) || (a == ValType.F32 && b == ValType.I32))
+            return ValType.I32;
+        return ValType.I64;
+    }
+
+    /** Buffers lifted since the last release are freed in the module */
+    void releaseLifted() throws WasmtimeException {
+        this.release(this.lifted);
+    }
+
+    /**
+     * The module owns the lowered buffers once they are passed to it, it frees
+     * them
+     */
+    void passLowered() {
+        this.lowered.clear();
+    }
+
+    /** Buffers lowered for params that were never passed are freed in the module */
+    void releaseLowered() throws WasmtimeException {
+        this.release(this.lowered);
+    }
+
+    private void release(List<int[]> buffers) throws WasmtimeException {
+        try {
+            for (int[] buffer : buffers) {
+                if (buffer[1] > 0)
+                    this.memory.free(buffer[0], buffer[1], buffer[2]);
+            }
+        } finally {
+            buffers.clear();
+        }
+    }
+
+    private int allocLowered(int size, int align) throws WasmtimeException {
+        int ptr = this.memory.alloc(size, align);
+        this.lowered.add(new int[] { ptr, size, align });
+        return ptr;
+    }
+
+    void lowerFlat(WitType type, Object value, List<WasmType> flat) throws WasmtimeException {
+        switch (type.getKind()) {
+            case BOOL:
+                flat.add(i32(asBoolean(type, value) ? 1 : 0));
+                break;
+            case S8:
+            case U8:
+            case S16:
+            case U16:
+            case S32:
+            case U32:
+            case CHAR:
+                flat.add(i32((int) asInteger(type, value)));
+                break;
+            case S64:
+            case U64:
+                flat.add(i64(asInteger(type, value)));
+                break;
+            case F32:
+                flat.add(f32(asNumber(type, value).floatValue()));
+                break;
+            case F64:
+                flat.add(f64(asNumber(type, value).doubleValue()));
+                break;
+            case STRING:
+            case LIST:
+                int[] buffer = this.lowerBuffer(type, value);
+                flat.add(i32(buffer[0]));
+                flat.add(i32(buffer[1]));
+                break;
+            case RECORD:
+                Map<?, ?> record = asRecord(type, value);
+                for (WitType.Field field : type.getFields()) {
+                    this.lowerFlat(field.getType(), record.get(field.getName()), flat);
+                }
+                break;
+            case VARIANT:
+                WitVariant variant = asVariant(type, value);
+                int index = caseIndex(type, variant);
+                flat.add(i32(index));
+
+                List<ValType> joined = flattenPayload(type);
+                List<WasmType> payload = new ArrayList<>();
+                WitType.Case c = type.getCases().get(index);
+                if (c.getType().isPresent()) {
+                    this.lowerFlat(c.getType().get(), caseValue(type, variant), payload);
+                }
+
+                for (int i = 0; i < joined.size(); i++) {
+                    flat.add(i < payload.size() ? widen(payload.get(i), joined.get(i)) : zero(joined.get(i)));
+                }
+                break;
+            default:
+                throw new IllegalStateException("unresolved type: " + type);
+        }
+    }
+
+    Object liftFlat(WitType type, Iterator<WasmType> flat) throws WasmtimeException {
+        switch (type.getKind()) {
+            case BOOL:
+                return nextI32(flat) != 0;
+            case S8:
+                return (int) (byte) nextI32(flat);
+            case U8:
+                return nextI32(flat) & 0xFF;
+            case S16:
+                return (int) (short) nextI32(flat);
+            case U16:
+                return nextI32(flat) & 0xFFFF;
+            case S32:
+            case CHAR:
+                return nextI32(flat);
+            case U32:
+                return Integer.toUnsignedLong(nextI32(flat));
+            case S64:
+            case U64:
+                return ((I64) next(flat)).field;
+            case F32:
+                return ((F32) next(flat)).field;
+            case F64:
+                return ((F64) next(flat)).field;
+            case STRING:
+            case LIST:
+                return this.liftBuffer(type, nextI32(flat), nextI32(flat));
+            case RECORD:
+                Map<String, Object> record = new LinkedHashMap<>();
+                for (WitType.Field field : type.getFields()) {
+                    record.put(field.getName(), this.liftFlat(field.getType(), flat));
+                }
+                return record;
+            case VARIANT:
+                int index = nextI32(flat);
+                List<ValType> joined = flattenPayload(type);
+                List<WasmType> payload = new ArrayList<>(joined.size());
+                for (int i = 0; i < joined.size(); i++) {
+                    payload.add(next(flat));
+                }
+
+                WitType.Case c = caseAt(type, index);
+                if (!c.getType().isPresent())
+                    return new WitVariant(c.getName());
+
+                WitType caseType = c.getType().get();
+                List<ValType> caseFlat = flatten(caseType);
+                List<WasmType> narrowed = new ArrayList<>(caseFlat.size());
+                for (int i = 0; i < caseFlat.size(); i++) {
+                    narrowed.add(narrow(payload.get(i), caseFlat.get(i)));
+                }
+                return new WitVariant(c.getName(), this.liftFlat(caseType, narrowed.iterator()));
+            default:
+                throw new IllegalStateException("unresolved type: " + type);
+        }
+    }
+
+    /** Allocates memory in the module for the value and stores it */
+    int lowerToMemory(WitType type, Object value) throws WasmtimeException {
+        ByteBuffer buf = newBuffer(size(type));
+        this.store(type, value, buf, 0);
+
+        int ptr = this.allocLowered(buf.capacity(), align(type));
+        this.memory.write(ptr, buf.array());
+        return ptr;
+    }
+
+    Object liftFromMemory(WitType type, int ptr) throws WasmtimeException {
+        ByteBuffer buf = wrap(this.memory.read(ptr, size(type)));
+        return this.load(type, buf, 0);
+    }
+
+    private void store(WitType type, Object value, ByteBuffer buf, int offset) throws WasmtimeException {
+        switch (type.getKind()) {
+            case BOOL:
+                buf.put(offset, (byte) (asBoolean(type, value) ? 1 : 0));
+                break;
+            case S8:
+            case U8:
+                buf.put(offset, (byte) asInteger(type, value));
+                break;
+            case S16:
+            case U16:
+                buf.putShort(offset, (short) asInteger(type, value));
+                break;
+            case S32:
+            case U32:
+            case CHAR:
+                buf.putInt(offset, (int) asInteger(type, value));
+                break;
+            case S64:
+            case U64:
+                buf.putLong(offset, asInteger(type, value));
+                break;
+            case F32:
+                buf.putFloat(offset, asNumber(type, value).floatValue());
+                break;
+            case F64:
+                buf.putDouble(offset, asNumber(type, value).doubleValue());
+                break;
+            case STRING:
+            case LIST:
+                int[] buffer = this.lowerBuffer(type, value);
+                buf.putInt(offset, buffer[0]);
+                buf.putInt(offset + 4, buffer[1]);
+                break;
+            case RECORD:
+                Map<?, ?> record = asRecord(type, value);
+                int fieldOffset = 0;
+                for (WitType.Field field : type.getFields()) {
+                    fieldOffset = alignTo(fieldOffset, align(field.getType()));
+                    this.store(field.getType(), record.get(field.getName()), buf, offset + fieldOffset);
+                    fieldOffset += size(field.getType());
+                }
+                break;
+            case VARIANT:
+                WitVariant variant = asVariant(type, value);
+                int index = caseIndex(type, variant);
+                storeDiscriminant(type, index, buf, offset);
+
+                WitType.Case c = type.getCases().get(index);
+                if (c.getType().isPresent()) {
+                    this.store(c.getType().get(), caseValue(type, variant), buf, offset + payloadOffset(type));
+                }
+                break;
+            default:
+                throw new IllegalStateException("unresolved type: " + type);
+        }
+    }
+
+    private Object load(WitType type, ByteBuffer buf, int offset) throws WasmtimeException {
+        switch (type.getKind()) {
+            case BOOL:
+                return buf.get(offset) != 0;
+            case S8:
+                return (int) buf.get(offset);
+            case U8:
+                return buf.get(offset) & 0xFF;
+            case S16:
+                return (int) buf.getShort(offset);
+            case U16:
+                return buf.getShort(offset) & 0xFFFF;
+            case S32:
+            case CHAR:
+                return buf.getInt(offset);
+            case U32:
+                return Integer.toUnsignedLong(buf.getInt(offset));
+            case S64:
+            case U64:
+                return buf.getLong(offset);
+            case F32:
+                return buf.getFloat(offset);
+            case F64:
+                return buf.getDouble(offset);
+            case STRING:
+            case LIST:
+                return this.liftBuffer(type, buf.getInt(offset), buf.getInt(offset + 4));
+            case RECORD:
+                Map<String, Object> record = new LinkedHashMap<>();
+                int fieldOffset = 0;
+                for (WitType.Field field : type.getFields()) {
+                    fieldOffset = alignTo(fieldOffset, align(field.getType()));
+                    record.put(field.getName(), this.load(field.getType(), buf, offset + fieldOffset));
+                    fieldOffset += size(field.getType());
+                }
+                return record;
+            case VARIANT:
+                WitType.Case c = caseAt(type, loadDiscriminant(type, buf, offset));
+                if (!c.getType().isPresent())
+                    return new WitVariant(c.getName());
+                return new WitVariant(c.getName(), this.load(c.getType().get(), buf, offset + payloadOffset(type)));
+            default:
+                throw new IllegalStateException("unresolved type: " + type);
+        }
+    }
+
+    /** @return the ptr and len of a string or list copied into the module */
+    private int[] lowerBuffer(WitType type, Object value) throws WasmtimeException {
+        if (type.getKind() == WitType.Kind.STRING) {
+            if (!(value instanceof String))
+                throw mismatch(type, value);
+
+            byte[] bytes = ((String) value).getBytes(StandardCharsets.UTF_8);
+            int ptr = this.allocLowered(bytes.length, 1);
+            this.memory.write(ptr, bytes);
+            return new int[] { ptr, bytes.length };
+        }
+
+        if (!(value instanceof List))
+            throw mismatch(type, value);
+
+        List<?> list = (List<?>) value;
+        WitType element = type.getElement().get();
+        int elementSize = size(element);
+
+        ByteBuffer buf = newBuffer(Math.multiplyExact(list.size(), elementSize));
+        for (int i = 0; i < list.size(); i++) {
+            this.store(element, list.get(i), buf, i * elementSize);
+        }
+
+        int ptr = this.allocLowered(buf.capacity(), align(element));
+        this.memory.write(ptr, buf.array());
+        return new int[] { ptr, list.size() };
+    }
+
+    private Object liftBuffer(WitType type, int ptr, int len) throws WasmtimeException {
+        if (type.getKind() == WitType.Kind.STRING) {
+            this.lifted.add(new int[] { ptr, len, 1 });
+            return new String(this.memory.read(ptr, len), StandardCharsets.UTF_8);
+        }
+
+        WitType element = type.getElement().get();
+        int elementSize = size(element);
+        int size = Math.multiplyExact(len, elementSize);
+        this.lifted.add(new int[] { ptr, size, align(element) });
+
+        ByteBuffer buf = wrap(this.memory.read(ptr, size));
+        List<Object> list = new ArrayList<>(len);
+        for (int i = 0; i < len; i++) {
+            list.add(this.load(element, buf, i * elementSize));
+        }
+        return list;
+    }
+
+    private static void storeDiscriminant(WitType variant, int index, ByteBuffer buf, int offset) {
+        switch (discriminantSize(variant)) {
+            case 1:
+                buf.put(offset, (byte) index);
+                break;
+            case 2:
+                buf.putShort(offset, (short) index);
+                break;
+            default:
+                buf.putInt(offset, index);
+        }
+    }
+
+    private static int loadDiscriminant(WitType variant, ByteBuffer buf, int offset) {
+        switch (discriminantSize(variant)) {
+            case 1:
+                return buf.get(offset) & 0xFF;
+            case 2:
+                return buf.getShort(offset) & 0xFFFF;
+            default:
+                return buf.getInt(offset);
+        }
+    }
+
+    private static ByteBuffer newBuffer(int size) {
+        return ByteBuffer.allocate(size).order(ByteOrder.LITTLE_ENDIAN);
+    }
+
+    private static ByteBuffer wrap(byte[] bytes) {
+        return ByteBuffer.wrap(bytes).order(ByteOrder.LITTLE_ENDIAN);
+    }
+
+    private static WasmType next(Iterator<WasmType> flat) throws WasmtimeException {
+        if (!flat.hasNext())
+            throw new WasmtimeException("fewer values than expected from WASM");
+        return flat.next();
+    }
+
+    private static int nextI32(Iterator<WasmType> flat) throws WasmtimeException {
+        WasmType value = next(flat);
+        if (!(value instanceof I32))
+            throw new WasmtimeException(String.format("expected i32 from WASM, found: %s", value));
+        return ((I32) value).field;
+    }
+
+    /** Reinterprets the value as the wider type shared by the cases of a variant */
+    private static WasmType widen(WasmType value, ValType to) {
+        if (to == ValType.I32 && value instanceof F32)
+            return i32(Float.floatToRawIntBits(((F32) value).field));
+        if (to != ValType.I64)
+            return value;
+
+        if (value instanceof I32)
+            return i64(Integer.toUnsignedLong(((I32) value).field));
+        if (value instanceof F32)
+            return i64(Integer.toUnsignedLong(Float.floatToRawIntBits(((F32) value).field)));
+        if (value instanceof F64)
+            return i64(Double.doubleToRawLongBits(((F64) value).field));
+        return value;
+    }
+
+    /** Reverses {@link #widen(WasmType, ValType)} */
+    private static WasmType narrow(WasmType value, ValType to) {
+        if (value instanceof I32 && to == ValType.F32)
+            return f32(Float.intBitsToFloat(((I32) value).field));
+        if (!(value instanceof I64))
+            return value;
+
+        long bits = ((I64) value).field;
+        switch (to) {
+            case I32:
+                return i32((int) bits);
+            case F32:
+                return f32(Float.intBitsToFloat((int) bits));
+            case F64:
+                return f64(Double.longBitsToDouble(bits));
+            default:
+                return value;
+        }
+    }
+
+    private static WasmType zero(ValType type) {
+        switch (type) {
+            case I64:
+                return i64(0);
+            case F32:
+                return f32(0);
+            case F64:
+                return f64(0);
+            default:
+                return i32(0);
+        }
+    }
+
+    private static WitType.Case caseAt(WitType variant, int index) throws WasmtimeException {
+        if (index < 0 || index >= variant.getCases().size())
+            throw new WasmtimeException(String.format("invalid case %d for variant %s", index, variant));
+        return variant.getCases().get(index);
+    }
+
+    private static int caseIndex(WitType variant, WitVariant value) {
+        List<WitType.Case> cases = variant.getCases();
+        for (int i = 0; i < cases.size(); i++) {
+            if (cases.get(i).getName().equals(value.getCase()))
+                return i;
+        }
+        throw new IllegalArgumentException(String.format("no case %s in variant %s", value.getCase(), variant));
+    }
+
+    private static Object caseValue(WitType variant, WitVariant value) {
+        return value.getValue().orElseThrow(() -> new IllegalArgumentException(
+                String.format("case %s of variant %s requires a value", value.getCase(), variant)));
+    }
+
+    private static IllegalArgumentException mismatch(WitType type, Object value) {
+        String found = value == null ? "null" : value.getClass().getName();
+        return new IllegalArgumentException(String.format("expected a value for %s, found: %s", type, found));
+    }
+
+    private static boolean asBoolean(WitType type, Object value) {
+        if (!(value instanceof Boolean))
+            throw mismatch(type, value);
+        return (Boolean) value;
+    }
+
+    private static Number asNumber(WitType type, Object value) {
+        if (!(value instanceof Number))
+            throw mismatch(type, value);
+        return (Number) value;
+    }
+
+    /** Integers must be in the range of the type, u64 is the bits of the long */
+    private static long asInteger(WitType type, Object value) {
+        if (type.getKind() == WitType.Kind.CHAR && value instanceof Character)
+            return (Character) value;
+
+        long v = asNumber(type, value).longValue();
+        long min;
+        long max;
+        switch (type.getKind()) {
+            case S8:
+                min = Byte.MIN_VALUE;
+                max = Byte.MAX_VALUE;
+                break;
+            case U8:
+                min = 0;
+                max = 0xFF;
+                break;
+            case S16:
+                min = Short.MIN_VALUE;
+                max = Short.MAX_VALUE;
+                break;
+            case U16:
+                min = 0;
+                max = 0xFFFF;
+                break;
+            case S32:
+                min = Integer.MIN_VALUE;
+                max = Integer.MAX_VALUE;
+                break;
+            case U32:
+                min = 0;
+                max = 0xFFFF_FFFFL;
+                break;
+            case CHAR:
+                min = 0;
+                max = Character.MAX_CODE_POINT;
+                break;
+            default:
+                return v;
+        }
+
+        if (v < min || v > max)
+            throw new IllegalArgumentException(String.format("%d is out of range for %s", v, type));
+        return v;
+    }
+
+    private static Map<?, ?> asRecord(WitType type, Object value) {
+        if (!(value instanceof Map))
+            throw mismatch(type, value);
+
+        Map<?, ?> record = (Map<?, ?>) value;
+        for (WitType.Field field : type.getFields()) {
+            if (!record.containsKey(field.getName()))
+                throw new IllegalArgumentException(String.format("missing field %s of %s", field.getName(), type));
+        }
+        return record;
+    }
+
+    private static WitVariant asVariant(WitType type, Object value) {
+        if (!(value instanceof WitVariant))
+            throw mismatch(type, value);
+        return (WitVariant) value;
+    }
+}
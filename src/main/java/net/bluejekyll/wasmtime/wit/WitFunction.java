@@ -0,0 +1,39 @@
+package net.bluejekyll.wasmtime.wit;
+
+import java.util.List;
+import java.util.Objects;
+import java.util.Optional;
+import java.util.stream.Collectors;
+
+/** A function from a WIT interface, exported by the WASM module with the same name */
+public final class WitFunction {
+    private final String name;
+    private final List<WitType.Field> params;
+    private final WitType result;
+
+    public WitFunction(String name, List<WitType.Field> params, WitType result) {
+        this.name = Objects.requireNonNull(name);
+        this.params = List.copyOf(params);
+        this.result = result;
+    }
+
+    public String getName() {
+        return this.name;
+    }
+
+    public List<WitType.Field> getParams() {
+        return this.params;
+    }
+
+    /** @return the type of the result, empty if the function has no result */
+    public Optional<WitType> getResult() {
+        return Optional.ofNullable(this.result);
+    }
+
+    @Override
+    public String toString() {
+        String params = this.params.stream().map(WitType.Field::toString).collect(Collectors.joining(", "));
+        String result = this.result == null ? "" : " -> " + this.result;
+        return String.format("%s: function(%s)%s", this.name, params, result);
+    }
+}
@@ -0,0 +1,191 @@
+package net.bluejekyll.wasmtime.wit;
+
+import static net.bluejekyll.wasmtime.ty.WasmTypeUtil.i32;
+
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.HashMap;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+import javax.annotation.concurrent.NotThreadSafe;
+
+import net.bluejekyll.wasmtime.WasmFunction;
+import net.bluejekyll.wasmtime.WasmInstance;
+import net.bluejekyll.wasmtime.WasmMemory;
+import net.bluejekyll.wasmtime.WasmStore;
+import net.bluejekyll.wasmtime.WasmtimeException;
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.WasmType;
+
+/**
+ * Calls the functions of a WIT interface exported by a WASM instance, e.g. a
+ * module built with {@code wit_bindgen_rust::export!}.
+ * <p>
+ * Strings and lists are passed in the memory exported as {@code memory},
+ * allocated with the {@code canonical_abi_realloc} and
+ * {@code canonical_abi_free} exports. Modules that only use numeric types do
+ * not need these exports. See {@link WitType} for the Java values of each
+ * type.
+ */
+@NotThreadSafe
+public class WitInstance implements AutoCloseable {
+    private static final String MEMORY = "memory";
+    private static final String REALLOC = "canonical_abi_realloc";
+    private static final String FREE = "canonical_abi_free";
+
+    private final WitInterface wit;
+    private final WasmInstance instance;
+    private final WasmStore store;
+    private final CanonicalAbi abi;
+
+    private final Map<String, WasmFunction> exports = new HashMap<>();
+    private WasmMemory memory;
+
+    public WitInstance(WitInterface wit, WasmInstance instance, WasmStore store) {
+        this.wit = wit;
+        this.instance = instance;
+        this.store = store;
+        this.abi = new CanonicalAbi(new ModuleMemory());
+    }
+
+    /**
+     * Call the function from the interface with the Java values of the params,
+     * in order.
+     *
+     * @return the result of the function, null if the function has no result
+     * @throws WasmtimeException        if the call fails or the module does not
+     *                                  match the interface
+     * @throws IllegalArgumentException if the args do not match the types of the
+     *                                  params
+     */
+    public Object call(String name, Object... args) throws WasmtimeException {
+        WitFunction function = this.wit.getFunction(name)
+                .orElseThrow(() -> new WasmtimeException(String.format("no function %s in the interface", name)));
+        List<WitType.Field> params = function.getParams();
+        if (args.length != params.size())
+            throw new IllegalArgumentException(
+                    String.format("%s expects %d args, got: %d", name, params.size(), args.length));
+
+        List<WasmType> flatArgs = new ArrayList<>();
+        WitType paramsRecord = WitType.record(name, params);
+        Exception failure = null;
+        try {
+            if (CanonicalAbi.flatten(paramsRecord).size() > CanonicalAbi.MAX_FLAT_PARAMS) {
+                Map<String, Object> values = new LinkedHashMap<>();
+                for (int i = 0; i < args.length; i++) {
+                    values.put(params.get(i).getName(), args[i]);
+                }
+                flatArgs.add(i32(this.abi.lowerToMemory(paramsRecord, values)));
+            } else {
+                for (int i = 0; i < args.length; i++) {
+                    this.abi.lowerFlat(params.get(i).getType(), args[i], flatArgs);
+                }
+            }
+        } catch (WasmtimeException | RuntimeException e) {
+            failure = e;
+            throw e;
+        } finally {
+            // the params were never passed, so the module will not free the buffers
+            if (failure != null)
+                release(this.abi::releaseLowered, failure);
+        }
+        this.abi.passLowered();
+
+        WasmType[] results = this.export(name).callMulti(this.instance, this.store,
+                flatArgs.toArray(new WasmType[0]));
+        if (!function.getResult().isPresent())
+            return null;
+
+        WitType resultType = function.getResult().get();
+        try {
+            if (CanonicalAbi.flatten(resultType).size() > CanonicalAbi.MAX_FLAT_RESULTS) {
+                if (results.length != 1 || !(results[0] instanceof I32))
+                    throw new WasmtimeException(
+                            String.format("expected a pointer to the results of %s, found: %s", name,
+                                    Arrays.toString(results)));
+                return this.abi.liftFromMemory(resultType, ((I32) results[0]).field);
+            }
+
+            return this.abi.liftFlat(resultType, Arrays.asList(results).iterator());
+        } catch (WasmtimeException | RuntimeException e) {
+            failure = e;
+            throw e;
+        } finally {
+            release(this.abi::releaseLifted, failure);
+        }
+    }
+
+    private interface Release {
+        void run() throws WasmtimeException;
+    }
+
+    /**
+     * If the call already failed, a failure to free the buffers is suppressed so
+     * the original exception surfaces.
+     */
+    private static void release(Release release, Exception failure) throws WasmtimeException {
+        try {
+            release.run();
+        } catch (WasmtimeException | RuntimeException e) {
+            if (failure == null)
+                throw e;
+            failure.addSuppressed(e);
+        }
+    }
+
+    private WasmFunction export(String name) throws WasmtimeException {
+        WasmFunction function = this.exports.get(name);
+        if (function == null) {
+            function = this.instance.getFunction(this.store, name)
+                    .orElseThrow(() -> new WasmtimeException(String.format("no export %s in the module", name)));
+            this.exports.put(name, function);
+        }
+        return function;
+    }
+
+    private WasmMemory memory() throws WasmtimeException {
+        if (this.memory == null) {
+            this.memory = this.instance.getMemory(this.store, MEMORY)
+                    .orElseThrow(() -> new WasmtimeException("no memory exported from the module"));
+        }
+        return this.memory;
+    }
+
+    @Override
+    public void close() {
+        this.exports.values().forEach(WasmFunction::close);
+        this.exports.clear();
+        if (this.memory != null) {
+            this.memory.close();
+            this.memory = null;
+        }
+    }
+
+    private class ModuleMemory implements CanonicalAbi.Memory {
+        @Override
+        public int alloc(int size, int align) throws WasmtimeException {
+            WitInstance self = WitInstance.this;
+            I32 ptr = self.export(REALLOC).call(self.instance, self.store, I32.class, i32(0), i32(0), i32(align),
+                    i32(size));
+            return ptr.field;
+        }
+
+        @Override
+        public void free(int ptr, int size, int align) throws WasmtimeException {
+            WitInstance self = WitInstance.this;
+            self.export(FREE).call(self.instance, self.store, i32(ptr), i32(size), i32(align));
+        }
+
+        @Override
+        public byte[] read(int ptr, int length) throws WasmtimeException {
+            return WitInstance.this.memory().read(WitInstance.this.store, Integer.toUnsignedLong(ptr), length);
+        }
+
+        @Override
+        public void write(int ptr, byte[] bytes) throws WasmtimeException {
+            WitInstance.this.memory().write(WitInstance.this.store, Integer.toUnsignedLong(ptr), bytes);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+package net.bluejekyll.wasmtime.wit;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Collection;
+import java.util.Collections;
+import java.util.Map;
+import java.util.Optional;
+
+import net.bluejekyll.wasmtime.WasmtimeException;
+
+/**
+ * The functions and types of a WIT interface description, as used by
+ * wit-bindgen to generate the exports of a WASM module.
+ * <p>
+ * The supported subset of WIT is the primitive types, string, list, record,
+ * variant, enum and type aliases, and functions with any number of named
+ * parameters and at most one result, e.g.
+ *
+ * <pre>
+ * record point { x: s32, y: s32 }
+ * add-points: function(a: point, b: point) -> point
+ * </pre>
+ *
+ * @see WitInstance
+ */
+public final class WitInterface {
+    private final Map<String, WitType> types;
+    private final Map<String, WitFunction> functions;
+
+    WitInterface(Map<String, WitType> types, Map<String, WitFunction> functions) {
+        this.types = Collections.unmodifiableMap(types);
+        this.functions = Collections.unmodifiableMap(functions);
+    }
+
+    /**
+     * @param source the WIT interface description
+     * @throws WasmtimeException if the description is invalid or not supported
+     */
+    public static WitInterface parse(String source) throws WasmtimeException {
+        return new WitParser(source).parse();
+    }
+
+    /** @see #parse(String) */
+    public static WitInterface parse(Path path) throws IOException, WasmtimeException {
+        return parse(new String(Files.readAllBytes(path), StandardCharsets.UTF_8));
+    }
+
+    /** @return the type defined with the name, e.g. a record */
+    public Optional<WitType> getType(String name) {
+        return Optional.ofNullable(this.types.get(name));
+    }
+
+    public Optional<WitFunction> getFunction(String name) {
+        return Optional.ofNullable(this.functions.get(name));
+    }
+
+    /** @return all of the functions, in the order they were declared */
+    public Collection<WitFunction> getFunctions() {
+        return this.functions.values();
+    }
+}
@@ -0,0 +1,327 @@
+package net.bluejekyll.wasmtime.wit;
+
+import java.util.ArrayList;
+import java.util.HashSet;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+
+import net.bluejekyll.wasmtime.WasmtimeException;
+
+/**
+ * A hand written parser for the subset of WIT described in
+ * {@link WitInterface}.
+ * <p>
+ * Named types may be used before they are defined, the references are
+ * resolved once the whole description has been parsed.
+ */
+final class WitParser {
+    private static final class Token {
+        final String text;
+        final int line;
+
+        Token(String text, int line) {
+            this.text = text;
+            this.line = line;
+        }
+    }
+
+    private final List<Token> tokens;
+    private int pos = 0;
+
+    private final Map<String, WitType> definitions = new LinkedHashMap<>();
+    private final Map<String, WitFunction> functions = new LinkedHashMap<>();
+
+    WitParser(String source) throws WasmtimeException {
+        this.tokens = tokenize(source);
+    }
+
+    private static List<Token> tokenize(String source) throws WasmtimeException {
+        List<Token> tokens = new ArrayList<>();
+        int line = 1;
+        int i = 0;
+        while (i < source.length()) {
+            char c = source.charAt(i);
+            if (c == '\n') {
+                line++;
+                i++;
+            } else if (Character.isWhitespace(c)) {
+                i++;
+            } else if (source.startsWith("//", i)) {
+                // comments, including doc comments, run to the end of the line
+                while (i < source.length() && source.charAt(i) != '\n')
+                    i++;
+            } else if (source.startsWith("->", i)) {
+                tokens.add(new Token("->", line));
+                i += 2;
+            } else if (":,(){}<>=".indexOf(c) >= 0) {
+                tokens.add(new Token(String.valueOf(c), line));
+                i++;
+            } else if (isIdentifierChar(c)) {
+                int start = i;
+                while (i < source.length() && isIdentifierChar(source.charAt(i)))
+                    i++;
+                tokens.add(new Token(source.substring(start, i), line));
+            } else {
+                throw new WasmtimeException(String.format("unexpected character '%c' on line %d", c, line));
+            }
+        }
+
+        return tokens;
+    }
+
+    private static boolean isIdentifierChar(char c) {
+        return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9') || c == '-' || c == '_';
+    }
+
+    WitInterface parse() throws WasmtimeException {
+        while (this.pos < this.tokens.size()) {
+            String keyword = this.peek();
+            switch (keyword) {
+                case "record":
+                    this.next();
+                    this.define(this.parseRecord());
+                    break;
+                case "variant":
+                    this.next();
+                    this.define(this.parseVariant());
+                    break;
+                case "enum":
+                    this.next();
+                    this.define(this.parseEnum());
+                    break;
+                case "type":
+                    this.next();
+                    String name = this.identifier();
+                    this.expect("=");
+                    this.define(name, this.parseType());
+                    break;
+                default:
+                    this.parseFunction();
+            }
+        }
+
+        Map<String, WitType> types = new LinkedHashMap<>();
+        for (String name : this.definitions.keySet()) {
+            types.put(name, this.resolve(WitType.named(name), new HashSet<>()));
+        }
+
+        Map<String, WitFunction> functions = new LinkedHashMap<>();
+        for (WitFunction function : this.functions.values()) {
+            List<WitType.Field> params = new ArrayList<>();
+            for (WitType.Field param : function.getParams()) {
+                params.add(new WitType.Field(param.getName(), this.resolve(param.getType(), new HashSet<>())));
+            }
+
+            WitType result = function.getResult().isPresent()
+                    ? this.resolve(function.getResult().get(), new HashSet<>())
+                    : null;
+            functions.put(function.getName(), new WitFunction(function.getName(), params, result));
+        }
+
+        return new WitInterface(types, functions);
+    }
+
+    private void define(WitType type) throws WasmtimeException {
+        this.define(type.getName().get(), type);
+    }
+
+    private void define(String name, WitType type) throws WasmtimeException {
+        if (this.definitions.containsKey(name))
+            throw this.error(String.format("type %s is already defined", name));
+        this.definitions.put(name, type);
+    }
+
+    private WitType parseRecord() throws WasmtimeException {
+        String name = this.identifier();
+        List<WitType.Field> fields = new ArrayList<>();
+        this.expect("{");
+        while (!this.consume("}")) {
+            String field = this.identifier();
+            this.expect(":");
+            fields.add(new WitType.Field(field, this.parseType()));
+            this.separator("}");
+        }
+
+        return WitType.record(name, fields);
+    }
+
+    private WitType parseVariant() throws WasmtimeException {
+        String name = this.identifier();
+        List<WitType.Case> cases = new ArrayList<>();
+        this.expect("{");
+        while (!this.consume("}")) {
+            String caseName = this.identifier();
+            WitType type = null;
+            if (this.consume("(")) {
+                type = this.parseType();
+                this.expect(")");
+            }
+
+            cases.add(new WitType.Case(caseName, type));
+            this.separator("}");
+        }
+
+        return WitType.variant(name, cases);
+    }
+
+    private WitType parseEnum() throws WasmtimeException {
+        String name = this.identifier();
+        List<WitType.Case> cases = new ArrayList<>();
+        this.expect("{");
+        while (!this.consume("}")) {
+            cases.add(new WitType.Case(this.identifier(), null));
+            this.separator("}");
+        }
+
+        return WitType.variant(name, cases);
+    }
+
+    private void parseFunction() throws WasmtimeException {
+        String name = this.identifier();
+        this.expect(":");
+        this.expect("function");
+        this.expect("(");
+
+        List<WitType.Field> params = new ArrayList<>();
+        while (!this.consume(")")) {
+            String param = this.identifier();
+            this.expect(":");
+            params.add(new WitType.Field(param, this.parseType()));
+            this.separator(")");
+        }
+
+        WitType result = null;
+        if (this.consume("->")) {
+            result = this.parseType();
+        }
+
+        if (this.functions.containsKey(name))
+            throw this.error(String.format("function %s is already defined", name));
+        this.functions.put(name, new WitFunction(name, params, result));
+    }
+
+    private WitType parseType() throws WasmtimeException {
+        String name = this.identifier();
+        switch (name) {
+            case "bool":
+                return WitType.BOOL;
+            case "s8":
+                return WitType.S8;
+            case "u8":
+                return WitType.U8;
+            case "s16":
+                return WitType.S16;
+            case "u16":
+                return WitType.U16;
+            case "s32":
+                return WitType.S32;
+            case "u32":
+                return WitType.U32;
+            case "s64":
+                return WitType.S64;
+            case "u64":
+                return WitType.U64;
+            case "f32":
+                return WitType.F32;
+            case "f64":
+                return WitType.F64;
+            case "char":
+                return WitType.CHAR;
+            case "string":
+                return WitType.STRING;
+            case "list":
+                this.expect("<");
+                WitType element = this.parseType();
+                this.expect(">");
+                return WitType.list(element);
+            case "option":
+            case "expected":
+            case "tuple":
+            case "flags":
+            case "handle":
+                throw this.error(String.format("unsupported WIT type: %s", name));
+            default:
+                return WitType.named(name);
+        }
+    }
+
+    /** Replaces the references to named types with their definitions */
+    private WitType resolve(WitType type, Set<String> resolving) throws WasmtimeException {
+        switch (type.getKind()) {
+            case NAMED:
+                String name = type.getName().get();
+                WitType definition = this.definitions.get(name);
+                if (definition == null)
+                    throw new WasmtimeException(String.format("undefined type: %s", name));
+                if (!resolving.add(name))
+                    throw new WasmtimeException(String.format("recursive type: %s", name));
+
+                WitType resolved = this.resolve(definition, resolving);
+                resolving.remove(name);
+                return resolved;
+            case LIST:
+                return WitType.list(this.resolve(type.getElement().get(), resolving));
+            case RECORD:
+                List<WitType.Field> fields = new ArrayList<>();
+                for (WitType.Field field : type.getFields()) {
+                    fields.add(new WitType.Field(field.getName(), this.resolve(field.getType(), resolving)));
+                }
+                return WitType.record(type.getName().get(), fields);
+            case VARIANT:
+                List<WitType.Case> cases = new ArrayList<>();
+                for (WitType.Case c : type.getCases()) {
+                    WitType caseType = c.getType().isPresent() ? this.resolve(c.getType().get(), resolving) : null;
+                    cases.add(new WitType.Case(c.getName(), caseType));
+                }
+                return WitType.variant(type.getName().get(), cases);
+            default:
+                return type;
+        }
+    }
+
+    private String peek() {
+        return this.tokens.get(this.pos).text;
+    }
+
+    private String next() throws WasmtimeException {
+        if (this.pos >= this.tokens.size())
+            throw new WasmtimeException("unexpected end of WIT");
+        return this.tokens.get(this.pos++).text;
+    }
+
+    private boolean consume(String expected) throws WasmtimeException {
+        if (this.pos >= this.tokens.size())
+            throw new WasmtimeException(String.format("unexpected end of WIT, expected: %s", expected));
+        if (!this.peek().equals(expected))
+            return false;
+        this.pos++;
+        return true;
+    }
+
+    private void expect(String expected) throws WasmtimeException {
+        if (!this.consume(expected))
+            throw this.error(String.format("expected '%s' found '%s'", expected, this.peek()));
+    }
+
+    /** Items in a list are separated by commas, a trailing comma is allowed */
+    private void separator(String close) throws WasmtimeException {
+        if (!this.consume(",") && !this.peek().equals(close))
+            throw this.error(String.format("expected ',' or '%s' found '%s'", close, this.peek()));
+    }
+
+    private String identifier() throws WasmtimeException {
+        String text = this.next();
+        if (!isIdentifierChar(text.charAt(0))) {
+            this.pos--;
+            throw this.error(String.format("expected a name found '%s'", text));
+        }
+        return text;
+    }
+
+    private WasmtimeException error(String msg) {
+        int line = this.tokens.get(Math.min(this.pos, this.tokens.size() - 1)).line;
+        return new WasmtimeException(String.format("%s on line %d", msg, line));
+    }
+}
@@ -0,0 +1,189 @@
+package net.bluejekyll.wasmtime.wit;
+
+import java.util.Collections;
+import java.util.List;
+import java.util.Objects;
+import java.util.Optional;
+
+/**
+ * A type from a WIT interface, see {@link WitInterface}.
+ * <p>
+ * The Java values for each kind of type are:
+ * <ul>
+ * <li>bool: Boolean</li>
+ * <li>s8, u8, s16, u16, s32: Integer</li>
+ * <li>u32, s64: Long</li>
+ * <li>u64: Long, values above Long.MAX_VALUE are negative, see
+ * {@link Long#toUnsignedString(long)}</li>
+ * <li>f32: Float, f64: Double</li>
+ * <li>char: Integer, the Unicode code point</li>
+ * <li>string: String</li>
+ * <li>list: java.util.List</li>
+ * <li>record: java.util.Map from the field names to the values</li>
+ * <li>variant and enum: {@link WitVariant}</li>
+ * </ul>
+ * Any Number is accepted for the numeric types, and a Character for char.
+ */
+public final class WitType {
+    public enum Kind {
+        BOOL,
+        S8,
+        U8,
+        S16,
+        U16,
+        S32,
+        U32,
+        S64,
+        U64,
+        F32,
+        F64,
+        CHAR,
+        STRING,
+        LIST,
+        RECORD,
+        VARIANT,
+        /** A reference to a named type, only used while parsing */
+        NAMED,
+    }
+
+    public static final WitType BOOL = new WitType(Kind.BOOL);
+    public static final WitType S8 = new WitType(Kind.S8);
+    public static final WitType U8 = new WitType(Kind.U8);
+    public static final WitType S16 = new WitType(Kind.S16);
+    public static final WitType U16 = new WitType(Kind.U16);
+    public static final WitType S32 = new WitType(Kind.S32);
+    public static final WitType U32 = new WitType(Kind.U32);
+    public static final WitType S64 = new WitType(Kind.S64);
+    public static final WitType U64 = new WitType(Kind.U64);
+    public static final WitType F32 = new WitType(Kind.F32);
+    public static final WitType F64 = new WitType(Kind.F64);
+    public static final WitType CHAR = new WitType(Kind.CHAR);
+    public static final WitType STRING = new WitType(Kind.STRING);
+
+    /** A named field of a record, or a parameter of a function */
+    public static final class Field {
+        private final String name;
+        private final WitType type;
+
+        public Field(String name, WitType type) {
+            this.name = Objects.requireNonNull(name);
+            this.type = Objects.requireNonNull(type);
+        }
+
+        public String getName() {
+            return this.name;
+        }
+
+        public WitType getType() {
+            return this.type;
+        }
+
+        @Override
+        public String toString() {
+            return this.name + ": " + this.type;
+        }
+    }
+
+    /** A case of a variant, the case may not have a value */
+    public static final class Case {
+        private final String name;
+        private final WitType type;
+
+        public Case(String name, WitType type) {
+            this.name = Objects.requireNonNull(name);
+            this.type = type;
+        }
+
+        public String getName() {
+            return this.name;
+        }
+
+        public Optional<WitType> getType() {
+            return Optional.ofNullable(this.type);
+        }
+
+        @Override
+        public String toString() {
+            return this.type == null ? this.name : this.name + "(" + this.type + ")";
+        }
+    }
+
+    private final Kind kind;
+    private final String name;
+    private final WitType element;
+    private final List<Field> fields;
+    private final List<Case> cases;
+
+    private WitType(Kind kind) {
+        this(kind, null, null, Collections.emptyList(), Collections.emptyList());
+    }
+
+    private WitType(Kind kind, String name, WitType element, List<Field> fields, List<Case> cases) {
+        this.kind = kind;
+        this.name = name;
+        this.element = element;
+        this.fields = fields;
+        this.cases = cases;
+    }
+
+    public static WitType list(WitType element) {
+        return new WitType(Kind.LIST, null, Objects.requireNonNull(element), Collections.emptyList(),
+                Collections.emptyList());
+    }
+
+    public static WitType record(String name, List<Field> fields) {
+        return new WitType(Kind.RECORD, Objects.requireNonNull(name), null, List.copyOf(fields),
+                Collections.emptyList());
+    }
+
+    /** An enum is a variant where none of the cases have a value */
+    public static WitType variant(String name, List<Case> cases) {
+        if (cases.isEmpty())
+            throw new IllegalArgumentException(String.format("variant %s has no cases", name));
+        return new WitType(Kind.VARIANT, Objects.requireNonNull(name), null, Collections.emptyList(),
+                List.copyOf(cases));
+    }
+
+    static WitType named(String name) {
+        return new WitType(Kind.NAMED, Objects.requireNonNull(name), null, Collections.emptyList(),
+                Collections.emptyList());
+    }
+
+    public Kind getKind() {
+        return this.kind;
+    }
+
+    /** @return the name of a record or variant */
+    public Optional<String> getName() {
+        return Optional.ofNullable(this.name);
+    }
+
+    /** @return the type of the elements of a list */
+    public Optional<WitType> getElement() {
+        return Optional.ofNullable(this.element);
+    }
+
+    /** @return the fields of a record, empty for all other types */
+    public List<Field> getFields() {
+        return this.fields;
+    }
+
+    /** @return the cases of a variant, empty for all other types */
+    public List<Case> getCases() {
+        return this.cases;
+    }
+
+    @Override
+    public String toString() {
+        switch (this.kind) {
+            case LIST:
+                return "list<" + this.element + ">";
+            case RECORD:
+            case VARIANT:
+            case NAMED:
+                return this.name;
+            default:
+                return this.kind.name().toLowerCase();
+        }
+    }
+}
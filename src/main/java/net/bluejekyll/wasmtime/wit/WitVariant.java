@@ -0,0 +1,46 @@
+package net.bluejekyll.wasmtime.wit;
+
+import java.util.Objects;
+import java.util.Optional;
+
+/** The Java value of a WIT variant or enum, the case and the value of the case, if it has one */
+public final class WitVariant {
+    private final String caseName;
+    private final Object value;
+
+    /** A case without a value, e.g. of an enum */
+    public WitVariant(String caseName) {
+        this(caseName, null);
+    }
+
+    public WitVariant(String caseName, Object value) {
+        this.caseName = Objects.requireNonNull(caseName);
+        this.value = value;
+    }
+
+    public String getCase() {
+        return this.caseName;
+    }
+
+    public Optional<Object> getValue() {
+        return Optional.ofNullable(this.value);
+    }
+
+    @Override
+    public boolean equals(Object obj) {
+        if (!(obj instanceof WitVariant))
+            return false;
+        WitVariant other = (WitVariant) obj;
+        return this.caseName.equals(other.caseName) && Objects.equals(this.value, other.value);
+    }
+
+    @Override
+    public int hashCode() {
+        return Objects.hash(this.caseName, this.value);
+    }
+
+    @Override
+    public String toString() {
+        return this.value == null ? this.caseName : String.format("%s(%s)", this.caseName, this.value);
+    }
+}
@@ -0,0 +1,73 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertEquals;
+
+import java.lang.reflect.Method;
+
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.ty.I32;
+
+/**
+ * Rough timing of calls from WASM to Java, comparing methods called through
+ * reflection, which convert the WasmTypes with the cached JNI classes, against
+ * methods with primitive signatures called directly.
+ * <p>
+ * The timings depend on the machine, only the results of the calls are
+ * asserted. Benchmarks are excluded from the tests, run them with
+ * {@code mvn -Pbenchmark test}.
+ */
+public class HostCallBenchmarkTest {
+    private static final int WARMUP = 10_000;
+    private static final int ITERATIONS = 100_000;
+
+    private static final String LOOP_WAT = "(module\n"
+            + " (import \"host\" \"add\" (func $add (param i32 i32) (result i32)))\n"
+            + " (func (export \"loop\") (param $n i32) (result i32)\n"
+            + "  (local $acc i32)\n"
+            + "  (block $done\n"
+            + "   (loop $next\n"
+            + "    (br_if $done (i32.eqz (local.get $n)))\n"
+            + "    (local.set $acc (call $add (local.get $acc) (i32.const 1)))\n"
+            + "    (local.set $n (i32.sub (local.get $n) (i32.const 1)))\n"
+            + "    (br $next)))\n"
+            + "  (local.get $acc))\n"
+            + " )";
+
+    public final I32 add(I32 a, I32 b) {
+        return new I32(a.field + b.field);
+    }
+
+    public final int addInts(int a, int b) {
+        return a + b;
+    }
+
+    @Test
+    public void benchmarkHostCalls() throws Exception {
+        long reflection = this.benchmark("reflection", this.getClass().getMethod("add", I32.class, I32.class));
+        long direct = this.benchmark("direct", this.getClass().getMethod("addInts", Integer.TYPE, Integer.TYPE));
+
+        System.out.printf("direct calls take %.2f of the time of reflection%n", (double) direct / reflection);
+    }
+
+    /** @return the nanoseconds taken for the host calls */
+    private long benchmark(String name, Method method) throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(LOOP_WAT,
+                (store, linker) -> linker.defineFunction("host", "add", WasmFunction.newFunc(store, method, this)))) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+            WasmFunction loop = wat.getFunction("loop");
+
+            assertEquals(WARMUP, loop.call(instance, store, I32.class, new I32(WARMUP)).intValue());
+
+            long start = System.nanoTime();
+            int result = loop.call(instance, store, I32.class, new I32(ITERATIONS)).intValue();
+            long elapsed = System.nanoTime() - start;
+
+            assertEquals(ITERATIONS, result);
+            System.out.printf("%s: %d host calls in %d ms, %d ns per call%n", name, ITERATIONS,
+                    elapsed / 1_000_000, elapsed / ITERATIONS);
+            return elapsed;
+        }
+    }
+}
@@ -0,0 +1,173 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertNotEquals;
+
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.File;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.ty.I32;
+
+public class WasiConfigTest {
+    private static final String WASI_WAT = "(module\n"
+            + " (import \"wasi_snapshot_preview1\" \"args_sizes_get\" (func $args_sizes_get (param i32 i32) (result i32)))\n"
+            + " (import \"wasi_snapshot_preview1\" \"environ_sizes_get\" (func $environ_sizes_get (param i32 i32) (result i32)))\n"
+            + " (import \"wasi_snapshot_preview1\" \"fd_prestat_get\" (func $fd_prestat_get (param i32 i32) (result i32)))\n"
+            + " (memory (export \"memory\") 1)\n"
+            + " (func (export \"argc\") (result i32)\n"
+            + "  (drop (call $args_sizes_get (i32.const 0) (i32.const 4)))\n"
+            + "  (i32.load (i32.const 0)))\n"
+            + " (func (export \"envc\") (result i32)\n"
+            + "  (drop (call $environ_sizes_get (i32.const 0) (i32.const 4)))\n"
+            + "  (i32.load (i32.const 0)))\n"
+            + " (func (export \"prestat\") (param i32) (result i32)\n"
+            + "  (call $fd_prestat_get (local.get 0) (i32.const 8)))\n"
+            + " )";
+
+    private static final String STDIO_WAT = "(module\n"
+            + " (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))\n"
+            + " (import \"wasi_snapshot_preview1\" \"fd_read\" (func $fd_read (param i32 i32 i32 i32) (result i32)))\n"
+            + " (memory (export \"memory\") 1)\n"
+            + " (data (i32.const 16) \"hello\\n\")\n"
+            + " (func (export \"hello\") (param i32) (result i32)\n"
+            + "  (i32.store (i32.const 0) (i32.const 16))\n"
+            + "  (i32.store (i32.const 4) (i32.const 6))\n"
+            + "  (call $fd_write (local.get 0) (i32.const 0) (i32.const 1) (i32.const 8)))\n"
+            + " (func (export \"read\") (result i32)\n"
+            + "  (i32.store (i32.const 0) (i32.const 32))\n"
+            + "  (i32.store (i32.const 4) (i32.const 16))\n"
+            + "  (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))\n"
+            + "  (i32.load (i32.const 8)))\n"
+            + " )";
+
+    private static int callInt(WasmEngine engine, WasmStore store, String name, I32... args) throws Exception {
+        return callInt(engine, store, WASI_WAT, name, args);
+    }
+
+    private static int callInt(WasmEngine engine, WasmStore store, String wat, String name, I32... args)
+            throws Exception {
+        try (WasmLinker linker = engine.newLinker(); WasmModule module = engine.newModule(wat.getBytes())) {
+            WasmInstance instance = linker.instantiate(store, module);
+            WasmFunction func = instance.getFunction(store, name).get();
+
+            return func.call(instance, store, I32.class, args).intValue();
+        }
+    }
+
+    @Test
+    public void testNoHostAccessByDefault() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            assertEquals(0, callInt(engine, store, "argc"));
+            assertEquals(0, callInt(engine, store, "envc"));
+            assertNotEquals(0, callInt(engine, store, "prestat", new I32(3)));
+        }
+    }
+
+    @Test
+    public void testArgsAndEnv() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig().args("prog", "--verbose").env("KEY", "value");
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            assertEquals(2, callInt(engine, store, "argc"));
+            assertEquals(1, callInt(engine, store, "envc"));
+        }
+    }
+
+    @Test
+    public void testPreopenDir() throws Exception {
+        File dir = Files.createTempDirectory("wasi-test").toFile();
+        dir.deleteOnExit();
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig()
+                .preopenDir(dir, "/data", WasiConfig.DirMode.READ_ONLY)
+                .preopenDir(dir, "/scratch", WasiConfig.DirMode.READ_WRITE);
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            // preopens are numbered after stdio
+            assertEquals(0, callInt(engine, store, "prestat", new I32(3)));
+            assertEquals(0, callInt(engine, store, "prestat", new I32(4)));
+            assertNotEquals(0, callInt(engine, store, "prestat", new I32(5)));
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testPreopenMissingDir() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig().preopenDir(new File("does/not/exist"), "/data",
+                WasiConfig.DirMode.READ_ONLY);
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            // the directory is only opened with the store
+        }
+    }
+
+    @Test
+    public void testStdoutBuffer() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig().stdoutBuffer().stderrBuffer();
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            assertEquals(0, callInt(engine, store, STDIO_WAT, "hello", new I32(1)));
+            assertEquals(0, callInt(engine, store, STDIO_WAT, "hello", new I32(2)));
+            assertEquals(0, callInt(engine, store, STDIO_WAT, "hello", new I32(1)));
+
+            assertEquals("hello\nhello\n", new String(store.readStdout(), StandardCharsets.UTF_8));
+            assertEquals("hello\n", new String(store.readStderr(), StandardCharsets.UTF_8));
+
+            // reading drains the buffer
+            assertEquals(0, store.readStdout().length);
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testStdoutNotBuffered() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            store.readStdout();
+        }
+    }
+
+    @Test
+    public void testStdioStreams() throws Exception {
+        ByteArrayOutputStream out = new ByteArrayOutputStream();
+        ByteArrayInputStream in = new ByteArrayInputStream("input".getBytes(StandardCharsets.UTF_8));
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig().stdin(in).stdout(out);
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            assertEquals(0, callInt(engine, store, STDIO_WAT, "hello", new I32(1)));
+            assertEquals("hello\n", out.toString(StandardCharsets.UTF_8));
+
+            assertEquals(5, callInt(engine, store, STDIO_WAT, "read"));
+            assertEquals(0, callInt(engine, store, STDIO_WAT, "read"));
+        }
+    }
+
+    @Test
+    public void testStdinBytes() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasiConfig wasi = new WasiConfig().stdin("abc".getBytes(StandardCharsets.UTF_8));
+                WasmEngine engine = wasm.newWasmEngine();
+                WasmStore store = engine.newStore(wasi)) {
+            assertEquals(3, callInt(engine, store, STDIO_WAT, "read"));
+        }
+    }
+
+    @Test
+    public void testClosedStdio() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            // writing to a closed fd is an error for the module, not the host
+            assertNotEquals(0, callInt(engine, store, STDIO_WAT, "hello", new I32(1)));
+        }
+    }
+}
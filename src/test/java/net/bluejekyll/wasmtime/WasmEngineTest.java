@@ -1,7 +1,22 @@
 package net.bluejekyll.wasmtime;
 
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
+import static org.junit.Assert.assertNotNull;
+import static org.junit.Assert.assertTrue;
 import static org.junit.Assert.fail;
 
+import java.io.File;
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Arrays;
+import java.util.Comparator;
+import java.util.Optional;
+import java.util.stream.Collectors;
+import java.util.stream.Stream;
+
+import org.junit.After;
 import org.junit.Test;
 
 public class WasmEngineTest {
@@ -14,4 +29,99 @@ public class WasmEngineTest {
             fail();
         }
     }
+
+    @Test
+    public void testNewWasmEngineWithConfig() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmConfig config = new WasmConfig()) {
+            config.consumeFuel(true)
+                    .epochInterruption(true)
+                    .wasmSimd(true)
+                    .wasmMultiMemory(true)
+                    .wasmReferenceTypes(true)
+                    .craneliftOptLevel(WasmConfig.OptLevel.SPEED)
+                    .debugInfo(false);
+
+            try (WasmEngine engine = wasm.newWasmEngine(config); WasmStore store = engine.newStore()) {
+                assertNotNull(store);
+            }
+
+            // the config is consumed by the engine
+            try {
+                wasm.newWasmEngine(config);
+                fail("the config was consumed");
+            } catch (NullPointerException e) {
+                // expected
+            }
+        }
+    }
+
+    private File cacheDir;
+
+    @After
+    public void deleteCacheDir() throws IOException {
+        if (this.cacheDir == null)
+            return;
+
+        // deepest paths first, so directories are empty when they are deleted
+        try (Stream<Path> paths = Files.walk(this.cacheDir.toPath())) {
+            for (Path path : paths.sorted(Comparator.reverseOrder()).collect(Collectors.toList())) {
+                Files.deleteIfExists(path);
+            }
+        }
+    }
+
+    private static long countFiles(File dir) throws IOException {
+        try (Stream<Path> paths = Files.walk(dir.toPath())) {
+            return paths.filter(Files::isRegularFile).count();
+        }
+    }
+
+    @Test
+    public void testCacheDirectory() throws Exception {
+        String wat = "(module (func (export \"nop\")))";
+        this.cacheDir = Files.createTempDirectory("wasmtime-cache").toFile();
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmConfig config = new WasmConfig().cacheDirectory(this.cacheDir);
+                WasmEngine engine = wasm.newWasmEngine(config);
+                WasmModule module = engine.newModule(wat.getBytes())) {
+            assertTrue("the compiled module is stored in the cache", countFiles(this.cacheDir) > 0);
+        }
+
+        // a new engine, as if the JVM had restarted
+        try (WasmConfig config = new WasmConfig().cacheDirectory(this.cacheDir);
+                WasmEngine engine = wasm.newWasmEngine(config);
+                WasmStore store = engine.newStore();
+                WasmLinker linker = engine.newLinker();
+                WasmModule module = engine.newModule(wat.getBytes())) {
+            WasmInstance instance = linker.instantiate(store, module);
+            assertTrue(instance.getFunction(store, "nop").isPresent());
+        }
+    }
+
+    @Test
+    public void testValidate() throws Exception {
+        // (module (func (export "nop")))
+        byte[] valid = new byte[] { 0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+                0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, 0x6e, 0x6f, 0x70, 0x00, 0x00, 0x0a, 0x04, 0x01, 0x02,
+                0x00, 0x0b };
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine()) {
+            assertFalse(engine.validate(valid).isPresent());
+
+            // truncate the code section
+            byte[] truncated = Arrays.copyOf(valid, valid.length - 2);
+            Optional<WasmValidationError> error = engine.validate(truncated);
+            assertTrue(error.isPresent());
+            assertTrue(error.get().getOffset() > 0);
+            assertFalse(error.get().getMessage().isEmpty());
+
+            // bad magic number
+            error = engine.validate("not wasm".getBytes());
+            assertTrue(error.isPresent());
+            assertEquals(0, error.get().getOffset());
+        }
+    }
 }
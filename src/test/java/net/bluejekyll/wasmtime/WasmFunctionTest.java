@@ -1,11 +1,15 @@
 package net.bluejekyll.wasmtime;
 
+import static org.junit.Assert.assertArrayEquals;
 import static org.junit.Assert.assertEquals;
 import static org.junit.Assert.assertNotNull;
+import static org.junit.Assert.assertSame;
 import static org.junit.Assert.assertTrue;
+import static org.junit.Assert.fail;
 
 import java.io.UnsupportedEncodingException;
 import java.lang.reflect.Method;
+import java.math.BigInteger;
 import java.nio.ByteBuffer;
 import java.util.Optional;
 
@@ -119,4 +123,385 @@ public class WasmFunctionTest {
             }
         }
     }
+
+    public final ExternRef echoExternRef(ExternRef ref) {
+        return ref;
+    }
+
+    @Test
+    public void testExternRefParamsAndReturn() throws Exception {
+        Object context = new Object();
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+
+            Method method = this.getClass().getMethod("echoExternRef", ExternRef.class);
+            WasmFunction func = WasmFunction.newFunc(store, method, this);
+
+            try (func) {
+                ExternRef val = func.call_for_tests(store, ExternRef.class, externRef(context));
+                assertSame(context, val.getField());
+
+                assertTrue(func.call_for_tests(store, ExternRef.class, ExternRef.NULL).isNull());
+            }
+        }
+    }
+
+    private static final String EXTERN_REF_WAT = "(module\n"
+            + " (import \"host\" \"describe\" (func $describe (param externref) (result i32)))\n"
+            + " (func (export \"identity\") (param externref) (result externref)\n"
+            + "  (local.get 0))\n"
+            + " (func (export \"describe\") (param externref) (result i32)\n"
+            + "  (call $describe (local.get 0)))\n"
+            + " )";
+
+    public final I32 describe(ExternRef ref) {
+        return i32(ref.getValue(StringBuilder.class).length());
+    }
+
+    @Test
+    public void testExternRefThroughWasm() throws Exception {
+        StringBuilder context = new StringBuilder("request");
+
+        try (WatInstance wat = WatInstance.instantiate(EXTERN_REF_WAT, (store, linker) -> {
+            try (WasmFunction describe = WasmFunction.newFunc(store, this, "describe", ExternRef.class)) {
+                linker.defineFunction("host", "describe", describe);
+            }
+        })) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+
+            WasmFunction identity = wat.getFunction("identity");
+            ExternRef val = identity.call(instance, store, ExternRef.class, externRef(context));
+            assertSame(context, val.getValue(StringBuilder.class));
+
+            // the host function receives the original object
+            WasmFunction guestDescribe = wat.getFunction("describe");
+            assertEquals(7, guestDescribe.call(instance, store, I32.class, externRef(context)).intValue());
+        }
+    }
+
+    public final V128 xorV128(V128 a, V128 b) {
+        byte[] bytes = a.getField();
+        byte[] other = b.getField();
+        for (int i = 0; i < V128.BYTES; i++) {
+            bytes[i] ^= other[i];
+        }
+        return v128(bytes);
+    }
+
+    @Test
+    public void testV128ParamsAndReturn() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+
+            Method method = this.getClass().getMethod("xorV128", V128.class, V128.class);
+            WasmFunction func = WasmFunction.newFunc(store, method, this);
+
+            try (func) {
+                V128 val = func.call_for_tests(store, V128.class, v128(BigInteger.valueOf(0b1100)),
+                        v128(BigInteger.valueOf(0b1010)));
+                assertEquals(BigInteger.valueOf(0b0110), val.toBigInteger());
+
+                // negative values are two's complement
+                V128 allOnes = func.call_for_tests(store, V128.class, v128(BigInteger.valueOf(-1)),
+                        v128(BigInteger.ZERO));
+                assertEquals(BigInteger.ONE.shiftLeft(128).subtract(BigInteger.ONE), allOnes.toBigInteger());
+            }
+        }
+    }
+
+    private static final String SIMD_WAT = "(module\n"
+            + " (func (export \"add\") (param v128 v128) (result v128)\n"
+            + "  (i32x4.add (local.get 0) (local.get 1)))\n"
+            + " )";
+
+    @Test
+    public void testV128ThroughWasm() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(new WasmConfig().wasmSimd(true), SIMD_WAT)) {
+            WasmFunction add = wat.getFunction("add");
+
+            // lanes are little-endian, lane 0 is 1 + 2 and lane 3 is -1 + 1
+            byte[] a = new byte[V128.BYTES];
+            byte[] b = new byte[V128.BYTES];
+            a[0] = 1;
+            b[0] = 2;
+            a[12] = a[13] = a[14] = a[15] = (byte) 0xFF;
+            b[12] = 1;
+
+            V128 sum = add.call(wat.instance, wat.store, V128.class, v128(a), v128(b));
+
+            byte[] expected = new byte[V128.BYTES];
+            expected[0] = 3;
+            assertArrayEquals(expected, sum.getField());
+        }
+    }
+
+    private static final String MULTI_VALUE_WAT = "(module\n"
+            + " (import \"host\" \"divmod\" (func $divmod (param i32 i32) (result i32 i32)))\n"
+            + " (func (export \"swap\") (param i32 i64) (result i64 i32)\n"
+            + "  (local.get 1) (local.get 0))\n"
+            + " (func (export \"divmod\") (param i32 i32) (result i32 i32)\n"
+            + "  (call $divmod (local.get 0) (local.get 1)))\n"
+            + " )";
+
+    public final WasmType[] divmod(I32 a, I32 b) {
+        return new WasmType[] { i32(a.field / b.field), i32(a.field % b.field) };
+    }
+
+    @Test
+    public void testMultiValue() throws Exception {
+        Method method = this.getClass().getMethod("divmod", I32.class, I32.class);
+        try (WatInstance wat = WatInstance.instantiate(MULTI_VALUE_WAT, (store, linker) -> {
+            try (WasmFunction divmod = WasmFunction.newMultiFunc(store, method, this, I32.class, I32.class)) {
+                linker.defineFunction("host", "divmod", divmod);
+            }
+        })) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+
+            WasmFunction swap = wat.getFunction("swap");
+            WasmType[] swapped = swap.callMulti(instance, store, i32(1), i64(2));
+            assertEquals(2, swapped.length);
+            assertEquals(2L, ((I64) swapped[0]).longValue());
+            assertEquals(1, ((I32) swapped[1]).intValue());
+
+            // results from the host are passed back through WASM
+            WasmFunction guestDivmod = wat.getFunction("divmod");
+            WasmType[] quotientRemainder = guestDivmod.callMulti(instance, store, i32(7), i32(2));
+            assertEquals(3, ((I32) quotientRemainder[0]).intValue());
+            assertEquals(1, ((I32) quotientRemainder[1]).intValue());
+        }
+    }
+
+    private static final String ARRAYS_WAT = "(module\n"
+            + " (import \"host\" \"reverse\" (func $reverse (param i32 i32 i32)))\n"
+            + " (import \"host\" \"tail\" (func $tail (param i32 i32 i32)))\n"
+            + " (memory (export \"memory\") 1)\n"
+            + " (global $next (mut i32) (i32.const 1024))\n"
+            + " (global $freed_align (mut i32) (i32.const 0))\n"
+            + " (func $alloc_bytes (export \"__alloc_bytes\") (param $size i32) (result i32)\n"
+            + "  (local $ptr i32)\n"
+            + "  (local.set $ptr (global.get $next))\n"
+            + "  (global.set $next (i32.add (local.get $ptr)\n"
+            + "   (i32.and (i32.add (local.get $size) (i32.const 7)) (i32.const -8))))\n"
+            + "  (local.get $ptr))\n"
+            + " (func (export \"__dealloc_bytes\") (param i32 i32))\n"
+            + " (func (export \"__alloc_aligned\") (param $size i32) (param $align i32) (result i32)\n"
+            + "  (call $alloc_bytes (local.get $size)))\n"
+            + " (func (export \"__dealloc_aligned\") (param i32 i32) (param $align i32)\n"
+            + "  (global.set $freed_align (local.get $align)))\n"
+            + " (func (export \"freed_align\") (result i32) (global.get $freed_align))\n"
+            + " (func (export \"sum_f32\") (param $ptr i32) (param $len i32) (result f32)\n"
+            + "  (local $acc f32)\n"
+            + "  (block $done\n"
+            + "   (loop $next\n"
+            + "    (br_if $done (i32.eqz (local.get $len)))\n"
+            + "    (local.set $acc (f32.add (local.get $acc) (f32.load (local.get $ptr))))\n"
+            + "    (local.set $ptr (i32.add (local.get $ptr) (i32.const 4)))\n"
+            + "    (local.set $len (i32.sub (local.get $len) (i32.const 1)))\n"
+            + "    (br $next)))\n"
+            + "  (local.get $acc))\n"
+            + " (func (export \"echo_i64\") (export \"echo_bytes\")\n"
+            + "  (param $ptr i32) (param $len i32) (param $ret i32)\n"
+            + "  (i32.store (local.get $ret) (local.get $ptr))\n"
+            + "  (i32.store offset=4 (local.get $ret) (local.get $len)))\n"
+            + " (func (export \"reverse\") (param i32 i32 i32)\n"
+            + "  (call $reverse (local.get 0) (local.get 1) (local.get 2)))\n"
+            + " (func (export \"tail\") (param i32 i32 i32)\n"
+            + "  (call $tail (local.get 0) (local.get 1) (local.get 2)))\n"
+            + " )";
+
+    public final int[] reverseInts(int[] values) {
+        int[] reversed = new int[values.length];
+        for (int i = 0; i < values.length; i++) {
+            reversed[i] = values[values.length - 1 - i];
+        }
+        return reversed;
+    }
+
+    public final ByteBuffer tail(ByteBuffer bytes) {
+        ByteBuffer tail = bytes.duplicate();
+        tail.position(1);
+        return tail;
+    }
+
+    private void defineArrayImports(WasmStore store, WasmLinker linker) throws Exception {
+        Method method = this.getClass().getMethod("reverseInts", int[].class);
+        try (WasmFunction reverse = WasmFunction.newFunc(store, method, this)) {
+            linker.defineFunction("host", "reverse", reverse);
+        }
+
+        method = this.getClass().getMethod("tail", ByteBuffer.class);
+        try (WasmFunction tail = WasmFunction.newFunc(store, method, this)) {
+            linker.defineFunction("host", "tail", tail);
+        }
+    }
+
+    @Test
+    public void testPrimitiveArrays() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(ARRAYS_WAT, this::defineArrayImports)) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+
+            // the length passed is the number of elements
+            WasmFunction sum = wat.getFunction("sum_f32");
+            Object total = sum.invoke(instance, store, Float.TYPE, new float[] { 1.5f, 2.25f, 3.0f });
+            assertEquals(6.75f, (Float) total, 0.0f);
+
+            // returned by reference
+            WasmFunction echo = wat.getFunction("echo_i64");
+            long[] longs = new long[] { 1L, Long.MAX_VALUE, -2L };
+            assertArrayEquals(longs, (long[]) echo.invoke(instance, store, long[].class, longs));
+
+            // returned elements are freed with the alignment of the element
+            WasmFunction freedAlign = wat.getFunction("freed_align");
+            assertEquals(8, freedAlign.invoke(instance, store, Integer.TYPE));
+
+            // passed from WASM to Java and returned by reference from Java
+            WasmFunction guestReverse = wat.getFunction("reverse");
+            int[] reversed = (int[]) guestReverse.invoke(instance, store, int[].class, new int[] { 1, 2, 3 });
+            assertArrayEquals(new int[] { 3, 2, 1 }, reversed);
+            assertEquals(4, freedAlign.invoke(instance, store, Integer.TYPE));
+        }
+    }
+
+    @Test
+    public void testByteBuffers() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(ARRAYS_WAT, this::defineArrayImports)) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+            WasmFunction echo = wat.getFunction("echo_bytes");
+
+            // only the bytes from the position to the limit are passed
+            ByteBuffer heap = ByteBuffer.wrap(new byte[] { 1, 2, 3, 4, 5 });
+            heap.position(1).limit(4);
+            ByteBuffer echoed = (ByteBuffer) echo.invoke(instance, store, ByteBuffer.class, heap);
+            assertEquals(ByteBuffer.wrap(new byte[] { 2, 3, 4 }), echoed);
+            assertEquals(1, heap.position());
+            assertEquals(4, heap.limit());
+
+            ByteBuffer direct = ByteBuffer.allocateDirect(5);
+            direct.put(new byte[] { 1, 2, 3, 4, 5 }).position(2);
+            echoed = (ByteBuffer) echo.invoke(instance, store, ByteBuffer.class, direct);
+            assertEquals(ByteBuffer.wrap(new byte[] { 3, 4, 5 }), echoed);
+            assertEquals(2, direct.position());
+
+            // passed from WASM to Java and returned by reference from Java
+            WasmFunction tail = wat.getFunction("tail");
+            ByteBuffer bytes = (ByteBuffer) tail.invoke(instance, store, ByteBuffer.class,
+                    ByteBuffer.wrap(new byte[] { 1, 2, 3 }));
+            assertEquals(ByteBuffer.wrap(new byte[] { 2, 3 }), bytes);
+        }
+    }
+
+    public final int addInts(int a, int b) {
+        return a + b;
+    }
+
+    public static long mulLongs(long a, long b) {
+        return a * b;
+    }
+
+    public final double mixed(int a, long b, float c, double d) {
+        return a + b + c + d;
+    }
+
+    public final int divide(int a, int b) {
+        return a / b;
+    }
+
+    @Test
+    public void testDirectPrimitiveFunctions() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            Method addInts = this.getClass().getMethod("addInts", Integer.TYPE, Integer.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, addInts, this)) {
+                assertEquals(3, func.call_for_tests(store, I32.class, i32(1), i32(2)).intValue());
+            }
+
+            // static methods don't need an object
+            Method mulLongs = this.getClass().getMethod("mulLongs", Long.TYPE, Long.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, mulLongs, null)) {
+                assertEquals(6L, func.call_for_tests(store, I64.class, i64(2), i64(3)).longValue());
+            }
+
+            Method mixed = this.getClass().getMethod("mixed", Integer.TYPE, Long.TYPE, Float.TYPE, Double.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, mixed, this)) {
+                F64 val = func.call_for_tests(store, F64.class, i32(1), i64(2), f32(0.5f), f64(0.25));
+                assertEquals(3.75, val.doubleValue(), 0.0);
+            }
+        }
+    }
+
+    public final Integer addBoxed(Integer a, I32 b) {
+        return a + b.field;
+    }
+
+    public final boolean isNegative(short value) {
+        return value < 0;
+    }
+
+    public final char offsetChar(char c, byte offset) {
+        return (char) (c + offset);
+    }
+
+    @Test
+    public void testBoxedAndSmallPrimitiveFunctions() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            Method addBoxed = this.getClass().getMethod("addBoxed", Integer.class, I32.class);
+            try (WasmFunction func = WasmFunction.newFunc(store, addBoxed, this)) {
+                assertEquals(3, func.call_for_tests(store, I32.class, i32(1), i32(2)).intValue());
+            }
+
+            // short and boolean are passed as i32
+            Method isNegative = this.getClass().getMethod("isNegative", Short.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, isNegative, this)) {
+                assertEquals(1, func.call_for_tests(store, I32.class, i32(-1)).intValue());
+                assertEquals(0, func.call_for_tests(store, I32.class, i32(1)).intValue());
+            }
+
+            // char is zero extended, byte is sign extended
+            Method offsetChar = this.getClass().getMethod("offsetChar", Character.TYPE, Byte.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, offsetChar, this)) {
+                assertEquals(0xFFFE, func.call_for_tests(store, I32.class, i32(0xFFFF), i32(0xFF)).intValue());
+            }
+        }
+    }
+
+    @Test
+    public void testNullBoxedArgument() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            Method addInts = this.getClass().getMethod("addInts", Integer.TYPE, Integer.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, addInts, this)) {
+                try {
+                    func.invoke(null, store, Integer.TYPE, 1, (Integer) null);
+                    fail("null arguments are rejected");
+                } catch (IllegalArgumentException e) {
+                    assertTrue(e.getMessage().contains("index: 1"));
+                }
+
+                // the native conversion reports nulls as an exception
+                try {
+                    func.call_for_tests(store, I32.class, i32(1), null);
+                    fail("null arguments are rejected");
+                } catch (WasmtimeException e) {
+                    assertTrue(e.getMessage().contains("index: 1"));
+                }
+            }
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testDirectFunctionException() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            Method divide = this.getClass().getMethod("divide", Integer.TYPE, Integer.TYPE);
+            try (WasmFunction func = WasmFunction.newFunc(store, divide, this)) {
+                func.call_for_tests(store, I32.class, i32(1), i32(0));
+            }
+        }
+    }
 }
@@ -0,0 +1,90 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
+import static org.junit.Assert.assertTrue;
+
+import org.junit.After;
+import org.junit.Before;
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.ty.F64;
+import net.bluejekyll.wasmtime.ty.GlobalType;
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.I64;
+import net.bluejekyll.wasmtime.ty.ValType;
+
+public class WasmGlobalTest {
+    private static final String GLOBAL_WAT = "(module\n"
+            + " (import \"config\" \"limit\" (global $limit i32))\n"
+            + " (global $counter (export \"counter\") (mut i64) (i64.const 1))\n"
+            + " (global (export \"pi\") f64 (f64.const 3.5))\n"
+            + " (func (export \"limit\") (result i32)\n"
+            + "  (global.get $limit))\n"
+            + " (func (export \"counter\") (result i64)\n"
+            + "  (global.get $counter))\n"
+            + " )";
+
+    private WatInstance wat;
+    private WasmStore store;
+    private WasmInstance instance;
+
+    @Before
+    public void setup() throws Exception {
+        this.wat = WatInstance.instantiate(GLOBAL_WAT,
+                (store, linker) -> linker.defineGlobal(store, "config", "limit", new I32(42), false));
+        this.store = this.wat.store;
+        this.instance = this.wat.instance;
+    }
+
+    @After
+    public void tearDown() throws Exception {
+        this.wat.close();
+    }
+
+    @Test
+    public void testDefineGlobal() throws Exception {
+        WasmFunction limit = this.instance.getFunction(this.store, "limit").get();
+        assertEquals(42, limit.call(this.instance, this.store, I32.class).intValue());
+    }
+
+    @Test
+    public void testGetGlobal() throws Exception {
+        assertFalse(this.instance.getGlobal(this.store, "nope").isPresent());
+
+        try (WasmGlobal pi = this.instance.getGlobal(this.store, "pi").get()) {
+            GlobalType ty = pi.getType(this.store);
+            assertEquals(ValType.F64, ty.getContent());
+            assertFalse(ty.isMutable());
+
+            assertEquals(3.5, ((F64) pi.get(this.store)).doubleValue(), 0.0);
+        }
+    }
+
+    @Test
+    public void testSetGlobal() throws Exception {
+        try (WasmGlobal counter = this.instance.getGlobal(this.store, "counter").get()) {
+            assertTrue(counter.getType(this.store).isMutable());
+            assertEquals(1L, ((I64) counter.get(this.store)).longValue());
+
+            counter.set(this.store, new I64(7));
+
+            WasmFunction get = this.instance.getFunction(this.store, "counter").get();
+            assertEquals(7L, get.call(this.instance, this.store, I64.class).longValue());
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testSetImmutable() throws Exception {
+        try (WasmGlobal pi = this.instance.getGlobal(this.store, "pi").get()) {
+            pi.set(this.store, new F64(1.0));
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testSetWrongType() throws Exception {
+        try (WasmGlobal counter = this.instance.getGlobal(this.store, "counter").get()) {
+            counter.set(this.store, new I32(1));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertArrayEquals;
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
+import static org.junit.Assert.assertTrue;
+import static org.junit.Assert.fail;
+
+import org.junit.After;
+import org.junit.Before;
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.ty.I32;
+
+public class WasmMemoryTest {
+    private static final String MEMORY_WAT = "(module\n"
+            + " (memory (export \"memory\") 1 3)\n"
+            + " (data (i32.const 8) \"\\01\\02\\03\\04\")\n"
+            + " (func (export \"load\") (param i32) (result i32)\n"
+            + "  (i32.load (local.get 0)))\n"
+            + " (func (export \"grow\") (param i32) (result i32)\n"
+            + "  (memory.grow (local.get 0)))\n"
+            + " )";
+
+    private WatInstance wat;
+    private WasmStore store;
+    private WasmInstance instance;
+    private WasmMemory memory;
+
+    @Before
+    public void setup() throws Exception {
+        this.wat = WatInstance.instantiate(MEMORY_WAT);
+        this.store = this.wat.store;
+        this.instance = this.wat.instance;
+        this.memory = this.instance.getMemory(this.store, "memory").get();
+    }
+
+    @After
+    public void tearDown() throws Exception {
+        this.memory.close();
+        this.wat.close();
+    }
+
+    @Test
+    public void testMissingMemory() throws Exception {
+        assertFalse(this.instance.getMemory(this.store, "nope").isPresent());
+    }
+
+    @Test
+    public void testSizeAndGrow() throws Exception {
+        assertEquals(1, this.memory.size(this.store));
+        assertEquals(WasmMemory.PAGE_SIZE, this.memory.dataSize(this.store));
+
+        assertEquals(1, this.memory.grow(this.store, 2));
+        assertEquals(3, this.memory.size(this.store));
+
+        try {
+            this.memory.grow(this.store, 1);
+            fail("memory maximum is 3 pages");
+        } catch (WasmtimeException e) {
+            // expected
+        }
+    }
+
+    @Test
+    public void testReadWrite() throws Exception {
+        assertArrayEquals(new byte[] { 1, 2, 3, 4 }, this.memory.read(this.store, 8, 4));
+
+        this.memory.write(this.store, 100, new byte[] { 5, 6, 7 });
+        assertArrayEquals(new byte[] { 5, 6, 7 }, this.memory.read(this.store, 100, 3));
+    }
+
+    @Test
+    public void testTypedAccess() throws Exception {
+        // little-endian
+        assertEquals(0x04030201, this.memory.readI32(this.store, 8));
+
+        this.memory.writeI32(this.store, 16, -2);
+        assertEquals(-2, this.memory.readI32(this.store, 16));
+
+        this.memory.writeI64(this.store, 24, Long.MIN_VALUE);
+        assertEquals(Long.MIN_VALUE, this.memory.readI64(this.store, 24));
+
+        this.memory.writeF32(this.store, 32, 1.5f);
+        assertEquals(1.5f, this.memory.readF32(this.store, 32), 0.0f);
+
+        this.memory.writeF64(this.store, 40, -2.25);
+        assertEquals(-2.25, this.memory.readF64(this.store, 40), 0.0);
+
+        // WASM sees the same bytes
+        WasmFunction load = this.instance.getFunction(this.store, "load").get();
+        this.memory.writeI32(this.store, 48, 42);
+        assertEquals(42, load.call(this.instance, this.store, I32.class, new I32(48)).intValue());
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testReadOutOfBounds() throws Exception {
+        this.memory.read(this.store, WasmMemory.PAGE_SIZE - 2, 4);
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testWriteOutOfBounds() throws Exception {
+        this.memory.writeI64(this.store, WasmMemory.PAGE_SIZE - 4, 1);
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testNegativeOffset() throws Exception {
+        this.memory.readI32(this.store, -1);
+    }
+
+    @Test
+    public void testDirectView() throws Exception {
+        WasmMemoryView view = this.memory.directView(this.store, 8, 16);
+        assertTrue(view.isValid());
+        assertEquals(16, view.length());
+
+        assertEquals(0x04030201, view.getInt(0));
+
+        // writes are visible to WASM without copying
+        view.putInt(8, 42);
+        WasmFunction load = this.instance.getFunction(this.store, "load").get();
+        assertEquals(42, load.call(this.instance, this.store, I32.class, new I32(16)).intValue());
+
+        // and writes from the host are visible in the view
+        this.memory.writeI64(this.store, 12, -1L);
+        assertEquals(-1L, view.getLong(4));
+    }
+
+    @Test
+    public void testDirectViewStaleAfterGrow() throws Exception {
+        WasmMemoryView view = this.memory.directView(this.store, 0, 8);
+        this.memory.grow(this.store, 1);
+
+        assertFalse(view.isValid());
+        try {
+            view.getInt(0);
+            fail("view should be stale");
+        } catch (IllegalStateException e) {
+            // expected
+        }
+
+        // a new view is valid
+        assertTrue(this.memory.directView(this.store, 0, 8).isValid());
+    }
+
+    @Test
+    public void testDirectViewValidWithoutGrowth() throws Exception {
+        WasmMemoryView view = this.memory.directView(this.store, 8, 8);
+        assertEquals(1, this.memory.grow(this.store, 0));
+        assertTrue(view.isValid());
+
+        // growing past the maximum of 3 pages fails and leaves the memory as it was
+        try {
+            this.memory.grow(this.store, 3);
+            fail("memory maximum is 3 pages");
+        } catch (WasmtimeException e) {
+            // expected
+        }
+
+        assertTrue(view.isValid());
+        assertEquals(0x04030201, view.getInt(0));
+    }
+
+    @Test
+    public void testDirectViewStaleAfterWasmGrow() throws Exception {
+        WasmMemoryView view = this.memory.directView(this.store, 0, 8);
+
+        WasmFunction grow = this.instance.getFunction(this.store, "grow").get();
+        assertEquals(1, grow.call(this.instance, this.store, I32.class, new I32(1)).intValue());
+
+        assertFalse(view.isValid());
+    }
+
+    @Test
+    public void testDirectViewInvalidAfterStoreClosed() throws Exception {
+        WasmMemoryView view = this.memory.directView(this.store, 0, 8);
+        this.store.close();
+
+        assertFalse(view.isValid());
+        try {
+            view.getInt(0);
+            fail("the store is closed");
+        } catch (IllegalStateException e) {
+            // expected
+        }
+        try {
+            view.put(0, new byte[] { 1 });
+            fail("the store is closed");
+        } catch (IllegalStateException e) {
+            // expected
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testDirectViewOutOfBounds() throws Exception {
+        this.memory.directView(this.store, WasmMemory.PAGE_SIZE - 4, 8);
+    }
+}
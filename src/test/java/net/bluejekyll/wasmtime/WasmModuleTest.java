@@ -1,12 +1,30 @@
 package net.bluejekyll.wasmtime;
 
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
 import static org.junit.Assert.assertNotNull;
+import static org.junit.Assert.assertTrue;
 import static org.junit.Assert.fail;
 
 import java.io.File;
+import java.nio.file.Files;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.List;
+import java.util.Optional;
 
 import org.junit.Test;
 
+import net.bluejekyll.wasmtime.ty.ExportType;
+import net.bluejekyll.wasmtime.ty.ExternKind;
+import net.bluejekyll.wasmtime.ty.FuncType;
+import net.bluejekyll.wasmtime.ty.GlobalType;
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.ImportType;
+import net.bluejekyll.wasmtime.ty.MemoryType;
+import net.bluejekyll.wasmtime.ty.TableType;
+import net.bluejekyll.wasmtime.ty.ValType;
+
 public class WasmModuleTest {
     @Test
     public void testNewWasmModule() throws Exception {
@@ -19,4 +37,135 @@ public class WasmModuleTest {
             assertNotNull(module);
         }
     }
+
+    private static final String ADD_WAT = "(module\n"
+            + " (func (export \"add\") (param i32 i32) (result i32)\n"
+            + "  (i32.add (local.get 0) (local.get 1)))\n"
+            + " )";
+
+    private static void assertAdds(WasmEngine engine, WasmModule module) throws Exception {
+        try (WasmStore store = engine.newStore(); WasmLinker linker = engine.newLinker()) {
+            WasmInstance instance = linker.instantiate(store, module);
+            Optional<WasmFunction> func = instance.getFunction(store, "add");
+            assertTrue(func.isPresent());
+
+            I32 ret = func.get().call(instance, store, I32.class, new I32(3), new I32(2));
+            assertEquals(5, ret.intValue());
+        }
+    }
+
+    @Test
+    public void testSerializeDeserialize() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine()) {
+            byte[] serialized;
+            try (WasmModule module = engine.newModule(ADD_WAT.getBytes())) {
+                serialized = module.serialize();
+            }
+
+            try (WasmModule module = engine.deserializeModule(serialized)) {
+                assertAdds(engine, module);
+            }
+        }
+    }
+
+    @Test
+    public void testDeserializeFile() throws Exception {
+        File file = Files.createTempFile("add", ".cwasm").toFile();
+        file.deleteOnExit();
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine()) {
+            try (WasmModule module = engine.newModule(ADD_WAT.getBytes())) {
+                Files.write(file.toPath(), module.serialize());
+            }
+
+            try (WasmModule module = engine.deserializeModuleFile(file)) {
+                assertAdds(engine, module);
+            }
+        }
+    }
+
+    @Test
+    public void testDeserializeMissingFile() throws Exception {
+        File file = new File(Files.createTempDirectory("wasmtime").toFile(), "missing.cwasm");
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine()) {
+            engine.deserializeModuleFile(file);
+            fail("the file does not exist");
+        } catch (WasmtimeException e) {
+            assertTrue(e.getMessage().contains(file.getName()));
+            assertFalse(e.getMessage().contains("same configuration"));
+        }
+    }
+
+    @Test
+    public void testDeserializeIncompatibleConfig() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        byte[] serialized;
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmModule module = engine.newModule(ADD_WAT.getBytes())) {
+            serialized = module.serialize();
+        }
+
+        try (WasmConfig config = new WasmConfig().consumeFuel(true);
+                WasmEngine engine = wasm.newWasmEngine(config)) {
+            engine.deserializeModule(serialized);
+            fail("the engine config does not match");
+        } catch (WasmtimeException e) {
+            assertTrue(e.getMessage().contains("same configuration"));
+        }
+    }
+
+    @Test
+    public void testImportsAndExports() throws Exception {
+        String wat = "(module\n"
+                + " (import \"env\" \"log\" (func (param i32 i64) (result f32)))\n"
+                + " (import \"env\" \"counter\" (global (mut i32)))\n"
+                + " (memory (export \"memory\") 1 2)\n"
+                + " (table (export \"table\") 3 funcref)\n"
+                + " (global (export \"pi\") f64 (f64.const 3.14))\n"
+                + " (func (export \"run\") (param f64) (result i32 i64) (i32.const 0) (i64.const 1))\n"
+                + " )";
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmModule module = engine.newModule(wat.getBytes())) {
+            List<ImportType> imports = module.getImports();
+            assertEquals(2, imports.size());
+
+            ImportType log = imports.get(0);
+            assertEquals("env", log.getModule());
+            assertEquals("log", log.getName());
+            assertEquals(ExternKind.FUNC, log.getType().getKind());
+            FuncType logTy = (FuncType) log.getType();
+            assertEquals(Arrays.asList(ValType.I32, ValType.I64), logTy.getParams());
+            assertEquals(Collections.singletonList(ValType.F32), logTy.getResults());
+
+            GlobalType counterTy = (GlobalType) imports.get(1).getType();
+            assertEquals(ValType.I32, counterTy.getContent());
+            assertTrue(counterTy.isMutable());
+
+            List<ExportType> exports = module.getExports();
+            assertEquals(4, exports.size());
+
+            assertEquals("memory", exports.get(0).getName());
+            MemoryType memoryTy = (MemoryType) exports.get(0).getType();
+            assertEquals(1, memoryTy.getMinimum());
+            assertEquals(2, memoryTy.getMaximum().getAsLong());
+            assertFalse(memoryTy.is64());
+
+            TableType tableTy = (TableType) exports.get(1).getType();
+            assertEquals(ValType.FuncRef, tableTy.getElement());
+            assertEquals(3, tableTy.getMinimum());
+            assertFalse(tableTy.getMaximum().isPresent());
+
+            GlobalType piTy = (GlobalType) exports.get(2).getType();
+            assertEquals(ValType.F64, piTy.getContent());
+            assertFalse(piTy.isMutable());
+
+            FuncType runTy = (FuncType) exports.get(3).getType();
+            assertEquals(Collections.singletonList(ValType.F64), runTy.getParams());
+            assertEquals(Arrays.asList(ValType.I32, ValType.I64), runTy.getResults());
+        }
+    }
 }
@@ -1,9 +1,16 @@
 package net.bluejekyll.wasmtime;
 
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
 import static org.junit.Assert.fail;
 
+import java.time.Duration;
+
 import org.junit.Test;
 
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.WasmVoid;
+
 public class WasmStoreTest {
     @Test
     public void testNewWasmStore() throws Exception {
@@ -14,4 +21,143 @@ public class WasmStoreTest {
             fail();
         }
     }
+
+    @Test
+    public void testFuelNotEnabled() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            assertFalse(store.fuelConsumed().isPresent());
+            assertFalse(store.fuelRemaining().isPresent());
+        }
+    }
+
+    @Test
+    public void testAddFuel() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmConfig config = new WasmConfig().consumeFuel(true);
+                WasmEngine engine = wasm.newWasmEngine(config);
+                WasmStore store = engine.newStore()) {
+            store.addFuel(100);
+            assertEquals(0, store.fuelConsumed().getAsLong());
+            assertEquals(100, store.fuelRemaining().getAsLong());
+
+            store.addFuel(50);
+            assertEquals(150, store.fuelRemaining().getAsLong());
+        }
+    }
+
+    @Test
+    public void testOutOfFuel() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(new WasmConfig().consumeFuel(true), SPIN_WAT,
+                (store, linker) -> store.addFuel(10_000))) {
+            try {
+                wat.getFunction("spin").call(wat.instance, wat.store);
+                fail("spin should have run out of fuel");
+            } catch (WasmOutOfFuelException e) {
+                // expected
+            }
+
+            assertEquals(0, wat.store.fuelRemaining().getAsLong());
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testDeadlineRequiresInterruption() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine(); WasmStore store = engine.newStore()) {
+            store.setDeadline(Duration.ofMillis(10));
+        }
+    }
+
+    private static final String SPIN_WAT = "(module\n"
+            + " (func (export \"spin\")\n"
+            + "  (loop br 0))\n"
+            + " (func (export \"answer\") (result i32)\n"
+            + "  (i32.const 42))\n"
+            + " )";
+
+    @Test
+    public void testDeadlineInterrupts() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(new WasmConfig().epochInterruption(true), SPIN_WAT)) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+
+            try {
+                wat.getFunction("spin").callWithTimeout(instance, store, Duration.ofMillis(50), WasmVoid.class);
+                fail("spin should have been interrupted");
+            } catch (WasmInterruptedException e) {
+                // expected
+            }
+
+            // the timeout does not apply to later calls on the store
+            WasmFunction answer = wat.getFunction("answer");
+            assertEquals(42, answer.call(instance, store, I32.class).intValue());
+        }
+    }
+
+    @Test
+    public void testClearedDeadlineDoesNotInterrupt() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(new WasmConfig().epochInterruption(true), SPIN_WAT)) {
+            WasmStore store = wat.store;
+            WasmInstance instance = wat.instance;
+            WasmFunction answer = wat.getFunction("answer");
+
+            // the deadline fires while no WASM is running, as if the call had just returned
+            store.setDeadline(Duration.ofMillis(10));
+            Thread.sleep(100);
+            store.clearEpochDeadline();
+
+            assertEquals(42, answer.call(instance, store, I32.class).intValue());
+            assertEquals(42, answer.callWithTimeout(instance, store, Duration.ofSeconds(10), I32.class).intValue());
+        }
+    }
+
+    private static final String GROW_WAT = "(module\n"
+            + " (memory (export \"memory\") 1)\n"
+            + " (table 1 funcref)\n"
+            + " (func (export \"grow\") (param i32) (result i32)\n"
+            + "  (memory.grow (local.get 0)))\n"
+            + " (func (export \"grow_table\") (param i32) (result i32)\n"
+            + "  (table.grow (ref.null func) (local.get 0)))\n"
+            + " )";
+
+    @Test
+    public void testMemorySizeLimit() throws Exception {
+        // two 64KiB pages
+        try (WatInstance wat = WatInstance.instantiate(GROW_WAT, (store, linker) -> store.limitMemorySize(2 * 65536))) {
+            WasmFunction grow = wat.getFunction("grow");
+
+            assertEquals(1, grow.call(wat.instance, wat.store, I32.class, new I32(1)).intValue());
+            assertEquals(-1, grow.call(wat.instance, wat.store, I32.class, new I32(1)).intValue());
+        }
+    }
+
+    @Test
+    public void testTableElementsLimit() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(GROW_WAT, (store, linker) -> store.limitTableElements(2))) {
+            WasmFunction grow = wat.getFunction("grow_table");
+
+            assertEquals(1, grow.call(wat.instance, wat.store, I32.class, new I32(1)).intValue());
+            assertEquals(-1, grow.call(wat.instance, wat.store, I32.class, new I32(1)).intValue());
+        }
+    }
+
+    @Test
+    public void testInstancesLimit() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(GROW_WAT, (store, linker) -> store.limitInstances(1))) {
+            try {
+                wat.linker.instantiate(wat.store, wat.module);
+                fail("second instance should exceed the limit");
+            } catch (WasmtimeException e) {
+                // expected
+            }
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testInitialMemoryOverLimit() throws Exception {
+        try (WatInstance wat = WatInstance.instantiate(GROW_WAT, (store, linker) -> store.limitMemorySize(1024))) {
+            fail("the initial memory is over the limit");
+        }
+    }
 }
@@ -0,0 +1,120 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
+import static org.junit.Assert.assertTrue;
+import static org.junit.Assert.fail;
+
+import org.junit.After;
+import org.junit.Before;
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.ty.FuncRef;
+import net.bluejekyll.wasmtime.ty.I32;
+import net.bluejekyll.wasmtime.ty.TableType;
+import net.bluejekyll.wasmtime.ty.ValType;
+
+public class WasmTableTest {
+    private static final String TABLE_WAT = "(module\n"
+            + " (type $ii (func (param i32) (result i32)))\n"
+            + " (table (export \"table\") 2 4 funcref)\n"
+            + " (func $double (param i32) (result i32)\n"
+            + "  (i32.mul (local.get 0) (i32.const 2)))\n"
+            + " (elem (i32.const 0) $double)\n"
+            + " (func (export \"call_at\") (param i32 i32) (result i32)\n"
+            + "  (call_indirect (type $ii) (local.get 1) (local.get 0)))\n"
+            + " (func (export \"double_ref\") (result funcref)\n"
+            + "  (ref.func $double))\n"
+            + " )";
+
+    private WatInstance wat;
+    private WasmStore store;
+    private WasmInstance instance;
+    private WasmTable table;
+
+    public final I32 triple(I32 a) {
+        return new I32(a.field * 3);
+    }
+
+    @Before
+    public void setup() throws Exception {
+        this.wat = WatInstance.instantiate(TABLE_WAT);
+        this.store = this.wat.store;
+        this.instance = this.wat.instance;
+        this.table = this.instance.getTable(this.store, "table").get();
+    }
+
+    @After
+    public void tearDown() throws Exception {
+        this.table.close();
+        this.wat.close();
+    }
+
+    private int callAt(int index, int arg) throws WasmtimeException {
+        WasmFunction callAt = this.instance.getFunction(this.store, "call_at").get();
+        return callAt.call(this.instance, this.store, I32.class, new I32(index), new I32(arg)).intValue();
+    }
+
+    @Test
+    public void testTableType() throws Exception {
+        assertFalse(this.instance.getTable(this.store, "nope").isPresent());
+
+        TableType ty = this.table.getType(this.store);
+        assertEquals(ValType.FuncRef, ty.getElement());
+        assertEquals(2, ty.getMinimum());
+        assertEquals(4, ty.getMaximum().getAsLong());
+        assertEquals(2, this.table.size(this.store));
+    }
+
+    @Test
+    public void testGetFuncRef() throws Exception {
+        FuncRef ref = this.table.getFuncRef(this.store, 0);
+        assertFalse(ref.isNull());
+
+        // guest function pointers can be called from the host
+        try (WasmFunction func = ref.getFunction().get()) {
+            assertEquals(42, func.call(this.instance, this.store, I32.class, new I32(21)).intValue());
+        }
+
+        assertTrue(this.table.getFuncRef(this.store, 1).isNull());
+    }
+
+    @Test
+    public void testSetHostFunction() throws Exception {
+        try (WasmFunction triple = WasmFunction.newFunc(this.store, this, "triple", I32.class)) {
+            this.table.set(this.store, 1, new FuncRef(triple));
+        }
+
+        assertEquals(10, this.callAt(0, 5));
+        assertEquals(15, this.callAt(1, 5));
+    }
+
+    @Test
+    public void testGrow() throws Exception {
+        assertEquals(2, this.table.grow(this.store, 2, FuncRef.NULL));
+        assertEquals(4, this.table.size(this.store));
+        assertTrue(this.table.getFuncRef(this.store, 3).isNull());
+
+        try {
+            this.table.grow(this.store, 1, FuncRef.NULL);
+            fail("table maximum is 4 elements");
+        } catch (WasmtimeException e) {
+            // expected
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testGetOutOfBounds() throws Exception {
+        this.table.get(this.store, 2);
+    }
+
+    @Test
+    public void testFuncRefResult() throws Exception {
+        WasmFunction doubleRef = this.instance.getFunction(this.store, "double_ref").get();
+        FuncRef ref = doubleRef.call(this.instance, this.store, FuncRef.class);
+
+        try (WasmFunction func = ref.getFunction().get()) {
+            assertEquals(8, func.call(this.instance, this.store, I32.class, new I32(4)).intValue());
+        }
+    }
+}
@@ -0,0 +1,102 @@
+package net.bluejekyll.wasmtime;
+
+import static org.junit.Assert.assertEquals;
+
+import org.junit.After;
+import org.junit.Before;
+import org.junit.Test;
+
+public class WasmTypedFunctionTest {
+    private static final String TYPED_WAT = "(module\n"
+            + " (global $count (mut i32) (i32.const 0))\n"
+            + " (func (export \"incr\")\n"
+            + "  (global.set $count (i32.add (global.get $count) (i32.const 1))))\n"
+            + " (func (export \"add_count\") (param i32)\n"
+            + "  (global.set $count (i32.add (global.get $count) (local.get 0))))\n"
+            + " (func (export \"count\") (param i32) (result i32)\n"
+            + "  (i32.add (global.get $count) (local.get 0)))\n"
+            + " (func (export \"add_i32\") (param i32 i32) (result i32)\n"
+            + "  (i32.add (local.get 0) (local.get 1)))\n"
+            + " (func (export \"neg_i64\") (param i64) (result i64)\n"
+            + "  (i64.sub (i64.const 0) (local.get 0)))\n"
+            + " (func (export \"mul_i64\") (param i64 i64) (result i64)\n"
+            + "  (i64.mul (local.get 0) (local.get 1)))\n"
+            + " (func (export \"sqrt\") (param f64) (result f64)\n"
+            + "  (f64.sqrt (local.get 0)))\n"
+            + " (func (export \"div_f64\") (param f64 f64) (result f64)\n"
+            + "  (f64.div (local.get 0) (local.get 1)))\n"
+            + " (func (export \"trap\") (param i32) (result i32)\n"
+            + "  (unreachable))\n"
+            + " )";
+
+    private WatInstance wat;
+    private WasmStore store;
+    private WasmInstance instance;
+
+    @Before
+    public void setup() throws Exception {
+        this.wat = WatInstance.instantiate(TYPED_WAT);
+        this.store = this.wat.store;
+        this.instance = this.wat.instance;
+    }
+
+    @After
+    public void tearDown() throws Exception {
+        this.wat.close();
+    }
+
+    private WasmFunction function(String name) {
+        return this.wat.getFunction(name);
+    }
+
+    @Test
+    public void testI32() throws Exception {
+        try (WasmTypedFunction.VoidToVoid incr = WasmTypedFunction.VoidToVoid.of(this.store, function("incr"));
+                WasmTypedFunction.I32ToVoid addCount = WasmTypedFunction.I32ToVoid.of(this.store,
+                        function("add_count"));
+                WasmTypedFunction.I32ToI32 count = WasmTypedFunction.I32ToI32.of(this.store, function("count"));
+                WasmTypedFunction.I32I32ToI32 add = WasmTypedFunction.I32I32ToI32.of(this.store,
+                        function("add_i32"))) {
+            for (int i = 0; i < 1000; i++) {
+                incr.call(this.store);
+            }
+            addCount.call(this.store, 10);
+
+            assertEquals(1011, count.call(this.store, 1));
+            assertEquals(3, add.call(this.store, 1, 2));
+            assertEquals(Integer.MIN_VALUE, add.call(this.store, Integer.MAX_VALUE, 1));
+        }
+    }
+
+    @Test
+    public void testI64() throws Exception {
+        try (WasmTypedFunction.I64ToI64 neg = WasmTypedFunction.I64ToI64.of(this.store, function("neg_i64"));
+                WasmTypedFunction.I64I64ToI64 mul = WasmTypedFunction.I64I64ToI64.of(this.store,
+                        function("mul_i64"))) {
+            assertEquals(-5L, neg.call(this.store, 5L));
+            assertEquals(1L << 40, mul.call(this.store, 1L << 20, 1L << 20));
+        }
+    }
+
+    @Test
+    public void testF64() throws Exception {
+        try (WasmTypedFunction.F64ToF64 sqrt = WasmTypedFunction.F64ToF64.of(this.store, function("sqrt"));
+                WasmTypedFunction.F64F64ToF64 div = WasmTypedFunction.F64F64ToF64.of(this.store,
+                        function("div_f64"))) {
+            assertEquals(3.0, sqrt.call(this.store, 9.0), 0.0);
+            assertEquals(2.5, div.call(this.store, 5.0, 2.0), 0.0);
+        }
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testSignatureMismatch() throws Exception {
+        WasmTypedFunction.I64ToI64.of(this.store, function("add_i32"));
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testTrap() throws Exception {
+        try (WasmTypedFunction.I32ToI32 trap = WasmTypedFunction.I32ToI32.of(this.store, function("trap"))) {
+            trap.call(this.store, 1);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+package net.bluejekyll.wasmtime;
+
+/**
+ * A module compiled from WAT and instantiated in a new engine and store, for
+ * tests. Closing this closes the instance, module, linker, store and engine.
+ */
+final class WatInstance implements AutoCloseable {
+    /** Prepares the store and linker, e.g. imports or limits, before instantiation */
+    interface Setup {
+        void setup(WasmStore store, WasmLinker linker) throws Exception;
+    }
+
+    final WasmEngine engine;
+    final WasmStore store;
+    final WasmLinker linker;
+    final WasmModule module;
+    final WasmInstance instance;
+
+    private WatInstance(WasmEngine engine, WasmStore store, WasmLinker linker, WasmModule module,
+            WasmInstance instance) {
+        this.engine = engine;
+        this.store = store;
+        this.linker = linker;
+        this.module = module;
+        this.instance = instance;
+    }
+
+    static WatInstance instantiate(String wat) throws Exception {
+        return instantiate(null, wat, (store, linker) -> {
+        });
+    }
+
+    static WatInstance instantiate(String wat, Setup setup) throws Exception {
+        return instantiate(null, wat, setup);
+    }
+
+    static WatInstance instantiate(WasmConfig config, String wat) throws Exception {
+        return instantiate(config, wat, (store, linker) -> {
+        });
+    }
+
+    /**
+     * @param config the config for the engine, it is consumed, or null for the
+     *               default engine
+     */
+    static WatInstance instantiate(WasmConfig config, String wat, Setup setup) throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        WasmEngine engine = config == null ? wasm.newWasmEngine() : wasm.newWasmEngine(config);
+        WasmStore store = null;
+        WasmLinker linker = null;
+        WasmModule module = null;
+
+        try {
+            store = engine.newStore();
+            linker = engine.newLinker();
+            module = engine.newModule(wat.getBytes());
+
+            setup.setup(store, linker);
+            WasmInstance instance = linker.instantiate(store, module);
+            return new WatInstance(engine, store, linker, module, instance);
+        } catch (Exception e) {
+            close(module, linker, store, engine);
+            throw e;
+        }
+    }
+
+    /** The exported function, which must exist */
+    WasmFunction getFunction(String name) {
+        return this.instance.getFunction(this.store, name)
+                .orElseThrow(() -> new AssertionError(String.format("missing exported function: %s", name)));
+    }
+
+    private static void close(AutoCloseable... closeables) throws Exception {
+        for (AutoCloseable closeable : closeables) {
+            if (closeable != null)
+                closeable.close();
+        }
+    }
+
+    @Override
+    public void close() throws Exception {
+        close(this.instance, this.module, this.linker, this.store, this.engine);
+    }
+}
@@ -7,4 +7,7 @@ public interface TestImportProxy extends WasmImportable {
 
     @WasmImport(name = "add_f32")
     float addFloats(float a, float b);
+
+    @WasmImport(name = "add_i64")
+    Long addLongs(Long a, Long b);
 }
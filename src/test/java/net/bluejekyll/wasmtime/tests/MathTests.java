@@ -6,6 +6,7 @@ import net.bluejekyll.wasmtime.ty.*;
 import org.junit.Test;
 
 import java.io.UnsupportedEncodingException;
+import java.math.BigInteger;
 import java.nio.ByteBuffer;
 import java.util.Optional;
 
@@ -58,6 +59,42 @@ public class MathTests {
         }
     }
 
+    @Test
+    public void testAddU32() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine();
+                WasmModule module = engine.newModule(TestUtil.MATH_PATH);
+                WasmStore store = engine.newStore();
+                WasmLinker linker = engine.newLinker()) {
+            WasmInstance instance = linker.instantiate(store, module);
+            Optional<WasmFunction> func = instance.getFunction(store, "add_u32");
+
+            assertTrue("add_u32 isn't present in the module", func.isPresent());
+            WasmFunction function = func.get();
+
+            U32 ret = function.call(instance, store, U32.class, u32(0x7FFF_FFFFL), u32(0x7FFF_FFFFL));
+            assertEquals(0xFFFF_FFFEL, ret.field);
+        }
+    }
+
+    @Test
+    public void testAddU64() throws Exception {
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine();
+                WasmModule module = engine.newModule(TestUtil.MATH_PATH);
+                WasmStore store = engine.newStore();
+                WasmLinker linker = engine.newLinker()) {
+            WasmInstance instance = linker.instantiate(store, module);
+            Optional<WasmFunction> func = instance.getFunction(store, "add_u64");
+
+            assertTrue("add_u64 isn't present in the module", func.isPresent());
+            WasmFunction function = func.get();
+
+            U64 ret = function.call(instance, store, U64.class, u64(Long.MAX_VALUE), u64(Long.MAX_VALUE));
+            assertEquals(new BigInteger("18446744073709551614"), ret.getField());
+        }
+    }
+
     @Test
     public void testAddF32() throws Exception {
         Wasmtime wasm = new Wasmtime();
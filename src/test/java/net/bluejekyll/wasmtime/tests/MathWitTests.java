@@ -2,11 +2,14 @@ package net.bluejekyll.wasmtime.tests;
 
 import net.bluejekyll.wasmtime.*;
 import net.bluejekyll.wasmtime.ty.*;
+import net.bluejekyll.wasmtime.wit.WitInstance;
+import net.bluejekyll.wasmtime.wit.WitInterface;
 
 import org.junit.Test;
 
 import java.io.UnsupportedEncodingException;
 import java.nio.ByteBuffer;
+import java.nio.file.Paths;
 import java.util.Optional;
 
 import static org.junit.Assert.*;
@@ -57,4 +60,24 @@ public class MathWitTests {
             assertEquals(ret.field, 5);
         }
     }
+
+    @Test
+    public void testWitUnsigned() throws Exception {
+        WitInterface wit = WitInterface.parse(Paths.get("tests/math-wit/src/math.wit"));
+
+        Wasmtime wasm = new Wasmtime();
+        try (WasmEngine engine = wasm.newWasmEngine();
+                WasmModule module = engine.newModule(TestUtil.MATH_WIT_PATH);
+                WasmStore store = engine.newStore();
+                WasmLinker linker = engine.newLinker()) {
+            WasmInstance instance = linker.instantiate(store, module);
+
+            try (WitInstance math = new WitInstance(wit, instance, store)) {
+                assertEquals(5, math.call("add-i32", 3, 2));
+                // u32 values above Integer.MAX_VALUE are longs
+                assertEquals(0xFFFF_FFFEL, math.call("add-u32", 0x7FFF_FFFFL, 0x7FFF_FFFFL));
+                assertEquals(5L, math.call("add-u64", 3L, 2L));
+            }
+        }
+    }
 }
@@ -0,0 +1,168 @@
+package net.bluejekyll.wasmtime.wit;
+
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertNull;
+import static org.junit.Assert.fail;
+
+import java.util.Arrays;
+import java.util.Map;
+
+import org.junit.After;
+import org.junit.Before;
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.WasmEngine;
+import net.bluejekyll.wasmtime.WasmInstance;
+import net.bluejekyll.wasmtime.WasmLinker;
+import net.bluejekyll.wasmtime.WasmModule;
+import net.bluejekyll.wasmtime.WasmStore;
+import net.bluejekyll.wasmtime.Wasmtime;
+import net.bluejekyll.wasmtime.WasmtimeException;
+import net.bluejekyll.wasmtime.ty.I32;
+
+public class WitInstanceTest {
+    private static final String WIT = "record point { x: s32, y: s32 }\n"
+            + "enum sign-kind { negative, zero, positive }\n"
+            + "variant shape { circle(f32), square(s32) }\n"
+            + "string-len: function(s: string) -> u32\n"
+            + "echo: function(s: string) -> string\n"
+            + "add-points: function(a: point, b: point) -> point\n"
+            + "sum: function(values: list<u32>) -> u64\n"
+            + "sign: function(x: s32) -> sign-kind\n"
+            + "shape-size: function(s: shape) -> f64\n"
+            + "missing: function()\n"
+            + "tag: function(name: string, values: list<u32>) -> u32\n";
+
+    // the module is what wit-bindgen would generate for the interface, with a bump allocator
+    private static final String WAT = "(module\n"
+            + " (memory (export \"memory\") 1)\n"
+            + " (global $heap (mut i32) (i32.const 1024))\n"
+            + " (global $frees (export \"frees\") (mut i32) (i32.const 0))\n"
+            + " (func (export \"canonical_abi_realloc\") (param i32 i32 i32 i32) (result i32)\n"
+            + "  (local $ptr i32)\n"
+            + "  (local.set $ptr (i32.and (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))\n"
+            + "   (i32.sub (i32.const 0) (local.get 2))))\n"
+            + "  (global.set $heap (i32.add (local.get $ptr) (local.get 3)))\n"
+            + "  (local.get $ptr))\n"
+            + " (func (export \"canonical_abi_free\") (param i32 i32 i32)\n"
+            + "  (global.set $frees (i32.add (global.get $frees) (i32.const 1))))\n"
+            + " (func (export \"string-len\") (param i32 i32) (result i32)\n"
+            + "  (local.get 1))\n"
+            + " (func (export \"echo\") (param i32 i32) (result i32)\n"
+            + "  (i32.store (i32.const 512) (local.get 0))\n"
+            + "  (i32.store (i32.const 516) (local.get 1))\n"
+            + "  (i32.const 512))\n"
+            + " (func (export \"add-points\") (param i32 i32 i32 i32) (result i32)\n"
+            + "  (i32.store (i32.const 512) (i32.add (local.get 0) (local.get 2)))\n"
+            + "  (i32.store (i32.const 516) (i32.add (local.get 1) (local.get 3)))\n"
+            + "  (i32.const 512))\n"
+            + " (func (export \"sum\") (param $ptr i32) (param $len i32) (result i64)\n"
+            + "  (local $acc i64)\n"
+            + "  (block $done\n"
+            + "   (loop $next\n"
+            + "    (br_if $done (i32.eqz (local.get $len)))\n"
+            + "    (local.set $acc (i64.add (local.get $acc) (i64.extend_i32_u (i32.load (local.get $ptr)))))\n"
+            + "    (local.set $ptr (i32.add (local.get $ptr) (i32.const 4)))\n"
+            + "    (local.set $len (i32.sub (local.get $len) (i32.const 1)))\n"
+            + "    (br $next)))\n"
+            + "  (local.get $acc))\n"
+            + " (func (export \"sign\") (param i32) (result i32)\n"
+            + "  (i32.add (i32.sub (i32.gt_s (local.get 0) (i32.const 0)) (i32.lt_s (local.get 0) (i32.const 0)))\n"
+            + "   (i32.const 1)))\n"
+            + " (func (export \"shape-size\") (param i32 i32) (result f64)\n"
+            + "  (if (result f64) (i32.eqz (local.get 0))\n"
+            + "   (then (f64.promote_f32 (f32.reinterpret_i32 (local.get 1))))\n"
+            + "   (else (f64.convert_i32_s (local.get 1)))))\n"
+            + " )";
+
+    private WasmEngine engine;
+    private WasmStore store;
+    private WasmLinker linker;
+    private WasmModule module;
+    private WasmInstance instance;
+    private WitInstance wit;
+
+    @Before
+    public void setup() throws Exception {
+        this.engine = new Wasmtime().newWasmEngine();
+        this.store = this.engine.newStore();
+        this.linker = this.engine.newLinker();
+        this.module = this.engine.newModule(WAT.getBytes());
+        this.instance = this.linker.instantiate(this.store, this.module);
+        this.wit = new WitInstance(WitInterface.parse(WIT), this.instance, this.store);
+    }
+
+    @After
+    public void tearDown() {
+        this.wit.close();
+        this.instance.close();
+        this.module.close();
+        this.linker.close();
+        this.store.close();
+        this.engine.close();
+    }
+
+    private int frees() throws Exception {
+        return ((I32) this.instance.getGlobal(this.store, "frees").get().get(this.store)).field;
+    }
+
+    @Test
+    public void testStrings() throws Exception {
+        // the length is in UTF-8 bytes
+        assertEquals(6L, this.wit.call("string-len", "héllo"));
+
+        assertEquals("hello wasm", this.wit.call("echo", "hello wasm"));
+        // the returned string is owned by the caller
+        assertEquals(1, this.frees());
+    }
+
+    @Test
+    public void testRecords() throws Exception {
+        Object point = this.wit.call("add-points", Map.of("x", 1, "y", 2), Map.of("x", 3, "y", -4));
+        assertEquals(Map.of("x", 4, "y", -2), point);
+    }
+
+    @Test
+    public void testListOfUnsigned() throws Exception {
+        assertEquals(0x1_0000_0000L, this.wit.call("sum", Arrays.asList(1L, 0xFFFF_FFFFL)));
+        assertEquals(0L, this.wit.call("sum", Arrays.asList()));
+    }
+
+    @Test
+    public void testVariants() throws Exception {
+        assertEquals(new WitVariant("negative"), this.wit.call("sign", -5));
+        assertEquals(new WitVariant("positive"), this.wit.call("sign", 7));
+
+        // the f32 of the circle shares the i32 of the square
+        assertEquals(1.5, (Double) this.wit.call("shape-size", new WitVariant("circle", 1.5f)), 0.0);
+        assertEquals(3.0, (Double) this.wit.call("shape-size", new WitVariant("square", 3)), 0.0);
+    }
+
+    @Test(expected = IllegalArgumentException.class)
+    public void testOutOfRange() throws Exception {
+        this.wit.call("sum", Arrays.asList(-1L));
+    }
+
+    @Test
+    public void testFreesParamsWhenLoweringFails() throws Exception {
+        try {
+            this.wit.call("tag", "name", Arrays.asList(-1L));
+            fail("-1 is not a u32");
+        } catch (IllegalArgumentException e) {
+            // expected
+        }
+
+        // the string was copied into the module before the list failed
+        assertEquals(1, this.frees());
+    }
+
+    @Test(expected = IllegalArgumentException.class)
+    public void testWrongArgs() throws Exception {
+        this.wit.call("add-points", Map.of("x", 1));
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testMissingExport() throws Exception {
+        assertNull(this.wit.call("missing"));
+    }
+}
@@ -0,0 +1,74 @@
+package net.bluejekyll.wasmtime.wit;
+
+import static org.junit.Assert.assertEquals;
+import static org.junit.Assert.assertFalse;
+import static org.junit.Assert.assertTrue;
+
+import org.junit.Test;
+
+import net.bluejekyll.wasmtime.WasmtimeException;
+
+public class WitInterfaceTest {
+    private static final String WIT = "// types may be used before they are defined\n"
+            + "add-points: function(a: point, b: point) -> point\n"
+            + "record point { x: s32, y: s32, }\n"
+            + "/// doc comments are ignored\n"
+            + "variant shape { circle(f32), square(s32), none }\n"
+            + "enum color { red, green }\n"
+            + "type points = list<point>\n"
+            + "centroid: function(points: points) -> point\n"
+            + "log: function(msg: string)\n";
+
+    @Test
+    public void testParse() throws Exception {
+        WitInterface wit = WitInterface.parse(WIT);
+
+        WitType point = wit.getType("point").get();
+        assertEquals(WitType.Kind.RECORD, point.getKind());
+        assertEquals(2, point.getFields().size());
+        assertEquals(WitType.S32, point.getFields().get(1).getType());
+
+        WitType shape = wit.getType("shape").get();
+        assertEquals(WitType.Kind.VARIANT, shape.getKind());
+        assertEquals(WitType.F32, shape.getCases().get(0).getType().get());
+        assertFalse(shape.getCases().get(2).getType().isPresent());
+
+        WitType color = wit.getType("color").get();
+        assertEquals(WitType.Kind.VARIANT, color.getKind());
+        assertEquals("green", color.getCases().get(1).getName());
+
+        WitFunction addPoints = wit.getFunction("add-points").get();
+        assertEquals(2, addPoints.getParams().size());
+        assertEquals(WitType.Kind.RECORD, addPoints.getResult().get().getKind());
+
+        // aliases are resolved to the type
+        WitFunction centroid = wit.getFunction("centroid").get();
+        WitType points = centroid.getParams().get(0).getType();
+        assertEquals(WitType.Kind.LIST, points.getKind());
+        assertEquals("point", points.getElement().get().toString());
+
+        assertFalse(wit.getFunction("log").get().getResult().isPresent());
+        assertEquals(3, wit.getFunctions().size());
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testUndefinedType() throws Exception {
+        WitInterface.parse("f: function(a: nope)");
+    }
+
+    @Test(expected = WasmtimeException.class)
+    public void testRecursiveType() throws Exception {
+        WitInterface.parse("record a { b: b }\nrecord b { a: list<a> }");
+    }
+
+    @Test
+    public void testSyntaxError() throws Exception {
+        try {
+            WitInterface.parse("f: function(a: s32) -> s32\ng: function(a s32)");
+        } catch (WasmtimeException e) {
+            assertTrue(e.getMessage(), e.getMessage().contains("line 2"));
+            return;
+        }
+        throw new AssertionError("expected a syntax error");
+    }
+}
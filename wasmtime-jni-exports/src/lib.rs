@@ -10,6 +10,8 @@ use std::{borrow::Cow, slice};
 pub const MEMORY_EXPORT: &str = "memory";
 pub const ALLOC_EXPORT: &str = "__alloc_bytes";
 pub const DEALLOC_EXPORT: &str = "__dealloc_bytes";
+pub const ALLOC_ALIGNED_EXPORT: &str = "__alloc_aligned";
+pub const DEALLOC_ALIGNED_EXPORT: &str = "__dealloc_aligned";
 
 /// Allocates size in bytes of `memory`, offset to area returned.
 ///
@@ -49,6 +51,54 @@ pub unsafe extern "C" fn __dealloc_bytes(ptr: u32, size: u32) {
     }
 }
 
+/// Allocates size in bytes of `memory` aligned to `align`, offset to area returned.
+///
+/// This is used for slices of elements, e.g. `&[i32]` or `&[f64]`, which must be aligned to the element size.
+///
+/// # Returns
+///
+/// Offset from start of `memory` export in WASM to the region, or 0 if unable to allocate.
+///
+/// # Safety
+///
+/// See `__alloc_bytes`, the region must be freed with `__dealloc_aligned` and the same `align`.
+#[no_mangle]
+//#[cfg(target_arch = "wasm32")]
+pub unsafe extern "C" fn __alloc_aligned(size: u32, align: u32) -> i32 {
+    let layout = match Layout::from_size_align(size as usize, align as usize) {
+        Ok(layout) => layout,
+        Err(_) => return 0,
+    };
+
+    // empty slices are never freed, see `__dealloc_aligned`, the same as `NonNull::dangling`
+    if layout.size() == 0 {
+        return layout.align() as i32;
+    }
+
+    let ptr = alloc::alloc(layout) as i32;
+
+    debug_assert_ne!(0, ptr);
+    ptr
+}
+
+/// Frees ptr from `memory` in WASM that was allocated with `align`
+///
+/// The returned slices of elements, e.g. a `Vec<i32>` as `&[i32]`, are freed with this after being copied to Java.
+///
+/// # Safety
+///
+/// Must be a pointer to data allocated with the __alloc_aligned, or by the global allocator with the same layout
+#[no_mangle]
+//#[cfg(target_arch = "wasm32")]
+pub unsafe extern "C" fn __dealloc_aligned(ptr: u32, size: u32, align: u32) {
+    if ptr != 0 && size != 0 {
+        // an invalid layout could not have been allocated, the same as `__alloc_aligned` it's ignored
+        if let Ok(layout) = Layout::from_size_align(size as usize, align as usize) {
+            alloc::dealloc(ptr as *mut u8, layout);
+        }
+    }
+}
+
 /// Data that was allocated inside a WASM module
 pub trait WasmAllocated: Sized {
     /// Return the WASM offset pointer
@@ -0,0 +1,464 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use anyhow::{ensure, Context, Error};
+use jni::descriptors::Desc;
+use jni::objects::{
+    GlobalRef, JByteBuffer, JClass, JFieldID, JMethodID, JObject, JStaticMethodID, JValue,
+};
+use jni::signature::{JavaType, Primitive};
+use jni::sys::{jfieldID, jmethodID};
+use jni::JNIEnv;
+use once_cell::sync::OnceCell;
+
+use crate::wasm_value::JavaPrimitive;
+
+static CACHE: OnceCell<JniCache> = OnceCell::new();
+
+/// Populates the cache, this is called from `JNI_OnLoad` where the classes of this library can be found
+pub(crate) fn init(env: &JNIEnv<'_>) -> Result<(), Error> {
+    get(env).map(|_| ())
+}
+
+/// The cache is populated in `JNI_OnLoad`, if that failed the lookup is retried with this env
+pub(crate) fn get(env: &JNIEnv<'_>) -> Result<&'static JniCache, Error> {
+    CACHE.get_or_try_init(|| {
+        JniCache::new(env).map_err(|err| {
+            // a failed lookup leaves an exception pending, e.g. NoClassDefFoundError, any JNI call after that is
+            //  illegal, including loading the library or throwing the error
+            if env.exception_check().unwrap_or(true) {
+                env.exception_clear().ok();
+            }
+            err
+        })
+    })
+}
+
+/// Classes, methods and fields used on every call between Java and WASM, to avoid looking them up per call.
+///
+/// The classes are held by a GlobalRef, which keeps the IDs of their methods and fields valid.
+pub(crate) struct JniCache {
+    pub object: CachedClass,
+    pub method_invoke: MethodId,
+    pub void: CachedClass,
+    pub void_primitive: CachedClass,
+    pub wasm_void: CachedClass,
+    pub wasm_function: CachedClass,
+    pub wasm_function_ctor: MethodId,
+    pub wasm_function_get_ptr: MethodId,
+    pub i32: WasmTypeClass,
+    pub i64: WasmTypeClass,
+    pub u32: WasmTypeClass,
+    pub u64: WasmTypeClass,
+    pub f32: WasmTypeClass,
+    pub f64: WasmTypeClass,
+    pub v128: WasmTypeClass,
+    pub func_ref: WasmTypeClass,
+    pub extern_ref: WasmTypeClass,
+    pub boolean: BoxedClass,
+    pub byte: BoxedClass,
+    pub character: BoxedClass,
+    pub short: BoxedClass,
+    pub integer: BoxedClass,
+    pub long: BoxedClass,
+    pub float: BoxedClass,
+    pub double: BoxedClass,
+    pub byte_array: CachedClass,
+    pub int_array: CachedClass,
+    pub long_array: CachedClass,
+    pub float_array: CachedClass,
+    pub double_array: CachedClass,
+    pub byte_buffer: ByteBufferClass,
+}
+
+impl JniCache {
+    fn new(env: &JNIEnv<'_>) -> Result<Self, Error> {
+        let void_primitive = env
+            .get_static_field("java/lang/Void", "TYPE", "Ljava/lang/Class;")?
+            .l()?;
+        let wasm_function = CachedClass::new(env, "net/bluejekyll/wasmtime/WasmFunction")?;
+
+        Ok(Self {
+            object: CachedClass::new(env, "java/lang/Object")?,
+            method_invoke: MethodId::new(
+                env,
+                "java/lang/reflect/Method",
+                "invoke",
+                "(Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;",
+            )?,
+            void: CachedClass::new(env, "java/lang/Void")?,
+            void_primitive: CachedClass(env.new_global_ref(void_primitive)?),
+            wasm_void: CachedClass::new(env, "net/bluejekyll/wasmtime/ty/WasmVoid")?,
+            wasm_function_ctor: MethodId::new(env, wasm_function.as_class(), "<init>", "(J)V")?,
+            wasm_function_get_ptr: MethodId::new(env, wasm_function.as_class(), "getPtr", "()J")?,
+            wasm_function,
+            i32: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/I32", "I")?,
+            i64: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/I64", "J")?,
+            u32: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/U32", "J")?,
+            u64: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/U64", "J")?,
+            f32: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/F32", "F")?,
+            f64: WasmTypeClass::new(env, "net/bluejekyll/wasmtime/ty/F64", "D")?,
+            v128: WasmTypeClass::with_field(env, "net/bluejekyll/wasmtime/ty/V128", "bytes", "[B")?,
+            func_ref: WasmTypeClass::with_field(
+                env,
+                "net/bluejekyll/wasmtime/ty/FuncRef",
+                "function",
+                "Lnet/bluejekyll/wasmtime/WasmFunction;",
+            )?,
+            extern_ref: WasmTypeClass::with_field(
+                env,
+                "net/bluejekyll/wasmtime/ty/ExternRef",
+                "value",
+                "Ljava/lang/Object;",
+            )?,
+            boolean: BoxedClass::new(env, "java/lang/Boolean", "booleanValue", "Z")?,
+            byte: BoxedClass::new(env, "java/lang/Byte", "byteValue", "B")?,
+            character: BoxedClass::new(env, "java/lang/Character", "charValue", "C")?,
+            short: BoxedClass::new(env, "java/lang/Short", "shortValue", "S")?,
+            integer: BoxedClass::new(env, "java/lang/Integer", "intValue", "I")?,
+            long: BoxedClass::new(env, "java/lang/Long", "longValue", "J")?,
+            float: BoxedClass::new(env, "java/lang/Float", "floatValue", "F")?,
+            double: BoxedClass::new(env, "java/lang/Double", "doubleValue", "D")?,
+            byte_array: CachedClass::new(env, "[B")?,
+            int_array: CachedClass::new(env, "[I")?,
+            long_array: CachedClass::new(env, "[J")?,
+            float_array: CachedClass::new(env, "[F")?,
+            double_array: CachedClass::new(env, "[D")?,
+            byte_buffer: ByteBufferClass::new(env)?,
+        })
+    }
+
+    /// All of the boxed classes, with the primitive they hold
+    pub fn boxed(&self) -> [(&BoxedClass, JavaPrimitive); 8] {
+        [
+            (&self.boolean, JavaPrimitive::Boolean),
+            (&self.byte, JavaPrimitive::Byte),
+            (&self.character, JavaPrimitive::Char),
+            (&self.short, JavaPrimitive::Short),
+            (&self.integer, JavaPrimitive::Int),
+            (&self.long, JavaPrimitive::Long),
+            (&self.float, JavaPrimitive::Float),
+            (&self.double, JavaPrimitive::Double),
+        ]
+    }
+
+    pub fn boxed_class(&self, primitive: JavaPrimitive) -> &BoxedClass {
+        match primitive {
+            JavaPrimitive::Boolean => &self.boolean,
+            JavaPrimitive::Byte => &self.byte,
+            JavaPrimitive::Char => &self.character,
+            JavaPrimitive::Short => &self.short,
+            JavaPrimitive::Int => &self.integer,
+            JavaPrimitive::Long => &self.long,
+            JavaPrimitive::Float => &self.float,
+            JavaPrimitive::Double => &self.double,
+        }
+    }
+}
+
+pub(crate) struct CachedClass(GlobalRef);
+
+impl CachedClass {
+    fn new(env: &JNIEnv<'_>, name: &str) -> Result<Self, Error> {
+        let class = env
+            .find_class(name)
+            .with_context(|| format!("class not found: {}", name))?;
+        Ok(Self(env.new_global_ref(class)?))
+    }
+
+    pub fn as_class(&self) -> JClass<'_> {
+        JClass::from(self.0.as_obj())
+    }
+}
+
+/// A method ID, valid for as long as the class is loaded
+#[derive(Clone, Copy)]
+pub(crate) struct MethodId(jmethodID);
+
+// the IDs are not tied to a thread, the JNIEnv they were looked up with is not needed to use them
+unsafe impl Send for MethodId {}
+unsafe impl Sync for MethodId {}
+
+impl MethodId {
+    fn new<'a, 'c>(
+        env: &JNIEnv<'a>,
+        class: impl Desc<'a, JClass<'c>>,
+        name: &str,
+        sig: &str,
+    ) -> Result<Self, Error> {
+        let id = env
+            .get_method_id(class, name, sig)
+            .with_context(|| format!("method not found: {}{}", name, sig))?;
+        Ok(id.into())
+    }
+
+    pub fn get(self) -> JMethodID<'static> {
+        JMethodID::from(self.0)
+    }
+
+    /// Only valid if this was created from a JStaticMethodID
+    pub fn get_static(self) -> JStaticMethodID<'static> {
+        JStaticMethodID::from(self.0)
+    }
+}
+
+impl From<JMethodID<'_>> for MethodId {
+    fn from(id: JMethodID<'_>) -> Self {
+        Self(id.into_inner())
+    }
+}
+
+impl From<JStaticMethodID<'_>> for MethodId {
+    fn from(id: JStaticMethodID<'_>) -> Self {
+        Self(id.into_inner())
+    }
+}
+
+/// A field ID, valid for as long as the class is loaded
+#[derive(Clone, Copy)]
+pub(crate) struct FieldId(jfieldID);
+
+unsafe impl Send for FieldId {}
+unsafe impl Sync for FieldId {}
+
+impl FieldId {
+    pub fn get(self) -> JFieldID<'static> {
+        JFieldID::from(self.0)
+    }
+}
+
+/// A WasmType with the constructor taking the value and the field holding the value
+pub(crate) struct WasmTypeClass {
+    class: CachedClass,
+    ctor: MethodId,
+    field: FieldId,
+    field_ty: JavaType,
+}
+
+impl WasmTypeClass {
+    /// For the numeric types, which have a public field named `field`
+    fn new(env: &JNIEnv<'_>, name: &str, sig: &str) -> Result<Self, Error> {
+        Self::with_field(env, name, "field", sig)
+    }
+
+    fn with_field(env: &JNIEnv<'_>, name: &str, field: &str, sig: &str) -> Result<Self, Error> {
+        let class = CachedClass::new(env, name)?;
+        let ctor = MethodId::new(env, class.as_class(), "<init>", &format!("({})V", sig))?;
+        let field = env
+            .get_field_id(class.as_class(), field, sig)
+            .with_context(|| format!("field not found: {}.{}", name, field))?;
+
+        Ok(Self {
+            class,
+            ctor,
+            field: FieldId(field.into_inner()),
+            field_ty: JavaType::from_str(sig)?,
+        })
+    }
+
+    pub fn as_class(&self) -> JClass<'_> {
+        self.class.as_class()
+    }
+
+    pub fn is_instance<'j>(&self, env: &JNIEnv<'j>, obj: JObject<'j>) -> jni::errors::Result<bool> {
+        env.is_instance_of(obj, self.as_class())
+    }
+
+    /// Constructs a new instance holding the value
+    pub fn new_object<'j>(
+        &self,
+        env: &JNIEnv<'j>,
+        value: JValue<'_>,
+    ) -> jni::errors::Result<JObject<'j>> {
+        env.new_object_unchecked(self.as_class(), self.ctor.get(), &[value])
+    }
+
+    /// Reads the value held by an instance of this class
+    pub fn get_value<'j>(
+        &self,
+        env: &JNIEnv<'j>,
+        obj: JObject<'j>,
+    ) -> jni::errors::Result<JValue<'j>> {
+        env.get_field_unchecked(obj, self.field.get(), self.field_ty.clone())
+    }
+}
+
+/// A boxed primitive, e.g. Integer, along with the class of the primitive type itself, e.g. int
+pub(crate) struct BoxedClass {
+    class: CachedClass,
+    primitive: CachedClass,
+    value_of: MethodId,
+    value: MethodId,
+    ty: JavaType,
+}
+
+impl BoxedClass {
+    fn new(env: &JNIEnv<'_>, name: &str, value_method: &str, sig: &str) -> Result<Self, Error> {
+        let class = CachedClass::new(env, name)?;
+        let primitive = env
+            .get_static_field(class.as_class(), "TYPE", "Ljava/lang/Class;")?
+            .l()?;
+        let value_of_sig = format!("({})L{};", sig, name);
+        let value_of = env
+            .get_static_method_id(class.as_class(), "valueOf", &value_of_sig)
+            .with_context(|| format!("method not found: valueOf{}", value_of_sig))?;
+        let value = MethodId::new(env, class.as_class(), value_method, &format!("(){}", sig))?;
+
+        Ok(Self {
+            primitive: CachedClass(env.new_global_ref(primitive)?),
+            class,
+            value_of: value_of.into(),
+            value,
+            ty: JavaType::from_str(sig)?,
+        })
+    }
+
+    /// True for the boxed class or the primitive type, subclasses are not possible
+    pub fn is_class<'j>(&self, env: &JNIEnv<'j>, clazz: JClass<'j>) -> jni::errors::Result<bool> {
+        Ok(env.is_same_object(clazz, self.class.as_class())?
+            || env.is_same_object(clazz, self.primitive.as_class())?)
+    }
+
+    pub fn is_instance<'j>(&self, env: &JNIEnv<'j>, obj: JObject<'j>) -> jni::errors::Result<bool> {
+        env.is_instance_of(obj, self.class.as_class())
+    }
+
+    /// Boxes the primitive value with `valueOf`
+    pub fn new_object<'j>(
+        &self,
+        env: &JNIEnv<'j>,
+        value: JValue<'_>,
+    ) -> jni::errors::Result<JObject<'j>> {
+        env.call_static_method_unchecked(
+            self.class.as_class(),
+            self.value_of.get_static(),
+            JavaType::Object(String::from("java/lang/Object")),
+            &[value],
+        )?
+        .l()
+    }
+
+    /// Unboxes the primitive value
+    pub fn get_value<'j>(
+        &self,
+        env: &JNIEnv<'j>,
+        obj: JObject<'j>,
+    ) -> jni::errors::Result<JValue<'j>> {
+        env.call_method_unchecked(obj, self.value.get(), self.ty.clone(), &[])
+    }
+}
+
+/// java.nio.ByteBuffer, the bytes from the position to the limit are passed to WASM
+pub(crate) struct ByteBufferClass {
+    class: CachedClass,
+    position: MethodId,
+    limit: MethodId,
+    is_direct: MethodId,
+    duplicate: MethodId,
+    get: MethodId,
+    wrap: MethodId,
+}
+
+impl ByteBufferClass {
+    const SIG: &'static str = "Ljava/nio/ByteBuffer;";
+
+    fn new(env: &JNIEnv<'_>) -> Result<Self, Error> {
+        let class = CachedClass::new(env, "java/nio/ByteBuffer")?;
+        let wrap_sig = format!("([B){}", Self::SIG);
+        let wrap = env
+            .get_static_method_id(class.as_class(), "wrap", &wrap_sig)
+            .with_context(|| format!("method not found: wrap{}", wrap_sig))?;
+
+        Ok(Self {
+            position: MethodId::new(env, class.as_class(), "position", "()I")?,
+            limit: MethodId::new(env, class.as_class(), "limit", "()I")?,
+            is_direct: MethodId::new(env, class.as_class(), "isDirect", "()Z")?,
+            duplicate: MethodId::new(
+                env,
+                class.as_class(),
+                "duplicate",
+                &format!("(){}", Self::SIG),
+            )?,
+            get: MethodId::new(env, class.as_class(), "get", &wrap_sig)?,
+            wrap: wrap.into(),
+            class,
+        })
+    }
+
+    pub fn as_class(&self) -> JClass<'_> {
+        self.class.as_class()
+    }
+
+    pub fn is_instance<'j>(&self, env: &JNIEnv<'j>, obj: JObject<'j>) -> jni::errors::Result<bool> {
+        env.is_instance_of(obj, self.as_class())
+    }
+
+    /// The bytes from the position to the limit, the position of the buffer is not changed.
+    ///
+    /// The bytes of a direct buffer are borrowed, the bytes of a heap buffer are copied.
+    pub fn remaining<'e, 'j>(
+        &self,
+        env: &'e JNIEnv<'j>,
+        buffer: JByteBuffer<'j>,
+    ) -> Result<Cow<'e, [u8]>, Error> {
+        let int = JavaType::Primitive(Primitive::Int);
+        let position = env
+            .call_method_unchecked(buffer, self.position.get(), int.clone(), &[])?
+            .i()?;
+        let limit = env
+            .call_method_unchecked(buffer, self.limit.get(), int, &[])?
+            .i()?;
+        let is_direct = env
+            .call_method_unchecked(
+                buffer,
+                self.is_direct.get(),
+                JavaType::Primitive(Primitive::Boolean),
+                &[],
+            )?
+            .z()?;
+
+        if is_direct {
+            let bytes: &'e [u8] = env.get_direct_buffer_address(buffer)?;
+            let (position, limit) = (usize::try_from(position)?, usize::try_from(limit)?);
+            ensure!(
+                position <= limit && limit <= bytes.len(),
+                "ByteBuffer position {} and limit {} are outside the capacity {}",
+                position,
+                limit,
+                bytes.len()
+            );
+
+            Ok(Cow::Borrowed(&bytes[position..limit]))
+        } else {
+            // read from a duplicate, reading from the buffer would move its position
+            let ty = JavaType::from_str(Self::SIG)?;
+            let duplicate = env
+                .call_method_unchecked(buffer, self.duplicate.get(), ty.clone(), &[])?
+                .l()?;
+            let array = env.new_byte_array(limit - position)?;
+            env.call_method_unchecked(
+                duplicate,
+                self.get.get(),
+                ty,
+                &[JValue::Object(JObject::from(array))],
+            )?;
+
+            Ok(Cow::Owned(env.convert_byte_array(array)?))
+        }
+    }
+
+    /// A new heap buffer, wrapping the byte[]
+    pub fn wrap<'j>(&self, env: &JNIEnv<'j>, bytes: JObject<'j>) -> Result<JObject<'j>, Error> {
+        let buffer = env
+            .call_static_method_unchecked(
+                self.as_class(),
+                self.wrap.get_static(),
+                JavaType::from_str(Self::SIG)?,
+                &[JValue::Object(bytes)],
+            )?
+            .l()?;
+
+        Ok(buffer)
+    }
+}
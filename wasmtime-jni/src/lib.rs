@@ -1,12 +1,20 @@
+mod jni_cache;
 mod opaque_ptr;
 mod ty;
+mod wasi_config;
+mod wasm_config;
 mod wasm_engine;
+mod wasm_epoch;
 mod wasm_exception;
 mod wasm_function;
+mod wasm_global;
 mod wasm_instance;
 mod wasm_linker;
+mod wasm_memory;
 mod wasm_module;
 mod wasm_state;
 mod wasm_store;
+mod wasm_table;
+mod wasm_typed_func;
 mod wasm_value;
 mod wasmtime;
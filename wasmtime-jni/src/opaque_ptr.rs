@@ -9,12 +9,18 @@ use log::{debug, trace};
 /// List of Opaque types that we support for passing to and from Java
 pub(crate) trait Opaqueable {}
 
+impl Opaqueable for wasmtime::Config {}
 impl Opaqueable for wasmtime::Engine {}
 impl Opaqueable for wasmtime::Func {}
+impl Opaqueable for wasmtime::Global {}
 impl Opaqueable for wasmtime::Instance {}
 impl<T> Opaqueable for wasmtime::Linker<T> {}
+impl Opaqueable for wasmtime::Memory {}
 impl Opaqueable for wasmtime::Module {}
 impl<T> Opaqueable for wasmtime::Store<T> {}
+impl Opaqueable for wasmtime::Table {}
+impl<P, R> Opaqueable for wasmtime::TypedFunc<P, R> {}
+impl Opaqueable for crate::wasi_config::WasiConfig {}
 
 // TODO: add methods to extract from a passed in Object to have better ownership semantics in Java.
 /// This borrows the pointer stored at jlong, not taking ownership
@@ -43,6 +49,14 @@ impl<'a, T> OpaquePtr<'a, T> {
         this
     }
 
+    /// Borrow a pointer that was read from a Java object, rather than passed as an argument
+    pub(crate) fn from_raw(ptr: jlong) -> Self {
+        Self {
+            ptr,
+            ty: PhantomData,
+        }
+    }
+
     #[track_caller]
     pub fn as_ref(&self) -> &'a T {
         trace!("{:?}::as_ref", self);
@@ -1,12 +1,14 @@
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 use std::ops::Deref;
 
-use anyhow::{anyhow, ensure, Error};
+use anyhow::{anyhow, ensure, Context, Error};
 use log::debug;
 use wasmtime::{Store, Val, ValType};
 pub use wasmtime_jni_exports::{WasmAllocated, WasmSlice};
 
 use crate::{
-    ty::{Abi, ComplexTy, ReturnAbi, WasmAlloc, WasmSliceWrapper},
+    ty::{Abi, ComplexTy, ReturnAbi, WasmAlloc, WasmElement, WasmSliceWrapper},
     wasm_state::JavaState,
 };
 
@@ -201,6 +203,116 @@ impl ReturnAbi for WasmSlice {
     }
 }
 
+/// A slice of elements in WASM memory, e.g. `&[f32]`.
+///
+/// This is passed the same as a WasmSlice, the offset and the length, except that the length is the number of
+///   elements and not the number of bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WasmElementSlice<T: WasmElement> {
+    ptr: i32,
+    len: i32,
+    ty: PhantomData<T>,
+}
+
+impl<T: WasmElement> WasmElementSlice<T> {
+    fn new(ptr: i32, len: i32) -> Self {
+        Self {
+            ptr,
+            len,
+            ty: PhantomData,
+        }
+    }
+
+    /// The elements stored in the bytes of the WasmSlice
+    pub fn from_bytes(bytes: WasmSlice) -> Result<Self, Error> {
+        let size = i32::try_from(T::SIZE)?;
+        ensure!(
+            bytes.len() % size == 0,
+            "{} bytes is not a multiple of the element size {}",
+            bytes.len(),
+            size
+        );
+
+        Ok(Self::new(bytes.ptr(), bytes.len() / size))
+    }
+
+    /// The bytes of the elements in memory
+    pub fn as_bytes(&self) -> Result<WasmSlice, Error> {
+        let len = usize::try_from(self.len)?
+            .checked_mul(T::SIZE)
+            .and_then(|len| i32::try_from(len).ok())
+            .with_context(|| format!("{} elements are outside WASM bounds", self.len))?;
+
+        Ok(unsafe { WasmSlice::new(self.ptr, len) })
+    }
+
+    pub fn ptr(&self) -> i32 {
+        self.ptr
+    }
+
+    /// The number of elements
+    pub fn len(&self) -> i32 {
+        self.len
+    }
+}
+
+impl<T: WasmElement> Abi for WasmElementSlice<T> {
+    fn push_arg_tys(args: &mut Vec<ValType>) {
+        WasmSlice::push_arg_tys(args)
+    }
+
+    fn store_to_args(self, args: &mut Vec<Val>) {
+        args.push(Val::from(self.ptr));
+        args.push(Val::from(self.len));
+    }
+
+    fn load_from_args(args: impl Iterator<Item = Val>) -> Result<Self, anyhow::Error> {
+        let slice = WasmSlice::load_from_args(args)?;
+        Ok(Self::new(slice.ptr(), slice.len()))
+    }
+
+    fn matches_arg_tys(tys: impl Iterator<Item = ValType>) -> anyhow::Result<()> {
+        WasmSlice::matches_arg_tys(tys)
+    }
+}
+
+/// Returned by reference the same as a WasmSlice, the length stored at the pointer is the number of elements
+impl<T: WasmElement> ReturnAbi for WasmElementSlice<T> {
+    fn return_or_push_arg_tys(args: &mut Vec<ValType>) -> Option<ValType> {
+        WasmSlice::return_or_push_arg_tys(args)
+    }
+
+    fn return_or_store_to_arg<'w>(
+        args: &mut Vec<Val>,
+        wasm_alloc: Option<&'w WasmAlloc>,
+        store: &mut Store<JavaState>,
+    ) -> Result<Option<WasmSliceWrapper<'w>>, Error> {
+        WasmSlice::return_or_store_to_arg(args, wasm_alloc, store)
+    }
+
+    fn get_return_by_ref_arg(args: impl Iterator<Item = Val>) -> Option<i32> {
+        WasmSlice::get_return_by_ref_arg(args)
+    }
+
+    fn return_or_load_or_from_args(
+        ret: Option<&Val>,
+        ret_by_ref_ptr: Option<WasmSliceWrapper<'_>>,
+        wasm_alloc: Option<&WasmAlloc>,
+        store: &mut Store<JavaState>,
+    ) -> Result<Self, anyhow::Error> {
+        let slice = WasmSlice::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?;
+        Ok(Self::new(slice.ptr(), slice.len()))
+    }
+
+    fn matches_return_or_arg_tys(
+        ret: Option<ValType>,
+        tys: impl Iterator<Item = ValType>,
+    ) -> Result<(), Error> {
+        WasmSlice::matches_return_or_arg_tys(ret, tys)
+    }
+}
+
 // impl<'b> IntoAbi for &'b ByteSlice {
 //     type Abi = WasmSlice;
 
@@ -215,3 +215,51 @@ direct_complex_ty!(i32, ValType::I32);
 direct_complex_ty!(i64, ValType::I64);
 direct_complex_ty!(f32, ValType::F32);
 direct_complex_ty!(f64, ValType::F64);
+
+/// An element of a slice in WASM memory, stored little-endian
+pub(crate) trait WasmElement: Copy + Default + std::fmt::Debug {
+    /// Size of the element in WASM memory
+    const SIZE: usize;
+
+    fn write_le(self, dst: &mut [u8]);
+
+    fn read_le(src: &[u8]) -> Self;
+
+    /// Encodes the elements as bytes for WASM memory
+    fn elements_to_le_bytes(elements: &[Self]) -> Vec<u8> {
+        let mut bytes = vec![0; elements.len() * Self::SIZE];
+        for (element, dst) in elements.iter().zip(bytes.chunks_exact_mut(Self::SIZE)) {
+            element.write_le(dst);
+        }
+
+        bytes
+    }
+
+    /// Decodes the elements from WASM memory, trailing bytes for a partial element are ignored
+    fn elements_from_le_bytes(bytes: &[u8]) -> Vec<Self> {
+        bytes.chunks_exact(Self::SIZE).map(Self::read_le).collect()
+    }
+}
+
+macro_rules! wasm_element {
+    ($t:ident) => {
+        impl WasmElement for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+
+            fn write_le(self, dst: &mut [u8]) {
+                dst.copy_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le(src: &[u8]) -> Self {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                bytes.copy_from_slice(src);
+                $t::from_le_bytes(bytes)
+            }
+        }
+    };
+}
+
+wasm_element!(i32);
+wasm_element!(i64);
+wasm_element!(f32);
+wasm_element!(f64);
@@ -2,6 +2,6 @@ pub(crate) mod byte_slice;
 pub(crate) mod complex_ty;
 mod wasm_alloc;
 
-pub(crate) use byte_slice::{WasmAllocated, WasmSlice};
-pub(crate) use complex_ty::{Abi, ComplexTy, ReturnAbi};
+pub(crate) use byte_slice::{WasmAllocated, WasmElementSlice, WasmSlice};
+pub(crate) use complex_ty::{Abi, ComplexTy, ReturnAbi, WasmElement};
 pub(crate) use wasm_alloc::{WasmAlloc, WasmSliceWrapper};
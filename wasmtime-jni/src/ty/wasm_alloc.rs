@@ -3,9 +3,11 @@ use std::mem;
 use std::ops::Deref;
 
 use anyhow::{anyhow, ensure, Context, Error};
-use log::debug;
+use log::{debug, warn};
 use wasmtime::{AsContextMut, Caller, Extern, Func, Instance, Memory, Store, Val};
-use wasmtime_jni_exports::{ALLOC_EXPORT, DEALLOC_EXPORT, MEMORY_EXPORT};
+use wasmtime_jni_exports::{
+    ALLOC_ALIGNED_EXPORT, ALLOC_EXPORT, DEALLOC_ALIGNED_EXPORT, DEALLOC_EXPORT, MEMORY_EXPORT,
+};
 
 use crate::{
     ty::{WasmAllocated, WasmSlice},
@@ -17,11 +19,14 @@ const MEM_SEGMENT_SIZE: usize = 64 * 1024;
 /// Allocator that can allocate and deallocate to and from a WASM module.
 ///
 /// This assumes the existence of `memory` Memory as well as `__alloc_bytes` and `__dealloc_bytes` Funcs
-///   are exported from the module.
+///   are exported from the module. The `__alloc_aligned` and `__dealloc_aligned` Funcs are optional, they are
+///   used for slices of elements, e.g. `&[i32]`, which need to be aligned to the element size.
 pub(crate) struct WasmAlloc {
     memory: Memory,
     alloc: Func,
     dealloc: Func,
+    alloc_aligned: Option<Func>,
+    dealloc_aligned: Option<Func>,
 }
 
 impl WasmAlloc {
@@ -33,8 +38,14 @@ impl WasmAlloc {
         let dealloc = caller
             .get_export(DEALLOC_EXPORT)
             .and_then(Extern::into_func);
+        let alloc_aligned = caller
+            .get_export(ALLOC_ALIGNED_EXPORT)
+            .and_then(Extern::into_func);
+        let dealloc_aligned = caller
+            .get_export(DEALLOC_ALIGNED_EXPORT)
+            .and_then(Extern::into_func);
 
-        Self::from(memory, alloc, dealloc)
+        Self::from(memory, alloc, dealloc, alloc_aligned, dealloc_aligned)
     }
 
     pub fn from_instance(instance: &Instance, mut store: impl AsContextMut) -> Option<Self> {
@@ -42,28 +53,58 @@ impl WasmAlloc {
             instance.get_memory(&mut store, MEMORY_EXPORT),
             instance.get_func(&mut store, ALLOC_EXPORT),
             instance.get_func(&mut store, DEALLOC_EXPORT),
+            instance.get_func(&mut store, ALLOC_ALIGNED_EXPORT),
+            instance.get_func(&mut store, DEALLOC_ALIGNED_EXPORT),
         )
     }
 
-    fn from(memory: Option<Memory>, alloc: Option<Func>, dealloc: Option<Func>) -> Option<Self> {
+    fn from(
+        memory: Option<Memory>,
+        alloc: Option<Func>,
+        dealloc: Option<Func>,
+        alloc_aligned: Option<Func>,
+        dealloc_aligned: Option<Func>,
+    ) -> Option<Self> {
         Some(Self {
             memory: memory?,
             alloc: alloc?,
             dealloc: dealloc?,
+            alloc_aligned,
+            dealloc_aligned,
         })
     }
 
     /// Safety, the returned array is uninitialized
+    ///
+    /// # Errors
+    ///
+    /// If the WasmSlice is not within the bounds of the Memory
     #[allow(clippy::mut_from_ref)]
     pub unsafe fn as_mut<'a>(
         &self,
         wasm_slice: WasmSlice,
         store: &'a mut impl AsContextMut,
-    ) -> &'a mut [u8] {
+    ) -> Result<&'a mut [u8], Error> {
         debug!("data ptr: {}", wasm_slice.ptr());
 
-        &mut self.memory.data_mut(store.as_context_mut())[wasm_slice.ptr() as usize..]
-            [..wasm_slice.len() as usize]
+        let start = usize::try_from(wasm_slice.ptr())
+            .with_context(|| format!("invalid offset into memory: {}", wasm_slice.ptr()))?;
+        let len = usize::try_from(wasm_slice.len())
+            .with_context(|| format!("invalid length of memory: {}", wasm_slice.len()))?;
+
+        let data = self.memory.data_mut(store.as_context_mut());
+        let data_len = data.len();
+        start
+            .checked_add(len)
+            .and_then(move |end| data.get_mut(start..end))
+            .ok_or_else(|| {
+                anyhow!(
+                    "offset {} len {} is outside of the memory of {} bytes",
+                    start,
+                    len,
+                    data_len
+                )
+            })
     }
 
     /// Allocates size bytes in the Wasm Memory context, returns the offset into the Memory region
@@ -72,7 +113,7 @@ impl WasmAlloc {
         size: usize,
         store: impl AsContextMut,
     ) -> Result<WasmSliceWrapper<'_>, Error> {
-        let len = size as i32;
+        let len = i32::try_from(size)?;
         let mut ptr = [Val::null(); 1];
         self.alloc.call(store, &[Val::I32(len)], &mut ptr)?;
 
@@ -87,10 +128,68 @@ impl WasmAlloc {
         Ok(WasmSliceWrapper::new(self, wasm_slice))
     }
 
+    /// Allocates size bytes aligned to `align` in the Wasm Memory context, see `alloc_size`.
+    ///
+    /// Without the `__alloc_aligned` export this falls back to `__alloc_bytes`.
+    pub unsafe fn alloc_aligned_size(
+        &self,
+        size: usize,
+        align: usize,
+        store: impl AsContextMut,
+    ) -> Result<WasmSliceWrapper<'_>, Error> {
+        let alloc_aligned = match self.alloc_aligned {
+            Some(ref alloc_aligned) => alloc_aligned,
+            None => return self.alloc_size(size, store),
+        };
+
+        let len = i32::try_from(size)?;
+        let mut ptr = [Val::null(); 1];
+        alloc_aligned.call(
+            store,
+            &[Val::I32(len), Val::I32(i32::try_from(align)?)],
+            &mut ptr,
+        )?;
+
+        let ptr = ptr
+            .get(0)
+            .and_then(|v| v.i32())
+            .ok_or_else(|| anyhow!("i32 was not returned from the alloc"))?;
+        ensure!(
+            ptr != 0,
+            "failed to allocate {} bytes aligned to {}",
+            len,
+            align
+        );
+
+        debug!("Allocated offset {} len {} align {}", ptr, len, align);
+
+        let wasm_slice = WasmSlice::new(ptr, len);
+        Ok(WasmSliceWrapper::new(self, wasm_slice))
+    }
+
     /// Allocates the bytes from the src bytes
     pub fn alloc_bytes(
         &self,
         src: &[u8],
+        store: impl AsContextMut,
+    ) -> Result<WasmSliceWrapper<'_>, Error> {
+        self.alloc_and_copy(src, None, store)
+    }
+
+    /// Allocates the bytes from the src bytes aligned to `align`, e.g. the bytes of a slice of elements
+    pub fn alloc_aligned_bytes(
+        &self,
+        src: &[u8],
+        align: usize,
+        store: impl AsContextMut,
+    ) -> Result<WasmSliceWrapper<'_>, Error> {
+        self.alloc_and_copy(src, Some(align), store)
+    }
+
+    fn alloc_and_copy(
+        &self,
+        src: &[u8],
+        align: Option<usize>,
         mut store: impl AsContextMut,
     ) -> Result<WasmSliceWrapper<'_>, Error> {
         let mem_base = self.memory.data_ptr(&mut store) as usize;
@@ -105,8 +204,11 @@ impl WasmAlloc {
         );
 
         // get target memory location and then copy into the function
-        let wasm_slice = unsafe { self.alloc_size(src.len(), &mut store)? };
-        let mem_bytes = unsafe { self.as_mut(wasm_slice.wasm_slice, &mut store) };
+        let wasm_slice = match align {
+            Some(align) => unsafe { self.alloc_aligned_size(src.len(), align, &mut store)? },
+            None => unsafe { self.alloc_size(src.len(), &mut store)? },
+        };
+        let mem_bytes = unsafe { self.as_mut(wasm_slice.wasm_slice, &mut store)? };
         mem_bytes.copy_from_slice(src);
 
         debug!(
@@ -136,6 +238,42 @@ impl WasmAlloc {
         Ok(())
     }
 
+    /// Deallocate the WasmSlice that was allocated with `align`, e.g. a `Vec<i32>` returned as a slice of elements
+    ///
+    /// Without the `__dealloc_aligned` export the memory can not be freed with the correct layout, and is leaked.
+    pub fn dealloc_aligned_bytes(
+        &self,
+        slice: WasmSlice,
+        align: usize,
+        store: impl AsContextMut,
+    ) -> Result<(), Error> {
+        let ptr = slice.ptr();
+        let len = slice.len();
+        let dealloc_aligned = match self.dealloc_aligned {
+            Some(ref dealloc_aligned) => dealloc_aligned,
+            None => {
+                warn!(
+                    "{} is not exported, leaking offset {} len {}",
+                    DEALLOC_ALIGNED_EXPORT, ptr, len
+                );
+                return Ok(());
+            }
+        };
+
+        let align = i32::try_from(align)?;
+        let mut no_result = [Val::null(); 0];
+        dealloc_aligned
+            .call(
+                store,
+                &[Val::I32(ptr), Val::I32(len), Val::I32(align)],
+                &mut no_result,
+            )
+            .with_context(|| anyhow!("failed to deallocate aligned bytes"))?;
+
+        debug!("Deallocated offset {} len {} align {}", ptr, len, align);
+        Ok(())
+    }
+
     pub fn alloc<T: Sized>(
         &self,
         store: &mut Store<JavaState>,
@@ -143,7 +281,7 @@ impl WasmAlloc {
         let wasm_slice = unsafe { self.alloc_size(mem::size_of::<T>(), &mut *store)? };
 
         // zero out the memory...
-        for b in unsafe { wasm_slice.as_mut(&mut *store) } {
+        for b in unsafe { wasm_slice.as_mut(&mut *store)? } {
             *b = 0;
         }
 
@@ -194,7 +332,10 @@ impl<'w> WasmSliceWrapper<'w> {
 
     /// Safety, the returned array is uninitialized
     #[allow(clippy::mut_from_ref)]
-    pub unsafe fn as_mut<'a>(&'a self, store: &'a mut Store<JavaState>) -> &'a mut [u8] {
+    pub unsafe fn as_mut<'a>(
+        &'a self,
+        store: &'a mut Store<JavaState>,
+    ) -> Result<&'a mut [u8], Error> {
         self.wasm_alloc.as_mut(self.wasm_slice, store)
     }
 
@@ -0,0 +1,435 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use anyhow::{Context, Error};
+use std::io::Cursor;
+
+use jni::objects::{GlobalRef, JClass, JObject, JString};
+use jni::sys::{jboolean, jbyteArray, jlong, JNI_TRUE};
+use jni::JNIEnv;
+use log::debug;
+use wasi_common::dir::DirCaps;
+use wasi_common::file::FileCaps;
+use wasi_common::pipe::{ReadPipe, WritePipe};
+use wasi_common::WasiFile;
+use wasmtime_wasi::sync::{self, ambient_authority, WasiCtxBuilder};
+use wasmtime_wasi::WasiCtx;
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+use crate::wasm_state::{JavaInputStream, JavaOutputStream, SharedBuffer, StdioBuffers};
+
+/// stdin, stdout and stderr occupy the first file descriptors
+const FIRST_PREOPEN_FD: u32 = 3;
+
+/// A directory on the host to make available to the WASM module
+#[derive(Clone, Debug)]
+struct Preopen {
+    host_path: PathBuf,
+    guest_path: PathBuf,
+    read_only: bool,
+}
+
+/// Source of stdin for the WASM module
+#[derive(Clone)]
+enum Input {
+    Closed,
+    Inherit,
+    Stream(GlobalRef),
+    Bytes(Vec<u8>),
+}
+
+impl Input {
+    fn to_file(&self, env: &JNIEnv<'_>) -> Result<Option<Box<dyn WasiFile>>, Error> {
+        let file: Box<dyn WasiFile> = match self {
+            Input::Closed => return Ok(None),
+            Input::Inherit => Box::new(sync::stdio::stdin()),
+            Input::Stream(stream) => {
+                Box::new(ReadPipe::new(JavaInputStream::new(env, stream.clone())?))
+            }
+            Input::Bytes(bytes) => Box::new(ReadPipe::new(Cursor::new(bytes.clone()))),
+        };
+
+        Ok(Some(file))
+    }
+}
+
+/// Destination of stdout or stderr for the WASM module
+#[derive(Clone)]
+enum Output {
+    Closed,
+    Inherit,
+    Stream(GlobalRef),
+    Buffer,
+}
+
+impl Output {
+    /// Returns the file for WASI and the buffer for the Store, if the output is captured
+    fn to_file(
+        &self,
+        env: &JNIEnv<'_>,
+        inherit: fn() -> Box<dyn WasiFile>,
+    ) -> Result<(Option<Box<dyn WasiFile>>, Option<SharedBuffer>), Error> {
+        let file: Box<dyn WasiFile> = match self {
+            Output::Closed => return Ok((None, None)),
+            Output::Inherit => inherit(),
+            Output::Stream(stream) => {
+                Box::new(WritePipe::new(JavaOutputStream::new(env, stream.clone())?))
+            }
+            Output::Buffer => {
+                let buffer = SharedBuffer::default();
+                let file: Box<dyn WasiFile> = Box::new(WritePipe::new(buffer.clone()));
+                return Ok((Some(file), Some(buffer)));
+            }
+        };
+
+        Ok((Some(file), None))
+    }
+}
+
+/// The WASI context settings for a Store.
+///
+/// By default nothing from the host is made available, no args, no env, no stdio and no directories.
+#[derive(Clone)]
+pub struct WasiConfig {
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    preopens: Vec<Preopen>,
+    stdin: Input,
+    stdout: Output,
+    stderr: Output,
+}
+
+impl Default for WasiConfig {
+    fn default() -> Self {
+        Self {
+            args: Vec::new(),
+            envs: Vec::new(),
+            preopens: Vec::new(),
+            stdin: Input::Closed,
+            stdout: Output::Closed,
+            stderr: Output::Closed,
+        }
+    }
+}
+
+impl WasiConfig {
+    /// Construct a new WasiCtx from the configuration, along with any buffers capturing the output
+    pub fn build(&self, env: &JNIEnv<'_>) -> Result<(WasiCtx, StdioBuffers), Error> {
+        let mut builder = WasiCtxBuilder::new()
+            .args(&self.args)
+            .context("failed to set WASI args")?
+            .envs(&self.envs)
+            .context("failed to set WASI env")?;
+
+        if let Some(stdin) = self.stdin.to_file(env)? {
+            builder = builder.stdin(stdin);
+        }
+
+        let (stdout, stdout_buffer) = self
+            .stdout
+            .to_file(env, || Box::new(sync::stdio::stdout()))?;
+        if let Some(stdout) = stdout {
+            builder = builder.stdout(stdout);
+        }
+
+        let (stderr, stderr_buffer) = self
+            .stderr
+            .to_file(env, || Box::new(sync::stdio::stderr()))?;
+        if let Some(stderr) = stderr {
+            builder = builder.stderr(stderr);
+        }
+
+        let mut wasi = builder.build();
+
+        for (fd, preopen) in (FIRST_PREOPEN_FD..).zip(self.preopens.iter()) {
+            debug!("preopening {:?} for WASI", preopen);
+
+            let dir = sync::Dir::open_ambient_dir(&preopen.host_path, ambient_authority())
+                .with_context(|| format!("failed to open directory: {:?}", preopen.host_path))?;
+            let dir = Box::new(sync::dir::Dir::from_cap_std(dir));
+
+            let (dir_caps, file_caps) = if preopen.read_only {
+                read_only_caps()
+            } else {
+                (DirCaps::all(), FileCaps::all())
+            };
+
+            wasi.insert_dir(fd, dir, dir_caps, file_caps, preopen.guest_path.clone());
+        }
+
+        let buffers = StdioBuffers {
+            stdout: stdout_buffer,
+            stderr: stderr_buffer,
+        };
+
+        Ok((wasi, buffers))
+    }
+}
+
+fn read_only_caps() -> (DirCaps, FileCaps) {
+    let dir_caps = DirCaps::OPEN
+        | DirCaps::READDIR
+        | DirCaps::READLINK
+        | DirCaps::PATH_FILESTAT_GET
+        | DirCaps::FILESTAT_GET;
+
+    let file_caps = FileCaps::READ
+        | FileCaps::SEEK
+        | FileCaps::TELL
+        | FileCaps::ADVISE
+        | FileCaps::FILESTAT_GET
+        | FileCaps::POLL_READWRITE;
+
+    (dir_caps, file_caps)
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    newWasiConfigNtv
+///  * Signature: ()J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_newWasiConfigNtv
+///  (JNIEnv *, jclass);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_newWasiConfigNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+) -> jlong {
+    OpaquePtr::from(WasiConfig::default()).make_opaque()
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    freeWasiConfig
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_freeWasiConfig
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_freeWasiConfig<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    config: OpaquePtr<'j, WasiConfig>,
+) {
+    drop(config.take());
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    argNtv
+///  * Signature: (JLjava/lang/String;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_argNtv
+///  (JNIEnv *, jclass, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_argNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    arg: JString<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let arg = env.get_string(arg)?;
+        let arg: Cow<str> = Cow::from(&arg);
+
+        config.args.push(arg.to_string());
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    envNtv
+///  * Signature: (JLjava/lang/String;Ljava/lang/String;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_envNtv
+///  (JNIEnv *, jclass, jlong, jstring, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_envNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    key: JString<'j>,
+    value: JString<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let key = env.get_string(key)?;
+        let value = env.get_string(value)?;
+
+        let key: Cow<str> = Cow::from(&key);
+        let value: Cow<str> = Cow::from(&value);
+
+        config.envs.push((key.to_string(), value.to_string()));
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    preopenDirNtv
+///  * Signature: (JLjava/lang/String;Ljava/lang/String;Z)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_preopenDirNtv
+///  (JNIEnv *, jclass, jlong, jstring, jstring, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_preopenDirNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    host_path: JString<'j>,
+    guest_path: JString<'j>,
+    read_only: jboolean,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let host_path = env.get_string(host_path)?;
+        let guest_path = env.get_string(guest_path)?;
+
+        let host_path: Cow<str> = Cow::from(&host_path);
+        let guest_path: Cow<str> = Cow::from(&guest_path);
+
+        config.preopens.push(Preopen {
+            host_path: PathBuf::from(host_path.as_ref()),
+            guest_path: PathBuf::from(guest_path.as_ref()),
+            read_only: read_only == JNI_TRUE,
+        });
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    inheritStdioNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_inheritStdioNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_inheritStdioNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    inherit: jboolean,
+) {
+    if inherit == JNI_TRUE {
+        config.stdin = Input::Inherit;
+        config.stdout = Output::Inherit;
+        config.stderr = Output::Inherit;
+    } else {
+        config.stdin = Input::Closed;
+        config.stdout = Output::Closed;
+        config.stderr = Output::Closed;
+    }
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stdinStreamNtv
+///  * Signature: (JLjava/io/InputStream;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stdinStreamNtv
+///  (JNIEnv *, jclass, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stdinStreamNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    stream: JObject<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        config.stdin = Input::Stream(env.new_global_ref(stream)?);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stdinBytesNtv
+///  * Signature: (J[B)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stdinBytesNtv
+///  (JNIEnv *, jclass, jlong, jbyteArray);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stdinBytesNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    bytes: jbyteArray,
+) {
+    wasm_exception::attempt(&env, |env| {
+        config.stdin = Input::Bytes(env.convert_byte_array(bytes)?);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stdoutStreamNtv
+///  * Signature: (JLjava/io/OutputStream;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stdoutStreamNtv
+///  (JNIEnv *, jclass, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stdoutStreamNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    stream: JObject<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        config.stdout = Output::Stream(env.new_global_ref(stream)?);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stdoutBufferNtv
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stdoutBufferNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stdoutBufferNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+) {
+    config.stdout = Output::Buffer;
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stderrStreamNtv
+///  * Signature: (JLjava/io/OutputStream;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stderrStreamNtv
+///  (JNIEnv *, jclass, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stderrStreamNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+    stream: JObject<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        config.stderr = Output::Stream(env.new_global_ref(stream)?);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasiConfig
+///  * Method:    stderrBufferNtv
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasiConfig_stderrBufferNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasiConfig_stderrBufferNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, WasiConfig>,
+) {
+    config.stderr = Output::Buffer;
+}
@@ -0,0 +1,256 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use anyhow::{anyhow, Context};
+use jni::objects::{JClass, JString};
+use jni::sys::{jboolean, jint, jlong, JNI_TRUE};
+use jni::JNIEnv;
+use log::debug;
+use wasmtime::{Config, OptLevel};
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    newConfigNtv
+///  * Signature: ()J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_newConfigNtv
+///  (JNIEnv *, jclass);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_newConfigNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+) -> jlong {
+    OpaquePtr::from(Config::new()).make_opaque()
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    freeConfig
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_freeConfig
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_freeConfig<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    config: OpaquePtr<'j, Config>,
+) {
+    drop(config.take());
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    consumeFuelNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_consumeFuelNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_consumeFuelNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.consume_fuel(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    epochInterruptionNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_epochInterruptionNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_epochInterruptionNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    // Wasmtime 0.31 has no epoch counter in the engine, interruption is driven through the
+    //  store's InterruptHandle, which requires the code to be compiled as interruptable.
+    config.interruptable(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    wasmSimdNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_wasmSimdNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_wasmSimdNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.wasm_simd(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    wasmMultiMemoryNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_wasmMultiMemoryNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_wasmMultiMemoryNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.wasm_multi_memory(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    wasmMultiValueNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_wasmMultiValueNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_wasmMultiValueNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.wasm_multi_value(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    wasmBulkMemoryNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_wasmBulkMemoryNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_wasmBulkMemoryNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.wasm_bulk_memory(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    wasmReferenceTypesNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_wasmReferenceTypesNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_wasmReferenceTypesNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.wasm_reference_types(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    craneliftOptLevelNtv
+///  * Signature: (JI)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_craneliftOptLevelNtv
+///  (JNIEnv *, jclass, jlong, jint);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_craneliftOptLevelNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    level: jint,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        // these must line up with the ordinals of WasmConfig.OptLevel
+        let level = match level {
+            0 => OptLevel::None,
+            1 => OptLevel::Speed,
+            2 => OptLevel::SpeedAndSize,
+            _ => return Err(anyhow!("unknown optimization level: {}", level)),
+        };
+
+        config.cranelift_opt_level(level);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    debugInfoNtv
+///  * Signature: (JZ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_debugInfoNtv
+///  (JNIEnv *, jclass, jlong, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_debugInfoNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    enable: jboolean,
+) {
+    config.debug_info(enable == JNI_TRUE);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    maxWasmStackNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_maxWasmStackNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_maxWasmStackNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    size: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let size = usize::try_from(size)?;
+        config.max_wasm_stack(size)?;
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmConfig
+///  * Method:    cacheConfigLoadNtv
+///  * Signature: (JLjava/lang/String;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmConfig_cacheConfigLoadNtv
+///  (JNIEnv *, jclass, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmConfig_cacheConfigLoadNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut config: OpaquePtr<'j, Config>,
+    path: JString<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let path = env.get_string(path)?;
+        let path: Cow<str> = Cow::from(&path);
+
+        debug!("loading cache config: {}", path);
+        config
+            .cache_config_load(path.as_ref())
+            .with_context(|| format!("failed to load cache config: {}", path))?;
+        Ok(())
+    })
+}
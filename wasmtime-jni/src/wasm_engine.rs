@@ -1,10 +1,16 @@
-use jni::objects::{JByteBuffer, JClass};
-use jni::sys::jlong;
+use std::borrow::Cow;
+use std::io;
+
+use anyhow::{Context, Error};
+use jni::objects::{JByteBuffer, JClass, JObject, JString, JValue};
+use jni::sys::{jbyteArray, jlong, jobject};
 use jni::JNIEnv;
 use log::{debug, warn};
+use wasmparser::BinaryReaderError;
 use wasmtime::{Engine, Linker, Module, Store};
 
 use crate::opaque_ptr::OpaquePtr;
+use crate::wasi_config::WasiConfig;
 use crate::wasm_exception;
 use crate::wasm_state::JavaState;
 
@@ -27,7 +33,7 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_freeEngine<'j>(
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_WasmEngine
 ///  * Method:    newStoreNtv
-///  * Signature: ()J
+///  * Signature: (J)J
 ///  */
 ///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmEngine_newStoreNtv
 ///  (JNIEnv *, jclass, jlong);
@@ -38,13 +44,45 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_newStoreNtv<'j>(
     engine: OpaquePtr<'j, Engine>,
 ) -> jlong {
     let ptr = wasm_exception::attempt(&env, |_env| {
-        let store: Store<JavaState> = Store::new(&engine, JavaState::new(env)?);
+        let store = new_store(env, &engine, &WasiConfig::default())?;
         Ok(OpaquePtr::from(store).make_opaque())
     });
 
     ptr
 }
 
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmEngine
+///  * Method:    newStoreWithWasiNtv
+///  * Signature: (JJ)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmEngine_newStoreWithWasiNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_newStoreWithWasiNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    engine: OpaquePtr<'j, Engine>,
+    wasi_config: OpaquePtr<'j, WasiConfig>,
+) -> jlong {
+    wasm_exception::attempt(&env, |_env| {
+        let store = new_store(env, &engine, &wasi_config)?;
+        Ok(OpaquePtr::from(store).make_opaque())
+    })
+}
+
+fn new_store(
+    env: JNIEnv<'_>,
+    engine: &Engine,
+    wasi_config: &WasiConfig,
+) -> Result<Store<JavaState>, Error> {
+    let state = JavaState::new(env, wasi_config)?;
+    let mut store: Store<JavaState> = Store::new(engine, state);
+    store.limiter(JavaState::limiter);
+
+    Ok(store)
+}
+
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_WasmEngine
 ///  * Method:    newModuleNtv
@@ -92,6 +130,118 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_newModuleNtv(
     OpaquePtr::from(module).make_opaque()
 }
 
+const VALIDATION_ERROR: &str = "net/bluejekyll/wasmtime/WasmValidationError";
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmEngine
+///  * Method:    validateNtv
+///  * Signature: (J[B)Lnet/bluejekyll/wasmtime/WasmValidationError;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmEngine_validateNtv
+///  (JNIEnv *, jclass, jlong, jbyteArray);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_validateNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    engine: OpaquePtr<'j, Engine>,
+    bytes: jbyteArray,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let bytes = env.convert_byte_array(bytes)?;
+
+            debug!("validating wasm module from bytes: {}", bytes.len());
+            let err = match Module::validate(&engine, &bytes) {
+                Ok(()) => return Ok(JObject::null().into_inner()),
+                Err(err) => err,
+            };
+
+            // the offset is unknown for errors other than those from the parser, i.e. unsupported features
+            let (offset, message) = match err.downcast_ref::<BinaryReaderError>() {
+                Some(err) => (err.offset() as jlong, err.message().to_string()),
+                None => (-1, format!("{:#}", err)),
+            };
+
+            debug!("validation failed at {}: {}", offset, message);
+            let message = env.new_string(message)?;
+            let validation_error = env.new_object(
+                VALIDATION_ERROR,
+                "(JLjava/lang/String;)V",
+                &[JValue::Long(offset), JObject::from(message).into()],
+            )?;
+
+            Ok(validation_error.into_inner())
+        },
+    )
+}
+
+/// The error for deserialization of a module produced by a different version of Wasmtime or a different engine config
+const INCOMPATIBLE_MODULE: &str =
+    "failed to deserialize module, it must be serialized by an engine with the same configuration";
+
+/// Only Wasmtime rejecting the artifact is reported as an incompatible module, not failures to read it
+fn deserialize_error(err: Error) -> Error {
+    if err.chain().any(|cause| cause.is::<io::Error>()) {
+        err
+    } else {
+        err.context(INCOMPATIBLE_MODULE)
+    }
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmEngine
+///  * Method:    deserializeModuleNtv
+///  * Signature: (J[B)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmEngine_deserializeModuleNtv
+///  (JNIEnv *, jclass, jlong, jbyteArray);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_deserializeModuleNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    engine: OpaquePtr<'j, Engine>,
+    bytes: jbyteArray,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let bytes = env.convert_byte_array(bytes)?;
+
+        debug!("deserializing wasm module from bytes: {}", bytes.len());
+        // Safety: Wasmtime verifies that the artifact was produced by a compatible engine, the bytes must
+        //  come from `Module::serialize`, see `WasmModule.serialize` in Java
+        let module = unsafe { Module::deserialize(&engine, bytes) }.map_err(deserialize_error)?;
+        Ok(OpaquePtr::from(module).make_opaque())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmEngine
+///  * Method:    deserializeModuleFileNtv
+///  * Signature: (JLjava/lang/String;)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmEngine_deserializeModuleFileNtv
+///  (JNIEnv *, jclass, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmEngine_deserializeModuleFileNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    engine: OpaquePtr<'j, Engine>,
+    path: JString<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let path = env.get_string(path)?;
+        let path: Cow<str> = Cow::from(&path);
+
+        debug!("deserializing wasm module from file: {}", path);
+        // Safety: see deserializeModuleNtv, the file must not be modified while the Module exists
+        let module = unsafe { Module::deserialize_file(&engine, path.as_ref()) }
+            .map_err(deserialize_error)
+            .with_context(|| format!("failed to load serialized module from file: {}", path))?;
+        Ok(OpaquePtr::from(module).make_opaque())
+    })
+}
+
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_WasmEngine
 ///  * Method:    newLinker
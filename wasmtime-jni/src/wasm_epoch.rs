@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Error};
+use log::{debug, warn};
+use once_cell::sync::OnceCell;
+use wasmtime::InterruptHandle;
+
+/// The period of a single epoch tick
+pub(crate) const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+static TICKER: OnceCell<EpochTicker> = OnceCell::new();
+
+/// Returns the global ticker, starting the background thread on first use.
+pub(crate) fn ticker() -> Result<&'static EpochTicker, Error> {
+    TICKER.get_or_try_init(EpochTicker::start)
+}
+
+/// Convert a timeout into the number of ticks, rounding up so that the deadline is never early
+pub(crate) fn ticks_for(timeout: Duration) -> u64 {
+    let tick = EPOCH_TICK.as_nanos();
+    let ticks = (timeout.as_nanos() + tick - 1) / tick;
+    ticks.max(1) as u64
+}
+
+struct Deadline {
+    epoch: u64,
+    handle: InterruptHandle,
+    /// Set by the ticker once the Store has been interrupted
+    fired: bool,
+}
+
+type Deadlines = HashMap<u64, Deadline>;
+
+/// Wasmtime 0.31 does not have epochs in the Engine, this emulates them.
+///
+/// A background thread increments the epoch every `EPOCH_TICK`, any Store whose deadline has been reached
+///  is interrupted through its `InterruptHandle`. As with Wasmtime's epochs, the Store will trap the next time
+///  WASM checks for an interrupt, which includes entering WASM if the deadline passed while no WASM was running.
+pub(crate) struct EpochTicker {
+    epoch: Arc<AtomicU64>,
+    deadlines: Arc<Mutex<Deadlines>>,
+    next_id: AtomicU64,
+}
+
+impl EpochTicker {
+    fn start() -> Result<Self, Error> {
+        let epoch = Arc::new(AtomicU64::new(0));
+        let deadlines = Arc::new(Mutex::new(Deadlines::new()));
+
+        let thread_epoch = Arc::clone(&epoch);
+        let thread_deadlines = Arc::clone(&deadlines);
+        thread::Builder::new()
+            .name("wasmtime-jni-epoch".to_string())
+            .spawn(move || loop {
+                thread::sleep(EPOCH_TICK);
+                let epoch = thread_epoch.fetch_add(1, Ordering::AcqRel) + 1;
+
+                let mut deadlines = match thread_deadlines.lock() {
+                    Ok(deadlines) => deadlines,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+
+                // fired deadlines stay registered until they are cleared, see `EpochDeadline::clear`
+                for (id, deadline) in deadlines.iter_mut() {
+                    if !deadline.fired && deadline.epoch <= epoch {
+                        debug!(
+                            "epoch deadline {} reached for {}, interrupting",
+                            deadline.epoch, id
+                        );
+                        deadline.handle.interrupt();
+                        deadline.fired = true;
+                    }
+                }
+            })
+            .context("failed to start the epoch ticker thread")?;
+
+        Ok(Self {
+            epoch,
+            deadlines,
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    /// The current epoch
+    pub fn current(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    /// Register a deadline, the Store will be interrupted once `ticks_beyond_current` ticks have elapsed.
+    ///
+    /// The deadline is removed when the returned value is dropped.
+    pub fn deadline(&self, ticks_beyond_current: u64, handle: InterruptHandle) -> EpochDeadline {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let deadline = self.current().saturating_add(ticks_beyond_current);
+
+        debug!("registering epoch deadline {} for {}", deadline, id);
+        self.lock_deadlines().insert(
+            id,
+            Deadline {
+                epoch: deadline,
+                handle,
+                fired: false,
+            },
+        );
+
+        EpochDeadline { id }
+    }
+
+    fn lock_deadlines(&self) -> std::sync::MutexGuard<'_, Deadlines> {
+        match self.deadlines.lock() {
+            Ok(deadlines) => deadlines,
+            Err(poisoned) => {
+                warn!("epoch deadlines were poisoned");
+                poisoned.into_inner()
+            }
+        }
+    }
+}
+
+/// A registered deadline for a Store, removed from the ticker on drop
+pub struct EpochDeadline {
+    id: u64,
+}
+
+impl EpochDeadline {
+    /// Remove the deadline from the ticker, returns true if the Store was interrupted.
+    ///
+    /// The ticker checks and fires deadlines under the same lock, after this returns the Store will not be
+    ///  interrupted by this deadline. If it fired while no WASM was running, the interrupt is still pending.
+    pub fn clear(self) -> bool {
+        Self::remove(self.id)
+    }
+
+    fn remove(id: u64) -> bool {
+        TICKER
+            .get()
+            .and_then(|ticker| ticker.lock_deadlines().remove(&id))
+            .map_or(false, |deadline| deadline.fired)
+    }
+}
+
+impl Drop for EpochDeadline {
+    fn drop(&mut self) {
+        Self::remove(self.id);
+    }
+}
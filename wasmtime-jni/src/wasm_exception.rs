@@ -7,9 +7,18 @@ use jni::strings::JavaStr;
 use jni::sys::jarray;
 use jni::JNIEnv;
 use log::warn;
+use wasmtime::{Trap, TrapCode};
 
 use crate::wasm_value;
 
+const WASMTIME_EXCEPTION: &str = "net/bluejekyll/wasmtime/WasmtimeException";
+const OUT_OF_FUEL_EXCEPTION: &str = "net/bluejekyll/wasmtime/WasmOutOfFuelException";
+const INTERRUPTED_EXCEPTION: &str = "net/bluejekyll/wasmtime/WasmInterruptedException";
+
+/// Wasmtime 0.31 traps on fuel exhaustion with a private error type and no TrapCode, this is the message it carries,
+///   see `test_out_of_fuel_exception`
+const OUT_OF_FUEL_MSG: &str = "all fuel consumed by WebAssembly";
+
 #[track_caller]
 pub fn attempt<R, F>(env: &JNIEnv, f: F) -> R
 where
@@ -32,13 +41,24 @@ where
         Err(err) => {
             let msg = format!("Error in WASM Binding: {:?}", err);
             warn!("{}", msg);
-            env.throw_new("net/bluejekyll/wasmtime/WasmtimeException", msg)
+            env.throw_new(exception_class(&err), msg)
                 .expect("failed to throw exception");
             or()
         }
     }
 }
 
+/// Select the Java exception type that best represents the error
+fn exception_class(err: &Error) -> &'static str {
+    let trap = err.chain().find_map(|cause| cause.downcast_ref::<Trap>());
+
+    match trap.map(|trap| (trap, trap.trap_code())) {
+        Some((_, Some(TrapCode::Interrupt))) => INTERRUPTED_EXCEPTION,
+        Some((trap, None)) if trap.to_string().contains(OUT_OF_FUEL_MSG) => OUT_OF_FUEL_EXCEPTION,
+        _ => WASMTIME_EXCEPTION,
+    }
+}
+
 pub fn exception_to_err<'j>(env: &JNIEnv<'j>, throwable: JThrowable<'j>) -> Error {
     let reporter = ReportJThrowable { env, throwable };
 
@@ -143,3 +163,33 @@ fn call_string_method<'j: 'l, 'l>(
         .with_context(|| format!("Failed to get_string for Exception: {:?}", obj))
         .map(Some)
 }
+
+#[cfg(test)]
+mod tests {
+    use wasmtime::{Config, Engine, Instance, Module, Store};
+
+    use super::*;
+
+    #[test]
+    fn test_out_of_fuel_exception() {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).expect("failed to create engine");
+        let module = Module::new(&engine, r#"(module (func (export "spin") (loop (br 0))))"#)
+            .expect("failed to compile module");
+
+        let mut store = Store::new(&engine, ());
+        store.add_fuel(1_000).expect("failed to add fuel");
+        let instance = Instance::new(&mut store, &module, &[]).expect("failed to instantiate");
+        let spin = instance
+            .get_typed_func::<(), (), _>(&mut store, "spin")
+            .expect("spin is exported");
+
+        let err = Error::from(
+            spin.call(&mut store, ())
+                .expect_err("spin should run out of fuel"),
+        )
+        .context("failed to execute wasm function");
+        assert_eq!(exception_class(&err), OUT_OF_FUEL_EXCEPTION);
+    }
+}
@@ -1,21 +1,28 @@
 use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::slice;
+use std::str::FromStr;
 
 use anyhow::{anyhow, Context, Error};
-use jni::objects::{JClass, JMethodID, JObject, JString, JValue, ReleaseMode};
-use jni::signature::JavaType;
-use jni::sys::{jlong, jobject, jobjectArray};
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValue, ReleaseMode};
+use jni::signature::{JavaType, Primitive, TypeSignature};
+use jni::sys::{jarray, jlong, jobject, jobjectArray, jsize};
 use jni::JNIEnv;
 use log::debug;
 use log::warn;
 use wasmtime::{AsContextMut, Caller, Func, FuncType, Instance, Store, Trap, Val, ValType};
 
+use crate::jni_cache::{self, MethodId};
 use crate::opaque_ptr::OpaquePtr;
-use crate::ty::{WasmAlloc, WasmSlice};
+use crate::ty::{WasmAlloc, WasmSlice, WasmSliceWrapper};
 use crate::wasm_exception;
 use crate::wasm_state::JavaState;
-use crate::wasm_value::{self, WasmTy, WasmVal};
+use crate::wasm_value::{self, JavaPrimitive, WasmTy, WasmVal};
+
+const WASM_TYPE: &str = "net/bluejekyll/wasmtime/ty/WasmType";
+
+/// The number of local references needed for a call from WASM to Java, the frame grows as needed
+const LOCAL_FRAME_CAPACITY: i32 = 16;
 
 /// Take ths src_bytes and copy into the location at ret_by_ref_ptr as a reference.
 ///   This will forget the allocation, as it is expected that the function accepting the reference will own the
@@ -41,6 +48,31 @@ unsafe fn pass_bytes_to_wasm_by_ref(
     Ok(())
 }
 
+/// Copy the Java array into WASM and store the slice of elements at ret_by_ref_ptr, see `pass_bytes_to_wasm_by_ref`.
+///
+/// # Safety
+///
+/// A pointer with allocated memory for a type of (i32,i32) length is valid and exists at `ret_by_ref_ptr` in the WASM module
+unsafe fn pass_array_to_wasm_by_ref(
+    env: &JNIEnv<'_>,
+    wasm_alloc: WasmAlloc,
+    ret_by_ref_ptr: i32,
+    jarray: jarray,
+    element: JavaPrimitive,
+    mut store: impl AsContextMut,
+) -> Result<(), Trap> {
+    let (elements, bytes) =
+        wasm_value::alloc_java_array(env, jarray, element, &wasm_alloc, &mut store)?;
+
+    // the length stored is the number of elements
+    let ret_by_ref_loc = wasm_alloc.obj_as_mut::<WasmSlice, _>(ret_by_ref_ptr, store);
+    *ret_by_ref_loc = elements;
+
+    // This memory is owned by the WASM code after this...
+    std::mem::forget(bytes);
+    Ok(())
+}
+
 /// /*
 /// * Class:     net_bluejekyll_wasmtime_WasmFunction
 /// * Method:    createFunc
@@ -68,25 +100,7 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_createFunc<'j>(
         let method_name = get_method_name(env, &method)?;
         debug!("building WASM function from method: \"{}\"", method_name);
 
-        // collect all the arguments from
-        let param_list = env.get_list(param_tys)?;
-        let mut wasm_args: Vec<ValType> = Vec::with_capacity(param_list.size()? as usize);
-        let mut java_args: Vec<WasmTy> = Vec::with_capacity(wasm_args.len());
-
-        for class in param_list.iter()? {
-            // this is a list of classes
-            let val = wasm_value::from_java_class(env, class.into(), false)
-                .context("error converting type to wasm")?;
-            let val = val.ok_or_else(|| anyhow!("Null parameters not allowed"))?;
-            debug!(
-                "Mapping parameter from {:?} to {:?}",
-                wasm_value::get_class_name(env, class.into())?,
-                val
-            );
-
-            val.push_arg_tys(&mut wasm_args);
-            java_args.push(val);
-        }
+        let (mut wasm_args, java_args) = params_from_java(env, param_tys)?;
 
         // determine the return type
         let java_ret = wasm_value::from_java_class(env, return_ty, true)
@@ -146,186 +160,260 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_createFunc<'j>(
                 .map_err(Error::from)
                 .context("Error accessing JNIEnv in WASM")?;
 
-            // get the invoke method on the method, the result is always Object
-            let ret = JavaType::Object(String::from("java/lang/Object"));
-            let method_id: JMethodID = match env.get_method_id(
-                "java/lang/reflect/Method",
-                "invoke",
-                "(Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;",
-            ) {
-                Err(err) => {
-                    let warning = format!("Error accessing byte buffer: {}", err);
-                    warn!("{}", warning);
-                    return Err(Trap::new(warning));
+            in_local_frame(&env, || {
+                let mut input_iter = inputs.iter().cloned();
+                let val = invoke_java_method(
+                    &env,
+                    &method,
+                    &obj,
+                    java_args,
+                    &mut input_iter,
+                    wasm_alloc.as_ref(),
+                    &mut caller,
+                )?;
+
+                // get the optional pointer to the arg to store a byte return by ref
+                let ret_by_ref_ptr = java_ret
+                    .as_ref()
+                    .and_then(|v| v.get_return_by_ref_arg(input_iter));
+
+                // Now get the return value
+                let val = wasm_value::from_jvalue(&env, val)?;
+                let result = outputs.get_mut(0);
+
+                // TODO: much of this logic is duplicitive with that in wasm_value::WasmVal::store_to_args
+                match (val, result) {
+                    (Some(WasmVal::Val(val)), Some(result)) => {
+                        debug!("associating {:?} with result", val);
+                        *result = val;
+                    }
+                    (Some(WasmVal::ByteBuffer(buffer)), None) => {
+                        debug!("allocating space and associating bytes for return by ref");
+                        let ptr = ret_by_ref_ptr
+                            .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
+                        let wasm_alloc =
+                            wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
+
+                        let bytes = jni_cache::get(&env)?
+                            .byte_buffer
+                            .remaining(&env, buffer)
+                            .context("failed to get bytes from ByteBuffer")?;
+
+                        // get mutable reference to the return by ref pointer and then store
+                        unsafe { pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, &bytes, &mut caller)? };
+                    }
+                    (Some(WasmVal::ByteArray { jarray, .. }), None) => {
+                        debug!("allocating space and associating bytes for return by ref");
+                        let ptr = ret_by_ref_ptr
+                            .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
+                        let wasm_alloc =
+                            wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
+
+                        let len = env
+                            .get_array_length(jarray)
+                            .context("failed to get Java array length")?;
+                        let jbytes = env
+                            .get_byte_array_elements(jarray, ReleaseMode::CopyBack)
+                            .context("failed to get java array elements")?;
+                        let byte_array: &[u8] = unsafe {
+                            slice::from_raw_parts(jbytes.as_ptr() as *const u8, len as usize)
+                        };
+
+                        // get mutable reference to the return by ref pointer and then store
+                        unsafe {
+                            pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, byte_array, &mut caller)?
+                        };
+                    }
+                    (Some(WasmVal::String(string)), None) => {
+                        debug!("allocating space and associating string for return by ref");
+                        let ptr = ret_by_ref_ptr
+                            .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
+                        let wasm_alloc =
+                            wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
+
+                        let jstr = env
+                            .get_string(string)
+                            .context("failed to get Java String")?;
+                        let cow = Cow::from(&jstr);
+                        debug!("String from Java for pass_by_ref: {}", cow);
+
+                        let cow_bytes = cow.as_bytes();
+
+                        // get mutable reference to the return by ref pointer and then store
+                        unsafe {
+                            pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, cow_bytes, &mut caller)?
+                        };
+                    }
+                    (
+                        Some(WasmVal::Array {
+                            jarray, element, ..
+                        }),
+                        None,
+                    ) => {
+                        debug!(
+                            "allocating space and associating {}[] for return by ref",
+                            element
+                        );
+                        let ptr = ret_by_ref_ptr
+                            .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
+                        let wasm_alloc =
+                            wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
+
+                        unsafe {
+                            pass_array_to_wasm_by_ref(
+                                &env,
+                                wasm_alloc,
+                                ptr,
+                                jarray,
+                                element,
+                                &mut caller,
+                            )?
+                        };
+                    }
+                    (
+                        Some(
+                            WasmVal::ByteBuffer(_)
+                            | WasmVal::ByteArray { .. }
+                            | WasmVal::Array { .. }
+                            | WasmVal::String(_),
+                        ),
+                        Some(_result),
+                    ) => {
+                        return Err(anyhow!(
+                            "Unexpected WASM return value, should have been return by reference"
+                        )
+                        .into());
+                    }
+                    (None, Some(result)) => {
+                        debug!("associating null with result");
+                        *result = Val::null();
+                    }
+                    (Some(val), None) => {
+                        warn!("WASM expected no result, but Java supplied: {:?}", val);
+                    }
+                    (None, None) => {
+                        debug!("returning no result");
+                    }
                 }
-                Ok(ok) => ok,
-            };
 
-            // build up parameters
-            let arg_class = env
-                .find_class("java/lang/Object")
-                .map_err(Error::from)
-                .context("Could not find Object?")?;
+                Ok(())
+            })
+        };
 
-            let method_args = env
-                .new_object_array(java_args.len() as i32, arg_class, JObject::null())
-                .map_err(Error::from)
-                .context("Could not create empty array?")?;
-
-            // set the parameters
-            let mut input_iter = inputs.iter().cloned();
-            for (i, java_arg) in java_args.iter().enumerate() {
-                let jvalue = unsafe {
-                    java_arg
-                        .load_from_args(&env, &mut input_iter, wasm_alloc.as_ref(), &mut caller)
-                        .with_context(|| format!("Failed to get Java arg from: {}", java_arg))?
-                };
+        let func_type = FuncType::new(wasm_args, wasm_ret);
+        debug!(
+            "method \"{}\" as function in WASM: {:?}",
+            method_name, func_type
+        );
 
-                debug!(
-                    "Setting parameter {}: {:?} as {:?}",
-                    i,
-                    java_arg,
-                    wasm_value::get_class_name_obj(&env, jvalue)
-                );
+        let func = Func::new(&mut *store, func_type, func);
 
-                env.set_object_array_element(method_args, i as i32, jvalue)
-                    .map_err(Error::from)
-                    .context("Failed to add value to array?")?;
-            }
+        Ok(OpaquePtr::from(func).make_opaque())
+    })
+}
 
-            // get the optional pointer to the arg to store a byte return by ref
-            let ret_by_ref_ptr = java_ret
-                .as_ref()
-                .and_then(|v| v.get_return_by_ref_arg(input_iter));
-
-            debug!("Calling Java method");
-
-            // setup the arguments for the call
-            let method_args = JObject::from(method_args);
-            let val = env
-                .call_method_unchecked(
-                    method.as_obj(),
-                    method_id,
-                    ret,
-                    &[JValue::Object(obj.as_obj()), JValue::Object(method_args)],
-                )
-                .map_err(Error::from)
-                .context("Call to Java method failed!");
-
-            // Check if Java threw an exception.
-            if val.is_err()
-                && env
-                    .exception_check()
-                    .context("Failed to check for exception")?
-            {
-                // get the exception
-                let exception = env
-                    .exception_occurred()
-                    .context("Failed to get exception")?;
-                // clear the exception so that we can make additional java calls
-                env.exception_clear().context("Failed to clear exception")?;
-
-                let err = wasm_exception::exception_to_err(&env, exception);
-                return Err(err.into());
-            }
+/// /*
+/// * Class:     net_bluejekyll_wasmtime_WasmFunction
+/// * Method:    createMultiFunc
+/// * Signature: (JLjava/lang/reflect/Method;Ljava/lang/Object;Ljava/util/List;Ljava/util/List;)J
+/// */
+/// JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmFunction_createMultiFunc
+///  (JNIEnv *, jclass, jlong, jobject, jobject, jobject, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_createMultiFunc<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    method: JObject<'j>,
+    obj: JObject<'j>,
+    result_tys: JObject<'j>,
+    param_tys: JObject<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, move |env| {
+        let method = env.new_global_ref(method)?;
+        let obj = env.new_global_ref(obj)?;
+        let jvm = env.get_java_vm()?;
 
-            // unwrap the exception
-            let val = val?;
+        let method_name = get_method_name(env, &method)?;
+        debug!(
+            "building multi-value WASM function from method: \"{}\"",
+            method_name
+        );
 
-            // Now get the return value
-            let val = wasm_value::from_jvalue(&env, val)?;
-            let result = outputs.get_mut(0);
+        let (wasm_args, java_args) = params_from_java(env, param_tys)?;
 
-            // TODO: much of this logic is duplicitive with that in wasm_value::WasmVal::store_to_args
-            match (val, result) {
-                (Some(WasmVal::Val(val)), Some(result)) => {
-                    debug!("associating {:?} with result", val);
-                    *result = val;
-                }
-                // (Some(WasmVal::ByteBuffer(val)), None) => {
-                //     debug!("allocating space and associating bytes for return by ref");
-                //     let ptr = ret_by_ref_ptr
-                //         .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
-                //     let wasm_alloc = wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
-
-                //     let bytes = env
-                //         .get_direct_buffer_address(val)
-                //         .context("could not get bytes from address")?;
-
-                //     // get mutable reference to the return by ref pointer and then store
-                //     unsafe { pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, byte_array)? };
-
-                // }
-                (Some(WasmVal::ByteArray { jarray, .. }), None) => {
-                    debug!("allocating space and associating bytes for return by ref");
-                    let ptr = ret_by_ref_ptr
-                        .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
-                    let wasm_alloc = wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
-
-                    let len = env
-                        .get_array_length(jarray)
-                        .context("failed to get Java array length")?;
-                    let jbytes = env
-                        .get_byte_array_elements(jarray, ReleaseMode::CopyBack)
-                        .context("failed to get java array elements")?;
-                    let byte_array: &[u8] = unsafe {
-                        slice::from_raw_parts(jbytes.as_ptr() as *const u8, len as usize)
-                    };
-
-                    // get mutable reference to the return by ref pointer and then store
-                    unsafe { pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, byte_array, &mut caller)? };
-                }
-                (Some(WasmVal::String(string)), None) => {
-                    debug!("allocating space and associating string for return by ref");
-                    let ptr = ret_by_ref_ptr
-                        .ok_or_else(|| anyhow!("expected return by ref argument pointer"))?;
-                    let wasm_alloc = wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required"))?;
-
-                    let jstr = env
-                        .get_string(string)
-                        .context("failed to get Java String")?;
-                    let cow = Cow::from(&jstr);
-                    debug!("String from Java for pass_by_ref: {}", cow);
-
-                    let cow_bytes = cow.as_bytes();
-
-                    // get mutable reference to the return by ref pointer and then store
-                    unsafe { pass_bytes_to_wasm_by_ref(wasm_alloc, ptr, cow_bytes, &mut caller)? };
-                }
-                // (Some(WasmVal::ByteBuffer(_val)), Some(_result)) => {
-                //     return Err(anyhow!(
-                //         "Unexpected WASM return value, should have been return by reference"
-                //     )
-                //     .into());
-                // }
-                (Some(WasmVal::ByteArray { .. }), Some(_result)) => {
+        // each result is a single WASM value, there is no return by ref
+        let mut wasm_rets: Vec<ValType> = Vec::new();
+        for class in env.get_list(result_tys)?.iter()? {
+            match wasm_value::from_java_class(env, class.into(), true)? {
+                Some(WasmTy::ValType(ty)) => wasm_rets.push(ty),
+                Some(WasmTy::U32) => wasm_rets.push(ValType::I32),
+                Some(WasmTy::U64) => wasm_rets.push(ValType::I64),
+                ty => {
                     return Err(anyhow!(
-                        "Unexpected WASM return value, should have been return by reference"
-                    )
-                    .into());
+                        "only WASM values are supported as multiple results, found: {:?}",
+                        ty
+                    ))
+                }
+            }
+        }
+
+        let func = move |mut caller: Caller<JavaState>,
+                         inputs: &[Val],
+                         outputs: &mut [Val]|
+              -> Result<(), Trap> {
+            let wasm_alloc = WasmAlloc::from_caller(&mut caller);
+
+            let env = jvm
+                .get_env()
+                .map_err(Error::from)
+                .context("Error accessing JNIEnv in WASM")?;
+
+            in_local_frame(&env, || {
+                let mut input_iter = inputs.iter().cloned();
+                let val = invoke_java_method(
+                    &env,
+                    &method,
+                    &obj,
+                    &java_args,
+                    &mut input_iter,
+                    wasm_alloc.as_ref(),
+                    &mut caller,
+                )?;
+
+                // the method returns an array of WasmTypes
+                let results = val.l().map_err(Error::from)?.into_inner();
+                if results.is_null() {
+                    return Err(
+                        anyhow!("expected {} results, Java returned null", outputs.len()).into(),
+                    );
                 }
-                (Some(WasmVal::String(_)), Some(_result)) => {
+
+                let len = env.get_array_length(results).map_err(Error::from)?;
+                if usize::try_from(len).map_err(Error::from)? != outputs.len() {
                     return Err(anyhow!(
-                        "Unexpected WASM return value, should have been return by reference"
+                        "expected {} results, Java returned {}",
+                        outputs.len(),
+                        len
                     )
                     .into());
                 }
-                (None, Some(result)) => {
-                    debug!("associating null with result");
-                    *result = Val::null();
-                }
-                (Some(val), None) => {
-                    warn!("WASM expected no result, but Java supplied: {:?}", val);
-                }
-                (None, None) => {
-                    debug!("returning no result");
+
+                for (i, output) in outputs.iter_mut().enumerate() {
+                    let result = env
+                        .get_object_array_element(results, i as jsize)
+                        .map_err(Error::from)?;
+
+                    // wasmtime checks that the result types match
+                    *output = wasm_value::val_from_java(&env, result)
+                        .with_context(|| format!("failed to convert result at index: {}", i))?;
                 }
-            }
 
-            Ok(())
+                Ok(())
+            })
         };
 
-        let func_type = FuncType::new(wasm_args, wasm_ret);
+        let func_type = FuncType::new(wasm_args, wasm_rets);
         debug!(
             "method \"{}\" as function in WASM: {:?}",
             method_name, func_type
@@ -337,6 +425,132 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_createFunc<'j>(
     })
 }
 
+/// /*
+/// * Class:     net_bluejekyll_wasmtime_WasmFunction
+/// * Method:    createDirectFunc
+/// * Signature: (JLjava/lang/Class;Ljava/lang/String;Ljava/lang/String;Ljava/lang/Object;)J
+/// */
+/// JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmFunction_createDirectFunc
+///  (JNIEnv *, jclass, jlong, jclass, jstring, jstring, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_createDirectFunc<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    clazz: JClass<'j>,
+    name: JString<'j>,
+    descriptor: JString<'j>,
+    obj: JObject<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, move |env| {
+        let name: String = env.get_string(name)?.into();
+        let descriptor: String = env.get_string(descriptor)?.into();
+        debug!(
+            "building direct WASM function from method: \"{}{}\"",
+            name, descriptor
+        );
+
+        let signature = TypeSignature::from_str(&descriptor)?;
+        let primitives = signature
+            .args
+            .iter()
+            .map(java_primitive)
+            .collect::<Result<Vec<_>, _>>()?;
+        let wasm_args = primitives.iter().map(|p| p.val_type()).collect::<Vec<_>>();
+        let wasm_ret = match &signature.ret {
+            JavaType::Primitive(Primitive::Void) => vec![],
+            ret => vec![java_primitive(ret)?.val_type()],
+        };
+
+        // a null obj is a static method, the class is held to keep the method ID valid
+        let target = if obj.is_null() {
+            let method = env.get_static_method_id(clazz, &name, &descriptor)?;
+            DirectTarget::Static(env.new_global_ref(clazz)?, method.into())
+        } else {
+            let method = env.get_method_id(clazz, &name, &descriptor)?;
+            DirectTarget::Instance(env.new_global_ref(obj)?, method.into())
+        };
+
+        let ret = signature.ret;
+        let jvm = env.get_java_vm()?;
+
+        // The WASM values are passed as the primitives of the Java method, no objects are created for the call
+        let func = move |_caller: Caller<JavaState>,
+                         inputs: &[Val],
+                         outputs: &mut [Val]|
+              -> Result<(), Trap> {
+            let env = jvm
+                .get_env()
+                .map_err(Error::from)
+                .context("Error accessing JNIEnv in WASM")?;
+
+            in_local_frame(&env, || {
+                let args = primitives
+                    .iter()
+                    .zip(inputs)
+                    .map(|(primitive, val)| primitive.to_jvalue(val))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let val = match &target {
+                    DirectTarget::Static(class, method) => env.call_static_method_unchecked(
+                        class,
+                        method.get_static(),
+                        ret.clone(),
+                        &args,
+                    ),
+                    DirectTarget::Instance(obj, method) => {
+                        env.call_method_unchecked(obj.as_obj(), method.get(), ret.clone(), &args)
+                    }
+                };
+
+                check_java_exception(&env)?;
+                let val = val
+                    .map_err(Error::from)
+                    .context("Call to Java method failed!")?;
+
+                if let Some(output) = outputs.get_mut(0) {
+                    *output = wasm_value::primitive_to_val(val)?;
+                }
+
+                Ok(())
+            })
+        };
+
+        let func_type = FuncType::new(wasm_args, wasm_ret);
+        debug!(
+            "method \"{}\" as direct function in WASM: {:?}",
+            name, func_type
+        );
+
+        let func = Func::new(&mut *store, func_type, func);
+
+        Ok(OpaquePtr::from(func).make_opaque())
+    })
+}
+
+/// The receiver of a direct call to a Java method
+enum DirectTarget {
+    Static(GlobalRef, MethodId),
+    Instance(GlobalRef, MethodId),
+}
+
+fn java_primitive(ty: &JavaType) -> Result<JavaPrimitive, Error> {
+    match ty {
+        JavaType::Primitive(Primitive::Boolean) => Ok(JavaPrimitive::Boolean),
+        JavaType::Primitive(Primitive::Byte) => Ok(JavaPrimitive::Byte),
+        JavaType::Primitive(Primitive::Char) => Ok(JavaPrimitive::Char),
+        JavaType::Primitive(Primitive::Short) => Ok(JavaPrimitive::Short),
+        JavaType::Primitive(Primitive::Int) => Ok(JavaPrimitive::Int),
+        JavaType::Primitive(Primitive::Long) => Ok(JavaPrimitive::Long),
+        JavaType::Primitive(Primitive::Float) => Ok(JavaPrimitive::Float),
+        JavaType::Primitive(Primitive::Double) => Ok(JavaPrimitive::Double),
+        _ => Err(anyhow!(
+            "Unsupported Java type for a direct function: {}",
+            ty
+        )),
+    }
+}
+
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_WasmFunction
 ///  * Method:    freeFunc
@@ -376,37 +590,14 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_callNtv<'j>(
         &env,
         || JObject::null().into_inner(),
         move |env| {
-            let len = env.get_array_length(args)?;
-            let len = usize::try_from(len)?;
-            let mut wasm_args = Vec::with_capacity(len);
-
             let wasm_alloc = if !instance.is_null() {
                 WasmAlloc::from_instance(&instance, &mut *store)
             } else {
                 None
             };
 
-            // let droppers will cleanup allocated memory in the WASM module after the function call
-            //   or should the callee drop?? hmm...
-            let mut wasm_droppers = Vec::with_capacity(len);
-
-            // we need to convert all the parameters to WASM vals for the call
-            debug!("got {} args for function", len);
-            for i in 0..len {
-                let obj = env
-                    .get_object_array_element(args, i32::try_from(i)?)
-                    .with_context(|| format!("could not get array index: {} len: {}", i, len))?;
-
-                let val = wasm_value::from_java(env, obj)
-                    .with_context(|| format!("failed to convert argument at index: {}", i))?;
-
-                debug!("adding arg: {}", val.ty());
-                if let Some(dropper) =
-                    val.store_to_args(env, &mut wasm_args, wasm_alloc.as_ref(), &mut *store)?
-                {
-                    wasm_droppers.push(dropper);
-                }
-            }
+            let (mut wasm_args, _wasm_droppers) =
+                args_from_java(env, args, wasm_alloc.as_ref(), &mut store)?;
 
             // now we may need to add a return_by_ref parameter
             let wasm_return_ty = wasm_value::from_java_class(env, return_type, true)?;
@@ -436,7 +627,7 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_callNtv<'j>(
 
             if val.len() > 1 {
                 return Err(anyhow!(
-                    "multiple return values not supported, expected 0 or 1 found: {}",
+                    "multiple return values require callMulti, expected 0 or 1 found: {}",
                     val.len()
                 ));
             }
@@ -462,6 +653,223 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_callNtv<'j>(
     )
 }
 
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmFunction
+///  * Method:    callMultiNtv
+///  * Signature: (JJJ[Lnet/bluejekyll/wasmtime/ty/WasmType;)[Lnet/bluejekyll/wasmtime/ty/WasmType;
+///  */
+///  JNIEXPORT jobjectArray JNICALL Java_net_bluejekyll_wasmtime_WasmFunction_callMultiNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jobjectArray);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmFunction_callMultiNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    func: OpaquePtr<'j, Func>,
+    instance: OpaquePtr<'j, Instance>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    args: jobjectArray,
+) -> jobjectArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        move |env| {
+            let wasm_alloc = if !instance.is_null() {
+                WasmAlloc::from_instance(&instance, &mut *store)
+            } else {
+                None
+            };
+
+            let (wasm_args, _wasm_droppers) =
+                args_from_java(env, args, wasm_alloc.as_ref(), &mut store)?;
+
+            // the results are all returned directly, the values are replaced by the call
+            let mut results: Vec<Val> = func.ty(&*store).results().map(|_| Val::null()).collect();
+
+            func.call(&mut *store, &wasm_args, &mut results)
+                .with_context(|| format!("failed to execute wasm function: {:?}", *func))?;
+
+            let array =
+                env.new_object_array(jsize::try_from(results.len())?, WASM_TYPE, JObject::null())?;
+            for (i, result) in results.iter().enumerate() {
+                let obj = wasm_value::to_java(env, result)?;
+                env.set_object_array_element(array, jsize::try_from(i)?, obj)?;
+            }
+
+            Ok(array)
+        },
+    )
+}
+
+/// Collects the WASM param types for the Java classes of the method parameters
+fn params_from_java<'j>(
+    env: &JNIEnv<'j>,
+    param_tys: JObject<'j>,
+) -> Result<(Vec<ValType>, Vec<WasmTy>), Error> {
+    let param_list = env.get_list(param_tys)?;
+    let mut wasm_args: Vec<ValType> = Vec::with_capacity(param_list.size()? as usize);
+    let mut java_args: Vec<WasmTy> = Vec::with_capacity(wasm_args.len());
+
+    for class in param_list.iter()? {
+        // this is a list of classes
+        let val = wasm_value::from_java_class(env, class.into(), false)
+            .context("error converting type to wasm")?;
+        let val = val.ok_or_else(|| anyhow!("Null parameters not allowed"))?;
+        debug!(
+            "Mapping parameter from {:?} to {:?}",
+            wasm_value::get_class_name(env, class.into())?,
+            val
+        );
+
+        val.push_arg_tys(&mut wasm_args);
+        java_args.push(val);
+    }
+
+    Ok((wasm_args, java_args))
+}
+
+/// Converts the WASM inputs to the Java args and invokes the method, exceptions thrown by the method are returned
+///   as a Trap.
+///
+/// The inputs are left after the last Java arg, e.g. for the return by ref pointer.
+fn invoke_java_method<'j>(
+    env: &JNIEnv<'j>,
+    method: &GlobalRef,
+    obj: &GlobalRef,
+    java_args: &[WasmTy],
+    input_iter: &mut impl Iterator<Item = Val>,
+    wasm_alloc: Option<&WasmAlloc>,
+    caller: &mut Caller<JavaState>,
+) -> Result<JValue<'j>, Trap> {
+    let cache = jni_cache::get(env)?;
+
+    // the invoke method on the method, the result is always Object
+    let ret = JavaType::Object(String::from("java/lang/Object"));
+    let method_id = cache.method_invoke.get();
+
+    // build up parameters
+    let method_args = env
+        .new_object_array(
+            java_args.len() as i32,
+            cache.object.as_class(),
+            JObject::null(),
+        )
+        .map_err(Error::from)
+        .context("Could not create empty array?")?;
+
+    // set the parameters
+    for (i, java_arg) in java_args.iter().enumerate() {
+        let jvalue = unsafe {
+            java_arg
+                .load_from_args(env, &mut *input_iter, wasm_alloc, &mut *caller)
+                .with_context(|| format!("Failed to get Java arg from: {}", java_arg))?
+        };
+
+        debug!(
+            "Setting parameter {}: {:?} as {:?}",
+            i,
+            java_arg,
+            wasm_value::get_class_name_obj(env, jvalue)
+        );
+
+        env.set_object_array_element(method_args, i as i32, jvalue)
+            .map_err(Error::from)
+            .context("Failed to add value to array?")?;
+    }
+
+    debug!("Calling Java method");
+
+    // setup the arguments for the call
+    let method_args = JObject::from(method_args);
+    let val = env
+        .call_method_unchecked(
+            method.as_obj(),
+            method_id,
+            ret,
+            &[JValue::Object(obj.as_obj()), JValue::Object(method_args)],
+        )
+        .map_err(Error::from)
+        .context("Call to Java method failed!");
+
+    // Check if Java threw an exception.
+    if val.is_err() {
+        check_java_exception(env)?;
+    }
+
+    // unwrap the exception
+    Ok(val?)
+}
+
+/// Runs a call from WASM to Java in a new local reference frame, the local references created for the call are freed
+///   when it returns, otherwise they would only be freed when the native method that called into WASM returns.
+fn in_local_frame<R>(env: &JNIEnv<'_>, f: impl FnOnce() -> Result<R, Trap>) -> Result<R, Trap> {
+    env.push_local_frame(LOCAL_FRAME_CAPACITY)
+        .map_err(Error::from)
+        .context("Failed to push local frame")?;
+
+    let result = f();
+
+    env.pop_local_frame(JObject::null())
+        .map_err(Error::from)
+        .context("Failed to pop local frame")?;
+    result
+}
+
+/// Any exception thrown by Java is cleared and returned as a Trap
+fn check_java_exception(env: &JNIEnv<'_>) -> Result<(), Trap> {
+    if env
+        .exception_check()
+        .context("Failed to check for exception")?
+    {
+        // get the exception
+        let exception = env
+            .exception_occurred()
+            .context("Failed to get exception")?;
+        // clear the exception so that we can make additional java calls
+        env.exception_clear().context("Failed to clear exception")?;
+
+        let err = wasm_exception::exception_to_err(env, exception);
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+/// Converts the Java args for a call to WASM.
+///
+/// The returned wrappers free the memory allocated in the WASM module for the args when dropped, after the call.
+fn args_from_java<'j, 'w>(
+    env: &JNIEnv<'j>,
+    args: jobjectArray,
+    wasm_alloc: Option<&'w WasmAlloc>,
+    store: &mut Store<JavaState>,
+) -> Result<(Vec<Val>, Vec<WasmSliceWrapper<'w>>), Error> {
+    let len = env.get_array_length(args)?;
+    let len = usize::try_from(len)?;
+    let mut wasm_args = Vec::with_capacity(len);
+
+    // let droppers will cleanup allocated memory in the WASM module after the function call
+    //   or should the callee drop?? hmm...
+    let mut wasm_droppers = Vec::with_capacity(len);
+
+    // we need to convert all the parameters to WASM vals for the call
+    debug!("got {} args for function", len);
+    for i in 0..len {
+        let obj = env
+            .get_object_array_element(args, i32::try_from(i)?)
+            .with_context(|| format!("could not get array index: {} len: {}", i, len))?;
+
+        let val = wasm_value::from_java(env, obj)
+            .with_context(|| format!("failed to convert argument at index: {}", i))?;
+
+        debug!("adding arg: {}", val.ty());
+        if let Some(dropper) = val.store_to_args(env, &mut wasm_args, wasm_alloc, &mut *store)? {
+            wasm_droppers.push(dropper);
+        }
+    }
+
+    Ok((wasm_args, wasm_droppers))
+}
+
 fn get_method_name<'j, O>(env: &JNIEnv<'j>, object: O) -> Result<String, Error>
 where
     O: Into<JObject<'j>>,
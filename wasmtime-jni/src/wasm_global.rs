@@ -0,0 +1,98 @@
+use jni::objects::{JClass, JObject};
+use jni::sys::jobject;
+use jni::JNIEnv;
+use wasmtime::{ExternType, Global, Store};
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+use crate::wasm_module;
+use crate::wasm_state::JavaState;
+use crate::wasm_value;
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmGlobal
+///  * Method:    freeGlobal
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmGlobal_freeGlobal
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmGlobal_freeGlobal<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    global: OpaquePtr<'j, Global>,
+) {
+    drop(global.take());
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmGlobal
+///  * Method:    getTypeNtv
+///  * Signature: (JJ)Lnet/bluejekyll/wasmtime/ty/GlobalType;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmGlobal_getTypeNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmGlobal_getTypeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    global: OpaquePtr<'j, Global>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let ty = ExternType::Global(global.ty(&*store));
+            Ok(wasm_module::extern_type_to_java(env, ty)?.into_inner())
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmGlobal
+///  * Method:    getNtv
+///  * Signature: (JJ)Lnet/bluejekyll/wasmtime/ty/WasmType;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmGlobal_getNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmGlobal_getNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    global: OpaquePtr<'j, Global>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let val = global.get(&mut *store);
+            Ok(wasm_value::to_java(env, &val)?.into_inner())
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmGlobal
+///  * Method:    setNtv
+///  * Signature: (JJLnet/bluejekyll/wasmtime/ty/WasmType;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmGlobal_setNtv
+///  (JNIEnv *, jclass, jlong, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmGlobal_setNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    global: OpaquePtr<'j, Global>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    value: JObject<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let val = wasm_value::val_from_java(env, value)?;
+
+        // wasmtime checks the mutability and the type of the value
+        global.set(&mut *store, val)?;
+        Ok(())
+    })
+}
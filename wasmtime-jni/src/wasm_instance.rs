@@ -62,3 +62,99 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmInstance_getFunctionNtv<
         Ok(func_ptr)
     })
 }
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmInstance
+///  * Method:    getMemoryNtv
+///  * Signature: (JJLjava/lang/String;)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmInstance_getMemoryNtv
+///  (JNIEnv *, jclass, jlong, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmInstance_getMemoryNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    instance: OpaquePtr<'j, Instance>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    name: JString<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let name = env.get_string(name)?;
+        let name: Cow<str> = Cow::from(&name);
+
+        let memory = instance.get_memory(&mut *store, &name);
+
+        let memory_ptr = if let Some(memory) = memory {
+            debug!("found memory in WASM: {}:{:?}", name, memory.ty(&*store));
+            OpaquePtr::from(memory).make_opaque()
+        } else {
+            0
+        };
+
+        Ok(memory_ptr)
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmInstance
+///  * Method:    getGlobalNtv
+///  * Signature: (JJLjava/lang/String;)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmInstance_getGlobalNtv
+///  (JNIEnv *, jclass, jlong, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmInstance_getGlobalNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    instance: OpaquePtr<'j, Instance>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    name: JString<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let name = env.get_string(name)?;
+        let name: Cow<str> = Cow::from(&name);
+
+        let global = instance.get_global(&mut *store, &name);
+
+        let global_ptr = if let Some(global) = global {
+            debug!("found global in WASM: {}:{:?}", name, global.ty(&*store));
+            OpaquePtr::from(global).make_opaque()
+        } else {
+            0
+        };
+
+        Ok(global_ptr)
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmInstance
+///  * Method:    getTableNtv
+///  * Signature: (JJLjava/lang/String;)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmInstance_getTableNtv
+///  (JNIEnv *, jclass, jlong, jlong, jstring);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmInstance_getTableNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    instance: OpaquePtr<'j, Instance>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    name: JString<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let name = env.get_string(name)?;
+        let name: Cow<str> = Cow::from(&name);
+
+        let table = instance.get_table(&mut *store, &name);
+
+        let table_ptr = if let Some(table) = table {
+            debug!("found table in WASM: {}:{:?}", name, table.ty(&*store));
+            OpaquePtr::from(table).make_opaque()
+        } else {
+            0
+        };
+
+        Ok(table_ptr)
+    })
+}
@@ -1,13 +1,14 @@
 use std::borrow::Cow;
 
-use jni::objects::{JClass, JString};
-use jni::sys::jlong;
+use jni::objects::{JClass, JObject, JString};
+use jni::sys::{jboolean, jlong, JNI_TRUE};
 use jni::JNIEnv;
-use wasmtime::{Func, Linker, Module, Store};
+use wasmtime::{Func, Global, GlobalType, Linker, Module, Mutability, Store};
 
 use crate::opaque_ptr::OpaquePtr;
 use crate::wasm_exception;
 use crate::wasm_state::JavaState;
+use crate::wasm_value;
 
 /// /*
 /// * Class:     net_bluejekyll_wasmtime_WasmLinker
@@ -54,6 +55,44 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmLinker_defineFunc<'j>(
     })
 }
 
+/// /*
+/// * Class:     net_bluejekyll_wasmtime_WasmLinker
+/// * Method:    defineGlobalNtv
+/// * Signature: (JJLjava/lang/String;Ljava/lang/String;Lnet/bluejekyll/wasmtime/ty/WasmType;Z)V
+/// */
+/// JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmLinker_defineGlobalNtv
+///  (JNIEnv *, jclass, jlong, jlong, jstring, jstring, jobject, jboolean);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmLinker_defineGlobalNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut linker: OpaquePtr<'j, Linker<JavaState>>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    module: JString<'j>,
+    name: JString<'j>,
+    value: JObject<'j>,
+    mutable: jboolean,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let module = env.get_string(module)?;
+        let name = env.get_string(name)?;
+
+        let module: Cow<str> = Cow::from(&module);
+        let name: Cow<str> = Cow::from(&name);
+
+        let val = wasm_value::val_from_java(env, value)?;
+        let mutability = if mutable == JNI_TRUE {
+            Mutability::Var
+        } else {
+            Mutability::Const
+        };
+
+        let global = Global::new(&mut *store, GlobalType::new(val.ty(), mutability), val)?;
+        linker.define(&module, &name, global)?;
+        Ok(())
+    })
+}
+
 /// /*
 /// * Class:     net_bluejekyll_wasmtime_WasmLinker
 /// * Method:    instantiateNtv
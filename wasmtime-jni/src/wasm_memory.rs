@@ -0,0 +1,273 @@
+use std::convert::TryFrom;
+
+use anyhow::{anyhow, Context, Error};
+use jni::objects::{JClass, JObject};
+use jni::sys::{jbyteArray, jdouble, jfloat, jint, jlong, jobject};
+use jni::JNIEnv;
+use wasmtime::{Memory, Store};
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+use crate::wasm_state::JavaState;
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    freeMemory
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_freeMemory
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_freeMemory<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+) {
+    drop(memory.take());
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    sizeNtv
+///  * Signature: (JJ)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_sizeNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_sizeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jlong {
+    wasm_exception::attempt(&env, |_env| Ok(jlong::try_from(memory.size(&*store))?))
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    dataSizeNtv
+///  * Signature: (JJ)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_dataSizeNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_dataSizeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jlong {
+    wasm_exception::attempt(&env, |_env| Ok(jlong::try_from(memory.data_size(&*store))?))
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    growNtv
+///  * Signature: (JJJ)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_growNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_growNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    delta: jlong,
+) -> jlong {
+    wasm_exception::attempt(&env, |_env| {
+        let delta = u64::try_from(delta)?;
+        let previous = memory
+            .grow(&mut *store, delta)
+            .with_context(|| format!("failed to grow memory by {} pages", delta))?;
+
+        Ok(jlong::try_from(previous)?)
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    readNtv
+///  * Signature: (JJJI)[B
+///  */
+///  JNIEXPORT jbyteArray JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_readNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jint);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_readNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+    offset: jlong,
+    length: jint,
+) -> jbyteArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let mut bytes = vec![0; usize::try_from(length)?];
+            read(&memory, &store, offset, &mut bytes)?;
+
+            Ok(env.byte_array_from_slice(&bytes)?)
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    writeNtv
+///  * Signature: (JJJ[B)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_writeNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jbyteArray);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_writeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    offset: jlong,
+    bytes: jbyteArray,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let bytes = env.convert_byte_array(bytes)?;
+        write(&memory, &mut store, offset, &bytes)
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmMemory
+///  * Method:    directViewNtv
+///  * Signature: (JJJI)Ljava/nio/ByteBuffer;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmMemory_directViewNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jint);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmMemory_directViewNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    memory: OpaquePtr<'j, Memory>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    offset: jlong,
+    length: jint,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let start = usize::try_from(offset)?;
+            let end = start
+                .checked_add(usize::try_from(length)?)
+                .ok_or_else(|| anyhow!("view of {} bytes at {} overflows", length, offset))?;
+
+            let data = memory.data_mut(&mut *store);
+            let data_size = data.len();
+            let view = data.get_mut(start..end).ok_or_else(|| {
+                anyhow!(
+                    "out of bounds view of {} bytes at {}, memory size: {}",
+                    length,
+                    offset,
+                    data_size
+                )
+            })?;
+
+            // The buffer aliases WASM memory, it's only valid until the memory grows, which Java
+            //  checks with the Store's memory generation
+            let buffer = env.new_direct_byte_buffer(view)?;
+            Ok(buffer.into_inner())
+        },
+    )
+}
+
+/// Generates the natives for reading and writing little-endian primitives, e.g. readI32Ntv (JJJ)I and writeI32Ntv (JJJI)V
+macro_rules! typed_access {
+    ($read:ident, $write:ident, $jty:ty) => {
+        #[no_mangle]
+        pub extern "system" fn $read<'j>(
+            env: JNIEnv<'j>,
+            _class: JClass<'j>,
+            memory: OpaquePtr<'j, Memory>,
+            store: OpaquePtr<'j, Store<JavaState>>,
+            offset: jlong,
+        ) -> $jty {
+            wasm_exception::attempt(&env, |_env| {
+                let mut bytes = [0; std::mem::size_of::<$jty>()];
+                read(&memory, &store, offset, &mut bytes)?;
+
+                Ok(<$jty>::from_le_bytes(bytes))
+            })
+        }
+
+        #[no_mangle]
+        pub extern "system" fn $write<'j>(
+            env: JNIEnv<'j>,
+            _class: JClass<'j>,
+            memory: OpaquePtr<'j, Memory>,
+            mut store: OpaquePtr<'j, Store<JavaState>>,
+            offset: jlong,
+            val: $jty,
+        ) {
+            wasm_exception::attempt(&env, |_env| {
+                let bytes = val.to_le_bytes();
+                write(&memory, &mut store, offset, &bytes)
+            })
+        }
+    };
+}
+
+typed_access!(
+    Java_net_bluejekyll_wasmtime_WasmMemory_readI32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmMemory_writeI32Ntv,
+    jint
+);
+typed_access!(
+    Java_net_bluejekyll_wasmtime_WasmMemory_readI64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmMemory_writeI64Ntv,
+    jlong
+);
+typed_access!(
+    Java_net_bluejekyll_wasmtime_WasmMemory_readF32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmMemory_writeF32Ntv,
+    jfloat
+);
+typed_access!(
+    Java_net_bluejekyll_wasmtime_WasmMemory_readF64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmMemory_writeF64Ntv,
+    jdouble
+);
+
+/// Bounds checked read of `bytes.len()` bytes at the offset
+fn read(
+    memory: &Memory,
+    store: &Store<JavaState>,
+    offset: jlong,
+    bytes: &mut [u8],
+) -> Result<(), Error> {
+    let start = usize::try_from(offset)?;
+    memory.read(store, start, bytes).with_context(|| {
+        format!(
+            "out of bounds read of {} bytes at {}, memory size: {}",
+            bytes.len(),
+            offset,
+            memory.data_size(store)
+        )
+    })
+}
+
+/// Bounds checked write of all the bytes at the offset
+fn write(
+    memory: &Memory,
+    store: &mut Store<JavaState>,
+    offset: jlong,
+    bytes: &[u8],
+) -> Result<(), Error> {
+    let start = usize::try_from(offset)?;
+    memory.write(&mut *store, start, bytes).with_context(|| {
+        format!(
+            "out of bounds write of {} bytes at {}, memory size: {}",
+            bytes.len(),
+            offset,
+            memory.data_size(&*store)
+        )
+    })
+}
@@ -1,8 +1,13 @@
-use jni::objects::JClass;
+use std::convert::TryFrom;
+
+use anyhow::{anyhow, Error};
+use jni::objects::{JClass, JObject, JValue};
+use jni::sys::{jbyteArray, jint, jintArray, jlong, jobjectArray, jsize};
 use jni::JNIEnv;
-use wasmtime::Module;
+use wasmtime::{ExternType, Module, Mutability, ValType};
 
 use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
 
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_WasmModule
@@ -19,3 +24,204 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmModule_freeModule<'j>(
 ) {
     drop(module.take());
 }
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmModule
+///  * Method:    serializeNtv
+///  * Signature: (J)[B
+///  */
+///  JNIEXPORT jbyteArray JNICALL Java_net_bluejekyll_wasmtime_WasmModule_serializeNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmModule_serializeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    module: OpaquePtr<'j, Module>,
+) -> jbyteArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let bytes = module.serialize()?;
+            Ok(env.byte_array_from_slice(&bytes)?)
+        },
+    )
+}
+
+const IMPORT_TYPE: &str = "net/bluejekyll/wasmtime/ty/ImportType";
+const EXPORT_TYPE: &str = "net/bluejekyll/wasmtime/ty/ExportType";
+const FUNC_TYPE: &str = "net/bluejekyll/wasmtime/ty/FuncType";
+const GLOBAL_TYPE: &str = "net/bluejekyll/wasmtime/ty/GlobalType";
+const TABLE_TYPE: &str = "net/bluejekyll/wasmtime/ty/TableType";
+const MEMORY_TYPE: &str = "net/bluejekyll/wasmtime/ty/MemoryType";
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmModule
+///  * Method:    getImportsNtv
+///  * Signature: (J)[Lnet/bluejekyll/wasmtime/ty/ImportType;
+///  */
+///  JNIEXPORT jobjectArray JNICALL Java_net_bluejekyll_wasmtime_WasmModule_getImportsNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmModule_getImportsNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    module: OpaquePtr<'j, Module>,
+) -> jobjectArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let imports = module.imports();
+            let array = env.new_object_array(
+                jsize::try_from(imports.len())?,
+                IMPORT_TYPE,
+                JObject::null(),
+            )?;
+
+            for (i, import) in imports.enumerate() {
+                let name = import
+                    .name()
+                    .ok_or_else(|| anyhow!("imports without a name require module linking"))?;
+
+                let module_name = env.new_string(import.module())?;
+                let name = env.new_string(name)?;
+                let ty = extern_type_to_java(env, import.ty())?;
+
+                let import = env.new_object(
+                    IMPORT_TYPE,
+                    "(Ljava/lang/String;Ljava/lang/String;Lnet/bluejekyll/wasmtime/ty/ExternType;)V",
+                    &[
+                        JObject::from(module_name).into(),
+                        JObject::from(name).into(),
+                        ty.into(),
+                    ],
+                )?;
+
+                env.set_object_array_element(array, jsize::try_from(i)?, import)?;
+            }
+
+            Ok(array)
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmModule
+///  * Method:    getExportsNtv
+///  * Signature: (J)[Lnet/bluejekyll/wasmtime/ty/ExportType;
+///  */
+///  JNIEXPORT jobjectArray JNICALL Java_net_bluejekyll_wasmtime_WasmModule_getExportsNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmModule_getExportsNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    module: OpaquePtr<'j, Module>,
+) -> jobjectArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let exports = module.exports();
+            let array = env.new_object_array(
+                jsize::try_from(exports.len())?,
+                EXPORT_TYPE,
+                JObject::null(),
+            )?;
+
+            for (i, export) in exports.enumerate() {
+                let name = env.new_string(export.name())?;
+                let ty = extern_type_to_java(env, export.ty())?;
+
+                let export = env.new_object(
+                    EXPORT_TYPE,
+                    "(Ljava/lang/String;Lnet/bluejekyll/wasmtime/ty/ExternType;)V",
+                    &[JObject::from(name).into(), ty.into()],
+                )?;
+
+                env.set_object_array_element(array, jsize::try_from(i)?, export)?;
+            }
+
+            Ok(array)
+        },
+    )
+}
+
+/// The ordinal of the ValType enum in Java
+fn val_type_ordinal(ty: &ValType) -> jint {
+    match ty {
+        ValType::I32 => 0,
+        ValType::I64 => 1,
+        ValType::F32 => 2,
+        ValType::F64 => 3,
+        ValType::V128 => 4,
+        ValType::FuncRef => 5,
+        ValType::ExternRef => 6,
+    }
+}
+
+fn val_types_to_java(
+    env: &JNIEnv<'_>,
+    tys: impl ExactSizeIterator<Item = ValType>,
+) -> Result<jintArray, Error> {
+    let ordinals: Vec<jint> = tys.map(|ty| val_type_ordinal(&ty)).collect();
+
+    let array = env.new_int_array(jsize::try_from(ordinals.len())?)?;
+    env.set_int_array_region(array, 0, &ordinals)?;
+    Ok(array)
+}
+
+pub(crate) fn extern_type_to_java<'j>(
+    env: &JNIEnv<'j>,
+    ty: ExternType,
+) -> Result<JObject<'j>, Error> {
+    let obj = match ty {
+        ExternType::Func(func) => {
+            let params = val_types_to_java(env, func.params())?;
+            let results = val_types_to_java(env, func.results())?;
+
+            env.new_object(
+                FUNC_TYPE,
+                "([I[I)V",
+                &[JObject::from(params).into(), JObject::from(results).into()],
+            )?
+        }
+        ExternType::Global(global) => env.new_object(
+            GLOBAL_TYPE,
+            "(IZ)V",
+            &[
+                JValue::Int(val_type_ordinal(global.content())),
+                JValue::Bool((global.mutability() == Mutability::Var).into()),
+            ],
+        )?,
+        ExternType::Table(table) => env.new_object(
+            TABLE_TYPE,
+            "(IJJ)V",
+            &[
+                JValue::Int(val_type_ordinal(table.element())),
+                JValue::Long(jlong::from(table.minimum())),
+                // unbounded maximums are passed as -1
+                JValue::Long(table.maximum().map_or(-1, jlong::from)),
+            ],
+        )?,
+        ExternType::Memory(memory) => env.new_object(
+            MEMORY_TYPE,
+            "(JJZ)V",
+            &[
+                JValue::Long(jlong::try_from(memory.minimum())?),
+                JValue::Long(
+                    memory
+                        .maximum()
+                        .map(jlong::try_from)
+                        .transpose()?
+                        .unwrap_or(-1),
+                ),
+                JValue::Bool(memory.is_64().into()),
+            ],
+        )?,
+        ty => return Err(anyhow!("module linking is not supported: {:?}", ty)),
+    };
+
+    Ok(obj)
+}
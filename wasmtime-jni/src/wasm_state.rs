@@ -1,29 +1,326 @@
-use anyhow::{Context, Error};
-use jni::JNIEnv;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 
-use wasmtime_wasi::{sync::WasiCtxBuilder, WasiCtx};
+use anyhow::Error;
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::sys::jint;
+use jni::{JNIEnv, JavaVM};
+use log::{debug, warn};
+use wasmtime::ResourceLimiter;
+use wasmtime_wasi::WasiCtx;
 
-/// Store associated data, currently empty
+use crate::wasi_config::WasiConfig;
+use crate::wasm_epoch::EpochDeadline;
+
+/// Store associated data
 pub struct JavaState {
     wasi: WasiCtx,
+    stdio: StdioBuffers,
+    limits: ResourceLimits,
+    fuel_added: u64,
+    epoch_deadline: Option<EpochDeadline>,
 }
 
 impl JavaState {
-    pub fn new(_env: JNIEnv<'_>) -> Result<Self, Error> {
-        // TODO: Security considerations here, we don't want to capture the parent processes env
-        //  we probably also want custom filehandles for the stdio of the module as well...
-        //
-        // see: https://docs.wasmtime.dev/examples-rust-wasi.html
-        let wasi = WasiCtxBuilder::new()
-            .inherit_stdio()
-            .inherit_args()
-            .context("failed to establish WASI context")?
-            .build();
+    /// The WASI context only has access to the host resources granted in the config
+    pub fn new(env: JNIEnv<'_>, wasi_config: &WasiConfig) -> Result<Self, Error> {
+        let (wasi, stdio) = wasi_config.build(&env)?;
 
-        Ok(JavaState { wasi })
+        Ok(JavaState {
+            wasi,
+            stdio,
+            limits: ResourceLimits::default(),
+            fuel_added: 0,
+            epoch_deadline: None,
+        })
     }
 
     pub fn wasi_mut(&mut self) -> &mut WasiCtx {
         &mut self.wasi
     }
+
+    /// Buffers capturing the output of the WASI context, if configured
+    pub fn stdio(&self) -> &StdioBuffers {
+        &self.stdio
+    }
+
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+
+    pub fn limits_mut(&mut self) -> &mut ResourceLimits {
+        &mut self.limits
+    }
+
+    /// For `Store::limiter`, the Store only reads the instance and memory counts when this is registered
+    pub fn limiter(&mut self) -> &mut dyn ResourceLimiter {
+        &mut self.limits
+    }
+
+    /// Total fuel that has been added to the Store, Wasmtime only tracks the consumed fuel
+    pub fn fuel_added(&self) -> u64 {
+        self.fuel_added
+    }
+
+    pub fn record_fuel_added(&mut self, fuel: u64) {
+        self.fuel_added = self.fuel_added.saturating_add(fuel);
+    }
+
+    /// Replaces any existing deadline, None clears the deadline
+    pub fn set_epoch_deadline(&mut self, deadline: Option<EpochDeadline>) {
+        self.epoch_deadline = deadline;
+    }
+
+    pub fn take_epoch_deadline(&mut self) -> Option<EpochDeadline> {
+        self.epoch_deadline.take()
+    }
+}
+
+/// Wasmtime's defaults for the count of instances and memories in a Store
+const DEFAULT_COUNT_LIMIT: usize = 10000;
+
+/// Limits on the resources WASM may create in a Store, by default only the Wasmtime defaults apply
+pub struct ResourceLimits {
+    memory_size: Option<usize>,
+    table_elements: Option<u32>,
+    instances: usize,
+    memories: usize,
+    /// Incremented whenever a memory grows, which may move the memory; boxed for a stable address
+    memory_generation: Box<AtomicU64>,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            memory_size: None,
+            table_elements: None,
+            instances: DEFAULT_COUNT_LIMIT,
+            memories: DEFAULT_COUNT_LIMIT,
+            memory_generation: Box::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// The maximum size in bytes of any single linear memory
+    pub fn set_memory_size(&mut self, bytes: usize) {
+        self.memory_size = Some(bytes);
+    }
+
+    /// The maximum number of elements in any single table
+    pub fn set_table_elements(&mut self, elements: u32) {
+        self.table_elements = Some(elements);
+    }
+
+    pub fn set_instances(&mut self, instances: usize) {
+        self.instances = instances;
+    }
+
+    pub fn set_memories(&mut self, memories: usize) {
+        self.memories = memories;
+    }
+
+    /// Views of memory from before a change in the generation may no longer be valid
+    pub fn memory_generation(&self) -> &AtomicU64 {
+        &self.memory_generation
+    }
+}
+
+impl ResourceLimiter for ResourceLimits {
+    fn memory_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> bool {
+        let allowed = self.memory_size.map_or(true, |max| desired <= max);
+        if !allowed {
+            debug!(
+                "denied memory growth from {} to {} bytes, limit: {:?}",
+                current, desired, self.memory_size
+            );
+        } else if desired > current && maximum.map_or(true, |max| desired <= max) {
+            // this is called before the memory grows, only growth that will happen invalidates the views,
+            //   i.e. not growing by 0 or past the memory's own maximum. An allocation failure still invalidates them.
+            self.memory_generation.fetch_add(1, Ordering::AcqRel);
+        }
+
+        allowed
+    }
+
+    fn table_growing(&mut self, current: u32, desired: u32, _maximum: Option<u32>) -> bool {
+        let allowed = self.table_elements.map_or(true, |max| desired <= max);
+        if !allowed {
+            debug!(
+                "denied table growth from {} to {} elements, limit: {:?}",
+                current, desired, self.table_elements
+            );
+        }
+
+        allowed
+    }
+
+    fn instances(&self) -> usize {
+        self.instances
+    }
+
+    fn memories(&self) -> usize {
+        self.memories
+    }
+}
+
+/// The in memory buffers for stdout and stderr, when they are captured
+#[derive(Default)]
+pub struct StdioBuffers {
+    pub stdout: Option<SharedBuffer>,
+    pub stderr: Option<SharedBuffer>,
+}
+
+/// An in memory buffer shared between the WASI pipe and the Store
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+        match self.0.lock() {
+            Ok(buf) => buf,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Returns everything written so far, leaving the buffer empty
+    pub fn take(&self) -> Vec<u8> {
+        mem::take(&mut *self.lock())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Forwards WASI output to a `java.io.OutputStream`
+pub(crate) struct JavaOutputStream {
+    vm: JavaVM,
+    stream: GlobalRef,
+}
+
+impl JavaOutputStream {
+    pub fn new(env: &JNIEnv<'_>, stream: GlobalRef) -> Result<Self, Error> {
+        Ok(Self {
+            vm: env.get_java_vm()?,
+            stream,
+        })
+    }
+}
+
+impl Write for JavaOutputStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let env = self.vm.get_env().map_err(to_io_error)?;
+
+        let len = buf.len().min(jint::MAX as usize);
+        let array = env
+            .byte_array_from_slice(&buf[..len])
+            .map_err(to_io_error)?;
+
+        let result = env.call_method(
+            self.stream.as_obj(),
+            "write",
+            "([BII)V",
+            &[
+                JValue::from(JObject::from(array)),
+                JValue::from(0),
+                JValue::from(len as jint),
+            ],
+        );
+
+        env.delete_local_ref(JObject::from(array))
+            .map_err(to_io_error)?;
+        result.map_err(|err| java_io_error(&env, err))?;
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let env = self.vm.get_env().map_err(to_io_error)?;
+
+        env.call_method(self.stream.as_obj(), "flush", "()V", &[])
+            .map_err(|err| java_io_error(&env, err))?;
+        Ok(())
+    }
+}
+
+/// Forwards WASI input from a `java.io.InputStream`
+pub(crate) struct JavaInputStream {
+    vm: JavaVM,
+    stream: GlobalRef,
+}
+
+impl JavaInputStream {
+    pub fn new(env: &JNIEnv<'_>, stream: GlobalRef) -> Result<Self, Error> {
+        Ok(Self {
+            vm: env.get_java_vm()?,
+            stream,
+        })
+    }
+}
+
+impl Read for JavaInputStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let env = self.vm.get_env().map_err(to_io_error)?;
+
+        let len = buf.len().min(jint::MAX as usize) as jint;
+        let array = env.new_byte_array(len).map_err(to_io_error)?;
+
+        let result = env
+            .call_method(
+                self.stream.as_obj(),
+                "read",
+                "([BII)I",
+                &[
+                    JValue::from(JObject::from(array)),
+                    JValue::from(0),
+                    JValue::from(len),
+                ],
+            )
+            .and_then(|read| read.i());
+
+        let read = match result {
+            // -1 is the end of the stream
+            Ok(read) if read <= 0 => 0,
+            Ok(read) => {
+                let read = read as usize;
+                let bytes = env.convert_byte_array(array).map_err(to_io_error)?;
+                buf[..read].copy_from_slice(&bytes[..read]);
+                read
+            }
+            Err(err) => {
+                let _ = env.delete_local_ref(JObject::from(array));
+                return Err(java_io_error(&env, err));
+            }
+        };
+
+        env.delete_local_ref(JObject::from(array))
+            .map_err(to_io_error)?;
+        Ok(read)
+    }
+}
+
+fn to_io_error(err: jni::errors::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.to_string())
+}
+
+/// Any exception from the stream is cleared, WASI reports the failure to the module as an errno
+fn java_io_error(env: &JNIEnv<'_>, err: jni::errors::Error) -> io::Error {
+    if let jni::errors::Error::JavaException = err {
+        warn!("exception thrown by Java stream used for WASI stdio");
+        if let Err(err) = env.exception_clear() {
+            return to_io_error(err);
+        }
+    }
+
+    to_io_error(err)
 }
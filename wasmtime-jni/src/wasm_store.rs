@@ -1,9 +1,20 @@
-use jni::objects::JClass;
+use std::convert::TryFrom;
+use std::mem;
+use std::slice;
+use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+
+use anyhow::{Context, Error};
+use jni::objects::{JClass, JObject};
+use jni::sys::{jbyteArray, jlong, jobject};
 use jni::JNIEnv;
-use wasmtime::Store;
+use log::debug;
+use wasmtime::{Func, Store};
 
 use crate::opaque_ptr::OpaquePtr;
-use crate::wasm_state::JavaState;
+use crate::wasm_epoch;
+use crate::wasm_exception;
+use crate::wasm_state::{JavaState, SharedBuffer};
 
 // TODO: consider requiring a background thread per store?
 
@@ -22,3 +33,350 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_freeStore<'j>(
 ) {
     drop(store.take());
 }
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    addFuelNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_addFuelNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_addFuelNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    fuel: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let fuel = u64::try_from(fuel)?;
+        store.add_fuel(fuel)?;
+        store.data_mut().record_fuel_added(fuel);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    fuelConsumedNtv
+///  * Signature: (J)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmStore_fuelConsumedNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_fuelConsumedNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jlong {
+    wasm_exception::attempt_or_else(
+        &env,
+        || -1,
+        |_env| {
+            // None when fuel consumption is not enabled on the engine
+            store
+                .fuel_consumed()
+                .map_or(Ok(-1), |fuel| jlong::try_from(fuel).map_err(Into::into))
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    fuelRemainingNtv
+///  * Signature: (J)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmStore_fuelRemainingNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_fuelRemainingNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jlong {
+    wasm_exception::attempt_or_else(
+        &env,
+        || -1,
+        |_env| {
+            let consumed = match store.fuel_consumed() {
+                Some(consumed) => consumed,
+                None => return Ok(-1),
+            };
+
+            // the final instructions before the trap may overdraw the budget
+            let remaining = store.data().fuel_added().saturating_sub(consumed);
+            Ok(jlong::try_from(remaining)?)
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setEpochDeadlineNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setEpochDeadlineNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setEpochDeadlineNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    ticks_beyond_current: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let ticks = u64::try_from(ticks_beyond_current)?;
+        set_epoch_deadline(&mut store, ticks)
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setDeadlineNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setDeadlineNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setDeadlineNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    timeout_millis: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let timeout = Duration::from_millis(u64::try_from(timeout_millis)?);
+        set_epoch_deadline(&mut store, wasm_epoch::ticks_for(timeout))
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    clearEpochDeadlineNtv
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_clearEpochDeadlineNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_clearEpochDeadlineNtv<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+) {
+    clear_epoch_deadline(&mut store);
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setMemorySizeLimitNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setMemorySizeLimitNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setMemorySizeLimitNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    bytes: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let bytes = usize::try_from(bytes)?;
+        store.data_mut().limits_mut().set_memory_size(bytes);
+        store.limiter(JavaState::limiter);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setTableElementsLimitNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setTableElementsLimitNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setTableElementsLimitNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    elements: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let elements = u32::try_from(elements)?;
+        store.data_mut().limits_mut().set_table_elements(elements);
+        store.limiter(JavaState::limiter);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setInstancesLimitNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setInstancesLimitNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setInstancesLimitNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    instances: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let instances = usize::try_from(instances)?;
+        store.data_mut().limits_mut().set_instances(instances);
+        store.limiter(JavaState::limiter);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    setMemoriesLimitNtv
+///  * Signature: (JJ)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmStore_setMemoriesLimitNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_setMemoriesLimitNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    memories: jlong,
+) {
+    wasm_exception::attempt(&env, |_env| {
+        let memories = usize::try_from(memories)?;
+        store.data_mut().limits_mut().set_memories(memories);
+        store.limiter(JavaState::limiter);
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    readStdoutNtv
+///  * Signature: (J)[B
+///  */
+///  JNIEXPORT jbyteArray JNICALL Java_net_bluejekyll_wasmtime_WasmStore_readStdoutNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_readStdoutNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jbyteArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let buffer = store
+                .data()
+                .stdio()
+                .stdout
+                .as_ref()
+                .context("stdout is not captured to a buffer in the WasiConfig")?;
+
+            take_buffer(env, buffer)
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    readStderrNtv
+///  * Signature: (J)[B
+///  */
+///  JNIEXPORT jbyteArray JNICALL Java_net_bluejekyll_wasmtime_WasmStore_readStderrNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_readStderrNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jbyteArray {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let buffer = store
+                .data()
+                .stdio()
+                .stderr
+                .as_ref()
+                .context("stderr is not captured to a buffer in the WasiConfig")?;
+
+            take_buffer(env, buffer)
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmStore
+///  * Method:    memoryGenerationNtv
+///  * Signature: (J)Ljava/nio/ByteBuffer;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmStore_memoryGenerationNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmStore_memoryGenerationNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let generation = store.data().limits().memory_generation();
+
+            // Safety: AtomicU64 has the same in-memory representation as u64 and is boxed for the life of the Store,
+            //  Java only reads the value from the thread using the Store, and never after the Store is closed.
+            let generation = unsafe {
+                slice::from_raw_parts_mut(
+                    generation as *const AtomicU64 as *mut u8,
+                    mem::size_of::<AtomicU64>(),
+                )
+            };
+
+            let buffer = env.new_direct_byte_buffer(generation)?;
+            Ok(buffer.into_inner())
+        },
+    )
+}
+
+fn take_buffer(env: &JNIEnv<'_>, buffer: &SharedBuffer) -> Result<jbyteArray, Error> {
+    let bytes = buffer.take();
+    Ok(env.byte_array_from_slice(&bytes)?)
+}
+
+fn set_epoch_deadline(store: &mut Store<JavaState>, ticks: u64) -> Result<(), Error> {
+    let handle = store
+        .interrupt_handle()
+        .context("epoch interruption must be enabled in the WasmConfig")?;
+
+    clear_epoch_deadline(store);
+    let deadline = wasm_epoch::ticker()?.deadline(ticks, handle);
+    store.data_mut().set_epoch_deadline(Some(deadline));
+    Ok(())
+}
+
+/// Removes the deadline, consuming the interrupt if it fired after WASM returned.
+///
+/// Wasmtime can not reset a pending interrupt, it is consumed by the next entry into WASM, so a no-op
+///  function is called to keep it from interrupting an unrelated call.
+fn clear_epoch_deadline(store: &mut Store<JavaState>) {
+    let fired = match store.data_mut().take_epoch_deadline() {
+        Some(deadline) => deadline.clear(),
+        None => return,
+    };
+
+    if fired {
+        let noop = Func::wrap(&mut *store, || {});
+        match noop.call(&mut *store, &[], &mut []) {
+            Ok(()) => debug!("the interrupt for the deadline was consumed by WASM"),
+            Err(trap) => debug!("consumed the pending interrupt for the deadline: {}", trap),
+        }
+    }
+}
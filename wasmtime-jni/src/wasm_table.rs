@@ -0,0 +1,158 @@
+use std::convert::TryFrom;
+
+use anyhow::{anyhow, Context};
+use jni::objects::{JClass, JObject};
+use jni::sys::{jlong, jobject};
+use jni::JNIEnv;
+use wasmtime::{ExternType, Store, Table};
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+use crate::wasm_module;
+use crate::wasm_state::JavaState;
+use crate::wasm_value;
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    freeTable
+///  * Signature: (J)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmTable_freeTable
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_freeTable<'j>(
+    _env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+) {
+    drop(table.take());
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    getTypeNtv
+///  * Signature: (JJ)Lnet/bluejekyll/wasmtime/ty/TableType;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmTable_getTypeNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_getTypeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let ty = ExternType::Table(table.ty(&*store));
+            Ok(wasm_module::extern_type_to_java(env, ty)?.into_inner())
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    sizeNtv
+///  * Signature: (JJ)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmTable_sizeNtv
+///  (JNIEnv *, jclass, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_sizeNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+    store: OpaquePtr<'j, Store<JavaState>>,
+) -> jlong {
+    wasm_exception::attempt(&env, |_env| Ok(jlong::from(table.size(&*store))))
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    getNtv
+///  * Signature: (JJJ)Lnet/bluejekyll/wasmtime/ty/WasmType;
+///  */
+///  JNIEXPORT jobject JNICALL Java_net_bluejekyll_wasmtime_WasmTable_getNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_getNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    index: jlong,
+) -> jobject {
+    wasm_exception::attempt_or_else(
+        &env,
+        || JObject::null().into_inner(),
+        |env| {
+            let index = u32::try_from(index)?;
+            let val = table.get(&mut *store, index).ok_or_else(|| {
+                anyhow!(
+                    "index {} out of bounds of table with size {}",
+                    index,
+                    table.size(&*store)
+                )
+            })?;
+
+            Ok(wasm_value::to_java(env, &val)?.into_inner())
+        },
+    )
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    setNtv
+///  * Signature: (JJJLnet/bluejekyll/wasmtime/ty/WasmType;)V
+///  */
+///  JNIEXPORT void JNICALL Java_net_bluejekyll_wasmtime_WasmTable_setNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_setNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    index: jlong,
+    value: JObject<'j>,
+) {
+    wasm_exception::attempt(&env, |env| {
+        let index = u32::try_from(index)?;
+        let val = wasm_value::val_from_java(env, value)?;
+
+        table
+            .set(&mut *store, index, val)
+            .with_context(|| format!("failed to set table element at index {}", index))?;
+        Ok(())
+    })
+}
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_WasmTable
+///  * Method:    growNtv
+///  * Signature: (JJJLnet/bluejekyll/wasmtime/ty/WasmType;)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_WasmTable_growNtv
+///  (JNIEnv *, jclass, jlong, jlong, jlong, jobject);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_WasmTable_growNtv<'j>(
+    env: JNIEnv<'j>,
+    _class: JClass<'j>,
+    table: OpaquePtr<'j, Table>,
+    mut store: OpaquePtr<'j, Store<JavaState>>,
+    delta: jlong,
+    init: JObject<'j>,
+) -> jlong {
+    wasm_exception::attempt(&env, |env| {
+        let delta = u32::try_from(delta)?;
+        let init = wasm_value::val_from_java(env, init)?;
+
+        let previous = table
+            .grow(&mut *store, delta, init)
+            .with_context(|| format!("failed to grow table by {} elements", delta))?;
+
+        Ok(jlong::from(previous))
+    })
+}
@@ -0,0 +1,110 @@
+use anyhow::Context;
+use jni::objects::JClass;
+use jni::sys::{jdouble, jint, jlong};
+use jni::JNIEnv;
+use wasmtime::{Func, Store, TypedFunc};
+
+use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
+use crate::wasm_state::JavaState;
+
+/// Natives for calling a function with a fixed signature, the type of the function is checked once when the
+///   TypedFunc is created, the calls pass primitives directly without any conversion.
+macro_rules! typed_func {
+    ($typed:ident, $free:ident, $call:ident, ($($param:ident: $pty:ty),*) -> $ret:ty) => {
+        #[no_mangle]
+        pub extern "system" fn $typed<'j>(
+            env: JNIEnv<'j>,
+            _class: JClass<'j>,
+            func: OpaquePtr<'j, Func>,
+            store: OpaquePtr<'j, Store<JavaState>>,
+        ) -> jlong {
+            wasm_exception::attempt(&env, |_env| {
+                let typed = func
+                    .typed::<($($pty,)*), $ret, _>(&*store)
+                    .with_context(|| {
+                        format!(
+                            "function is not {}: {:?}",
+                            stringify!(($($pty),*) -> $ret),
+                            func.ty(&*store)
+                        )
+                    })?;
+
+                Ok(OpaquePtr::from(typed).make_opaque())
+            })
+        }
+
+        #[no_mangle]
+        pub extern "system" fn $free<'j>(
+            _env: JNIEnv<'j>,
+            _class: JClass<'j>,
+            typed: OpaquePtr<'j, TypedFunc<($($pty,)*), $ret>>,
+        ) {
+            drop(typed.take());
+        }
+
+        #[no_mangle]
+        pub extern "system" fn $call<'j>(
+            env: JNIEnv<'j>,
+            _class: JClass<'j>,
+            typed: OpaquePtr<'j, TypedFunc<($($pty,)*), $ret>>,
+            mut store: OpaquePtr<'j, Store<JavaState>>,
+            $($param: $pty),*
+        ) -> $ret {
+            wasm_exception::attempt(&env, |_env| {
+                typed
+                    .call(&mut *store, ($($param,)*))
+                    .with_context(|| format!("failed to execute wasm function: {:?}", typed.func()))
+            })
+        }
+    };
+}
+
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedVoidToVoidNtv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeVoidToVoidNtv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callVoidToVoidNtv,
+    () -> ()
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedI32ToVoidNtv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeI32ToVoidNtv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callI32ToVoidNtv,
+    (a: jint) -> ()
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedI32ToI32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeI32ToI32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callI32ToI32Ntv,
+    (a: jint) -> jint
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedI32I32ToI32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeI32I32ToI32Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callI32I32ToI32Ntv,
+    (a: jint, b: jint) -> jint
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedI64ToI64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeI64ToI64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callI64ToI64Ntv,
+    (a: jlong) -> jlong
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedI64I64ToI64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeI64I64ToI64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callI64I64ToI64Ntv,
+    (a: jlong, b: jlong) -> jlong
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedF64ToF64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeF64ToF64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callF64ToF64Ntv,
+    (a: jdouble) -> jdouble
+);
+typed_func!(
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_typedF64F64ToF64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_freeF64F64ToF64Ntv,
+    Java_net_bluejekyll_wasmtime_WasmTypedFunction_callF64F64ToF64Ntv,
+    (a: jdouble, b: jdouble) -> jdouble
+);
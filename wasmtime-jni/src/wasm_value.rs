@@ -1,31 +1,27 @@
 use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::fmt;
 use std::marker::PhantomData;
 use std::slice;
 
-use anyhow::{anyhow, Context, Error};
-use jni::objects::{JClass, JObject, JString, JValue, ReleaseMode};
-use jni::sys::jbyteArray;
+use anyhow::{anyhow, ensure, Context, Error};
+use jni::objects::{GlobalRef, JByteBuffer, JClass, JObject, JString, JValue, ReleaseMode};
+use jni::signature::{JavaType, Primitive};
+use jni::sys::{jarray, jbyteArray, jsize};
 use jni::JNIEnv;
 use log::debug;
-use wasmtime::{AsContextMut, Store, Val, ValType};
+use wasmtime::{AsContextMut, ExternRef, Func, Store, Val, ValType};
 use wasmtime_jni_exports::WasmAllocated;
 
-use crate::ty::{Abi, ReturnAbi, WasmAlloc, WasmSlice, WasmSliceWrapper};
+use crate::jni_cache::{self, JniCache};
+use crate::opaque_ptr::OpaquePtr;
+use crate::ty::{
+    Abi, ReturnAbi, WasmAlloc, WasmElement, WasmElementSlice, WasmSlice, WasmSliceWrapper,
+};
 use crate::wasm_state::JavaState;
 
-const CLASS: &str = "Ljava/lang/Class;";
-const I64: &str = "net/bluejekyll/wasmtime/ty/I64";
-const I32: &str = "net/bluejekyll/wasmtime/ty/I32";
-const F64: &str = "net/bluejekyll/wasmtime/ty/F64";
-const F32: &str = "net/bluejekyll/wasmtime/ty/F32";
-const WASM_VOID: &str = "net/bluejekyll/wasmtime/ty/WasmVoid";
-const VOID: &str = "java/lang/Void";
 const STRING: &str = "java/lang/String";
 const BYTE_ARRAY: &str = "[B";
-const PRIMITIVE: &str = "TYPE";
-
-const BYTE_BUFFER: &str = "java/nio/ByteBuffer";
 
 pub(crate) fn get_class_name_obj<'j>(env: &JNIEnv<'j>, obj: JObject<'j>) -> Result<String, Error> {
     get_class_name(env, env.get_object_class(obj)?)
@@ -44,6 +40,14 @@ pub(crate) enum WasmTy {
     ByteBuffer,
     ByteArray,
     String,
+    /// Passed as an i32, zero-extended to a long in Java
+    U32,
+    /// Passed as an i64, the bits of the long in Java
+    U64,
+    /// A Java primitive or its boxed class, passed as the natural WASM value type
+    Primitive(JavaPrimitive),
+    /// A Java int[], long[], float[] or double[], passed as a slice of the elements like a WasmSlice
+    Array(JavaPrimitive),
     ValType(ValType),
 }
 
@@ -56,47 +60,62 @@ impl From<ValType> for WasmTy {
 impl WasmTy {
     pub fn push_arg_tys(&self, args: &mut Vec<ValType>) {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::push_arg_tys(args)
             }
-            WasmTy::ValType(ValType::I32) => i32::push_arg_tys(args),
-            WasmTy::ValType(ValType::I64) => i64::push_arg_tys(args),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => i32::push_arg_tys(args),
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => i64::push_arg_tys(args),
             WasmTy::ValType(ValType::F32) => f32::push_arg_tys(args),
             WasmTy::ValType(ValType::F64) => f64::push_arg_tys(args),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(primitive) => args.push(primitive.val_type()),
+            // reference types are passed as a single Val
+            WasmTy::ValType(ty) => args.push(ty.clone()),
         }
     }
 
-    pub fn matches_arg_tys(&self, tys: impl Iterator<Item = ValType>) -> anyhow::Result<()> {
+    pub fn matches_arg_tys(&self, mut tys: impl Iterator<Item = ValType>) -> anyhow::Result<()> {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::matches_arg_tys(tys)
             }
-            WasmTy::ValType(ValType::I32) => i32::matches_arg_tys(tys),
-            WasmTy::ValType(ValType::I64) => i64::matches_arg_tys(tys),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => i32::matches_arg_tys(tys),
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => i64::matches_arg_tys(tys),
             WasmTy::ValType(ValType::F32) => f32::matches_arg_tys(tys),
             WasmTy::ValType(ValType::F64) => f64::matches_arg_tys(tys),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(primitive) => {
+                WasmTy::ValType(primitive.val_type()).matches_arg_tys(tys)
+            }
+            WasmTy::ValType(ty) => {
+                let next = tys.next();
+                ensure!(
+                    next.as_ref() == Some(ty),
+                    "Expected ty for next arg: {:?} got: {:?}",
+                    ty,
+                    next
+                );
+
+                Ok(())
+            }
         }
     }
 
     pub fn get_return_by_ref_arg(&self, args: impl Iterator<Item = Val>) -> Option<i32> {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::get_return_by_ref_arg(args)
             }
-            WasmTy::ValType(ValType::I32) => i32::get_return_by_ref_arg(args),
-            WasmTy::ValType(ValType::I64) => i64::get_return_by_ref_arg(args),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => i32::get_return_by_ref_arg(args),
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => i64::get_return_by_ref_arg(args),
             WasmTy::ValType(ValType::F32) => f32::get_return_by_ref_arg(args),
             WasmTy::ValType(ValType::F64) => f64::get_return_by_ref_arg(args),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(_) | WasmTy::ValType(_) => None,
         }
     }
 
     pub(crate) unsafe fn load_from_args<'j>(
         &self,
         env: &JNIEnv<'j>,
-        args: impl Iterator<Item = Val>,
+        mut args: impl Iterator<Item = Val>,
         wasm_alloc: Option<&WasmAlloc>,
         store: impl AsContextMut,
     ) -> Result<JObject<'j>, anyhow::Error> {
@@ -114,9 +133,28 @@ impl WasmTy {
                 let wasm_slice = WasmSlice::load_from_args(args)?;
                 IntoString(wasm_slice).into_java(env, wasm_alloc, store)
             }
+            WasmTy::Array(JavaPrimitive::Int) => {
+                let elements = WasmElementSlice::<i32>::load_from_args(args)?;
+                IntoArray(elements).into_java(env, wasm_alloc, store)
+            }
+            WasmTy::Array(JavaPrimitive::Long) => {
+                let elements = WasmElementSlice::<i64>::load_from_args(args)?;
+                IntoArray(elements).into_java(env, wasm_alloc, store)
+            }
+            WasmTy::Array(JavaPrimitive::Float) => {
+                let elements = WasmElementSlice::<f32>::load_from_args(args)?;
+                IntoArray(elements).into_java(env, wasm_alloc, store)
+            }
+            WasmTy::Array(JavaPrimitive::Double) => {
+                let elements = WasmElementSlice::<f64>::load_from_args(args)?;
+                IntoArray(elements).into_java(env, wasm_alloc, store)
+            }
+            WasmTy::Array(element) => Err(anyhow!("Unsupported Java array: {}[]", element)),
             WasmTy::ValType(ValType::I32) => {
                 i32::load_from_args(args)?.into_java(env, wasm_alloc, store)
             }
+            WasmTy::U32 => IntoU32(i32::load_from_args(args)?).into_java(env, wasm_alloc, store),
+            WasmTy::U64 => IntoU64(i64::load_from_args(args)?).into_java(env, wasm_alloc, store),
             WasmTy::ValType(ValType::I64) => {
                 i64::load_from_args(args)?.into_java(env, wasm_alloc, store)
             }
@@ -126,20 +164,32 @@ impl WasmTy {
             WasmTy::ValType(ValType::F64) => {
                 f64::load_from_args(args)?.into_java(env, wasm_alloc, store)
             }
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(primitive) => {
+                let val = args
+                    .next()
+                    .ok_or_else(|| anyhow!("next argument missing, expected: {}", primitive))?;
+                IntoBoxed(*primitive, val).into_java(env, wasm_alloc, store)
+            }
+            WasmTy::ValType(ty) => {
+                let val = args
+                    .next()
+                    .ok_or_else(|| anyhow!("next argument missing, expected: {:?}", ty))?;
+                to_java(env, &val)
+            }
         }
     }
 
     pub(crate) fn return_or_push_arg_tys(&self, args: &mut Vec<ValType>) -> Option<ValType> {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::return_or_push_arg_tys(args)
             }
-            WasmTy::ValType(ValType::I32) => i32::return_or_push_arg_tys(args),
-            WasmTy::ValType(ValType::I64) => i64::return_or_push_arg_tys(args),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => i32::return_or_push_arg_tys(args),
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => i64::return_or_push_arg_tys(args),
             WasmTy::ValType(ValType::F32) => f32::return_or_push_arg_tys(args),
             WasmTy::ValType(ValType::F64) => f64::return_or_push_arg_tys(args),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(primitive) => Some(primitive.val_type()),
+            WasmTy::ValType(ty) => Some(ty.clone()),
         }
     }
 
@@ -151,14 +201,29 @@ impl WasmTy {
         arg_tys: impl Iterator<Item = ValType>,
     ) -> Result<(), anyhow::Error> {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::matches_return_or_arg_tys(ret, arg_tys)
             }
-            WasmTy::ValType(ValType::I32) => i32::matches_return_or_arg_tys(ret, arg_tys),
-            WasmTy::ValType(ValType::I64) => i64::matches_return_or_arg_tys(ret, arg_tys),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => {
+                i32::matches_return_or_arg_tys(ret, arg_tys)
+            }
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => {
+                i64::matches_return_or_arg_tys(ret, arg_tys)
+            }
             WasmTy::ValType(ValType::F32) => f32::matches_return_or_arg_tys(ret, arg_tys),
             WasmTy::ValType(ValType::F64) => f64::matches_return_or_arg_tys(ret, arg_tys),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(primitive) => {
+                WasmTy::ValType(primitive.val_type()).matches_return_or_arg_tys(ret, arg_tys)
+            }
+            WasmTy::ValType(ty) => {
+                ensure!(
+                    ret.as_ref() == Some(ty),
+                    "Expected {:?} but was {:?}",
+                    ty,
+                    ret
+                );
+                Ok(())
+            }
         }
     }
 
@@ -170,14 +235,18 @@ impl WasmTy {
         store: &mut Store<JavaState>,
     ) -> Result<Option<WasmSliceWrapper<'w>>, Error> {
         match self {
-            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String => {
+            WasmTy::ByteBuffer | WasmTy::ByteArray | WasmTy::String | WasmTy::Array(_) => {
                 WasmSlice::return_or_store_to_arg(args, wasm_alloc, store)
             }
-            WasmTy::ValType(ValType::I32) => i32::return_or_store_to_arg(args, wasm_alloc, store),
-            WasmTy::ValType(ValType::I64) => i64::return_or_store_to_arg(args, wasm_alloc, store),
+            WasmTy::ValType(ValType::I32) | WasmTy::U32 => {
+                i32::return_or_store_to_arg(args, wasm_alloc, store)
+            }
+            WasmTy::ValType(ValType::I64) | WasmTy::U64 => {
+                i64::return_or_store_to_arg(args, wasm_alloc, store)
+            }
             WasmTy::ValType(ValType::F32) => f32::return_or_store_to_arg(args, wasm_alloc, store),
             WasmTy::ValType(ValType::F64) => f64::return_or_store_to_arg(args, wasm_alloc, store),
-            WasmTy::ValType(_) => unimplemented!(),
+            WasmTy::Primitive(_) | WasmTy::ValType(_) => Ok(None),
         }
     }
 }
@@ -188,28 +257,102 @@ impl fmt::Display for WasmTy {
             WasmTy::ByteBuffer => write!(f, "ByteBuffer"),
             WasmTy::ByteArray => write!(f, "byte[]"),
             WasmTy::String => write!(f, "String"),
+            WasmTy::U32 => write!(f, "u32"),
+            WasmTy::U64 => write!(f, "u64"),
+            WasmTy::Primitive(primitive) => primitive.fmt(f),
+            WasmTy::Array(element) => write!(f, "{}[]", element),
             WasmTy::ValType(val) => val.fmt(f),
         }
     }
 }
 
+/// The Java primitive types, the smaller integer types and boolean are passed to WASM as an i32
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum JavaPrimitive {
+    Boolean,
+    Byte,
+    Char,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+}
+
+impl JavaPrimitive {
+    pub fn val_type(self) -> ValType {
+        match self {
+            JavaPrimitive::Long => ValType::I64,
+            JavaPrimitive::Float => ValType::F32,
+            JavaPrimitive::Double => ValType::F64,
+            _ => ValType::I32,
+        }
+    }
+
+    /// Converts the WASM value, the i32 is truncated for the smaller types, any non-zero value is true
+    pub fn to_jvalue(self, val: &Val) -> Result<JValue<'static>, Error> {
+        let jvalue = match (self, val) {
+            (JavaPrimitive::Boolean, Val::I32(v)) => JValue::Bool(u8::from(*v != 0)),
+            (JavaPrimitive::Byte, Val::I32(v)) => JValue::Byte(*v as i8),
+            (JavaPrimitive::Char, Val::I32(v)) => JValue::Char(*v as u16),
+            (JavaPrimitive::Short, Val::I32(v)) => JValue::Short(*v as i16),
+            (JavaPrimitive::Int, Val::I32(v)) => JValue::Int(*v),
+            (JavaPrimitive::Long, Val::I64(v)) => JValue::Long(*v),
+            (JavaPrimitive::Float, Val::F32(v)) => JValue::Float(f32::from_bits(*v)),
+            (JavaPrimitive::Double, Val::F64(v)) => JValue::Double(f64::from_bits(*v)),
+            _ => {
+                return Err(anyhow!(
+                    "Expected {} for Java {}, got: {}",
+                    self.val_type(),
+                    self,
+                    val.ty()
+                ))
+            }
+        };
+
+        Ok(jvalue)
+    }
+}
+
+impl fmt::Display for JavaPrimitive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let name = match self {
+            JavaPrimitive::Boolean => "boolean",
+            JavaPrimitive::Byte => "byte",
+            JavaPrimitive::Char => "char",
+            JavaPrimitive::Short => "short",
+            JavaPrimitive::Int => "int",
+            JavaPrimitive::Long => "long",
+            JavaPrimitive::Float => "float",
+            JavaPrimitive::Double => "double",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
 pub(crate) enum WasmVal<'j> {
-    // TODO: go back and review usage of ByteBuffer... need more thought here.
-    // ByteBuffer(JByteBuffer<'j>),
+    ByteBuffer(JByteBuffer<'j>),
     ByteArray {
         jarray: jbyteArray,
         lifetime: PhantomData<&'j ()>,
     },
     String(JString<'j>),
+    Array {
+        jarray: jarray,
+        element: JavaPrimitive,
+        lifetime: PhantomData<&'j ()>,
+    },
     Val(Val),
 }
 
 impl<'j> fmt::Debug for WasmVal<'j> {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
-            // WasmVal::ByteBuffer(_) => write!(f, "ByteBuffer"),
+            WasmVal::ByteBuffer(_) => write!(f, "ByteBuffer"),
             WasmVal::ByteArray { .. } => write!(f, "byte[]"),
             WasmVal::String(_) => write!(f, "String"),
+            WasmVal::Array { element, .. } => write!(f, "{}[]", element),
             WasmVal::Val(val) => val.fmt(f),
         }
     }
@@ -223,11 +366,20 @@ impl<'j> WasmVal<'j> {
         }
     }
 
+    fn from_array(jarray: jarray, element: JavaPrimitive) -> Self {
+        WasmVal::Array {
+            jarray,
+            element,
+            lifetime: PhantomData,
+        }
+    }
+
     pub fn ty(&self) -> WasmTy {
         match self {
-            // WasmVal::ByteBuffer(_) => WasmTy::ByteBuffer,
+            WasmVal::ByteBuffer(_) => WasmTy::ByteBuffer,
             WasmVal::ByteArray { .. } => WasmTy::ByteArray,
             WasmVal::String(_) => WasmTy::String,
+            WasmVal::Array { element, .. } => WasmTy::Array(*element),
             WasmVal::Val(val) => val.ty().into(),
         }
     }
@@ -240,16 +392,18 @@ impl<'j> WasmVal<'j> {
         mut store: S,
     ) -> Result<Option<WasmSliceWrapper<'w>>, anyhow::Error> {
         match self {
-            // WasmVal::ByteBuffer(buffer) => {
-            //     let direct_bytes: &[u8] = env.get_direct_buffer_address(buffer)?;
-
-            //     // the module might not have the memory exported
-            //     let wasm_alloc = wasm_alloc
-            //         .ok_or_else(|| anyhow!("no memory or allocator supplied from module"))?;
-            //     let wasm_slice = wasm_alloc.alloc_bytes(direct_bytes)?;
-            //     wasm_slice.store_to_args(args);
-            //     return Ok(Some(wasm_slice));
-            // }
+            WasmVal::ByteBuffer(buffer) => {
+                // only the bytes from the position to the limit are passed
+                let bytes = jni_cache::get(env)?.byte_buffer.remaining(env, buffer)?;
+
+                // the module might not have the memory exported
+                let wasm_alloc = wasm_alloc
+                    .ok_or_else(|| anyhow!("no memory or allocator supplied from module"))?;
+                let wasm_slice = wasm_alloc.alloc_bytes(&bytes, &mut store)?;
+
+                wasm_slice.store_to_args(args);
+                return Ok(Some(wasm_slice));
+            }
             WasmVal::ByteArray { jarray, .. } => {
                 // This is should be safe, it's copied into while borrowed the WASM context.
                 let len = env.get_array_length(jarray)?;
@@ -283,22 +437,34 @@ impl<'j> WasmVal<'j> {
                 wasm_slice.store_to_args(args);
                 return Ok(Some(wasm_slice));
             }
+            WasmVal::Array {
+                jarray, element, ..
+            } => {
+                // the module might not have the memory exported
+                let wasm_alloc = wasm_alloc
+                    .ok_or_else(|| anyhow!("no memory or allocator supplied from module"))?;
+                let (elements, wasm_bytes) =
+                    alloc_java_array(env, jarray, element, wasm_alloc, store)?;
+
+                elements.store_to_args(args);
+                return Ok(Some(wasm_bytes));
+            }
             WasmVal::Val(val @ Val::I32(_)) => val.unwrap_i32().store_to_args(args),
             WasmVal::Val(val @ Val::I64(_)) => val.unwrap_i64().store_to_args(args),
             WasmVal::Val(val @ Val::F32(_)) => val.unwrap_f32().store_to_args(args),
             WasmVal::Val(val @ Val::F64(_)) => val.unwrap_f64().store_to_args(args),
-            WasmVal::Val(v) => unimplemented!("type not yet supported as an arg: {:?}", v),
+            WasmVal::Val(val) => args.push(val),
         }
 
         Ok(None)
     }
 }
 
-// impl<'j> From<JByteBuffer<'j>> for WasmVal<'j> {
-//     fn from(bytes: JByteBuffer<'j>) -> Self {
-//         WasmVal::ByteBuffer(bytes)
-//     }
-// }
+impl<'j> From<JByteBuffer<'j>> for WasmVal<'j> {
+    fn from(bytes: JByteBuffer<'j>) -> Self {
+        WasmVal::ByteBuffer(bytes)
+    }
+}
 
 impl<'j> From<JString<'j>> for WasmVal<'j> {
     fn from(string: JString<'j>) -> Self {
@@ -331,7 +497,7 @@ impl IntoJavaObject for IntoByteBuffer {
     ) -> Result<JObject<'j>, Error> {
         let wasm_alloc =
             wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required for this return type"))?;
-        let bytes = wasm_alloc.as_mut(self.0, &mut store);
+        let bytes = wasm_alloc.as_mut(self.0, &mut store)?;
 
         debug!(
             "length of bytes for ByteBuffer: {} expected len: {}",
@@ -358,7 +524,7 @@ impl IntoJavaObject for IntoByteArray {
     ) -> Result<JObject<'j>, Error> {
         let wasm_alloc =
             wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required for this return type"))?;
-        let bytes = wasm_alloc.as_mut(self.0, &mut store);
+        let bytes = wasm_alloc.as_mut(self.0, &mut store)?;
 
         debug!(
             "length of bytes for ByteBuffer: {} expected len: {}",
@@ -385,7 +551,7 @@ impl IntoJavaObject for IntoString {
     ) -> Result<JObject<'j>, Error> {
         let wasm_alloc =
             wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required for this return type"))?;
-        let bytes = wasm_alloc.as_mut(self.0, &mut store);
+        let bytes = wasm_alloc.as_mut(self.0, &mut store)?;
 
         debug!(
             "length of bytes for ByteBuffer: {} expected len: {}",
@@ -412,7 +578,9 @@ impl IntoJavaObject for i64 {
         _store: S,
     ) -> Result<JObject<'j>, Error> {
         let jvalue = JValue::Long(self);
-        env.new_object(I64, "(J)V", &[jvalue])
+        jni_cache::get(env)?
+            .i64
+            .new_object(env, jvalue)
             .context("Failed to create new Long")
     }
 }
@@ -425,11 +593,184 @@ impl IntoJavaObject for i32 {
         _store: S,
     ) -> Result<JObject<'j>, Error> {
         let jvalue = JValue::Int(self);
-        env.new_object(I32, "(I)V", &[jvalue])
+        jni_cache::get(env)?
+            .i32
+            .new_object(env, jvalue)
             .context("Failed to create new Integer")
     }
 }
 
+/// The i32 from WASM as a U32 in Java
+struct IntoU32(i32);
+impl IntoJavaObject for IntoU32 {
+    unsafe fn into_java<'j, S: AsContextMut>(
+        self,
+        env: &JNIEnv<'j>,
+        _wasm_alloc: Option<&WasmAlloc>,
+        _store: S,
+    ) -> Result<JObject<'j>, Error> {
+        let jvalue = JValue::Long(i64::from(self.0 as u32));
+        jni_cache::get(env)?
+            .u32
+            .new_object(env, jvalue)
+            .context("Failed to create new U32")
+    }
+}
+
+/// The i64 from WASM as a U64 in Java, the Java long holds the same bits
+struct IntoU64(i64);
+impl IntoJavaObject for IntoU64 {
+    unsafe fn into_java<'j, S: AsContextMut>(
+        self,
+        env: &JNIEnv<'j>,
+        _wasm_alloc: Option<&WasmAlloc>,
+        _store: S,
+    ) -> Result<JObject<'j>, Error> {
+        let jvalue = JValue::Long(self.0);
+        jni_cache::get(env)?
+            .u64
+            .new_object(env, jvalue)
+            .context("Failed to create new U64")
+    }
+}
+
+/// The Java primitive arrays which are passed to WASM as slices of their elements
+trait JavaArrayElement: WasmElement {
+    fn new_array(env: &JNIEnv<'_>, len: jsize) -> jni::errors::Result<jarray>;
+
+    fn get_region(env: &JNIEnv<'_>, array: jarray, buf: &mut [Self]) -> jni::errors::Result<()>;
+
+    fn set_region(env: &JNIEnv<'_>, array: jarray, buf: &[Self]) -> jni::errors::Result<()>;
+}
+
+macro_rules! java_array_element {
+    ($t:ident, $new:ident, $get:ident, $set:ident) => {
+        impl JavaArrayElement for $t {
+            fn new_array(env: &JNIEnv<'_>, len: jsize) -> jni::errors::Result<jarray> {
+                env.$new(len)
+            }
+
+            fn get_region(
+                env: &JNIEnv<'_>,
+                array: jarray,
+                buf: &mut [Self],
+            ) -> jni::errors::Result<()> {
+                env.$get(array, 0, buf)
+            }
+
+            fn set_region(
+                env: &JNIEnv<'_>,
+                array: jarray,
+                buf: &[Self],
+            ) -> jni::errors::Result<()> {
+                env.$set(array, 0, buf)
+            }
+        }
+    };
+}
+
+java_array_element!(
+    i32,
+    new_int_array,
+    get_int_array_region,
+    set_int_array_region
+);
+java_array_element!(
+    i64,
+    new_long_array,
+    get_long_array_region,
+    set_long_array_region
+);
+java_array_element!(
+    f32,
+    new_float_array,
+    get_float_array_region,
+    set_float_array_region
+);
+java_array_element!(
+    f64,
+    new_double_array,
+    get_double_array_region,
+    set_double_array_region
+);
+
+/// Copies the Java array into WASM memory as little-endian elements.
+///
+/// Returns the pointer and the number of elements, as passed to WASM, along with the allocated bytes.
+pub(crate) fn alloc_java_array<'w>(
+    env: &JNIEnv<'_>,
+    jarray: jarray,
+    element: JavaPrimitive,
+    wasm_alloc: &'w WasmAlloc,
+    store: impl AsContextMut,
+) -> Result<(WasmSlice, WasmSliceWrapper<'w>), Error> {
+    fn alloc<'w, T: JavaArrayElement>(
+        env: &JNIEnv<'_>,
+        jarray: jarray,
+        wasm_alloc: &'w WasmAlloc,
+        store: impl AsContextMut,
+    ) -> Result<(WasmSlice, WasmSliceWrapper<'w>), Error> {
+        let len = env.get_array_length(jarray)?;
+        let mut elements = vec![T::default(); usize::try_from(len)?];
+        T::get_region(env, jarray, &mut elements)?;
+
+        let wasm_bytes =
+            wasm_alloc.alloc_aligned_bytes(&T::elements_to_le_bytes(&elements), T::SIZE, store)?;
+        let wasm_elements = WasmElementSlice::<T>::from_bytes(wasm_bytes.wasm_slice())?;
+        debug!("copied {} elements to {:?}", len, wasm_elements);
+
+        let wasm_slice = unsafe { WasmSlice::new(wasm_elements.ptr(), wasm_elements.len()) };
+        Ok((wasm_slice, wasm_bytes))
+    }
+
+    match element {
+        JavaPrimitive::Int => alloc::<i32>(env, jarray, wasm_alloc, store),
+        JavaPrimitive::Long => alloc::<i64>(env, jarray, wasm_alloc, store),
+        JavaPrimitive::Float => alloc::<f32>(env, jarray, wasm_alloc, store),
+        JavaPrimitive::Double => alloc::<f64>(env, jarray, wasm_alloc, store),
+        _ => Err(anyhow!("Unsupported Java array: {}[]", element)),
+    }
+}
+
+/// The elements in WASM memory as a new Java array
+struct IntoArray<T: WasmElement>(WasmElementSlice<T>);
+impl<T: JavaArrayElement> IntoJavaObject for IntoArray<T> {
+    unsafe fn into_java<'j, S: AsContextMut>(
+        self,
+        env: &JNIEnv<'j>,
+        wasm_alloc: Option<&WasmAlloc>,
+        mut store: S,
+    ) -> Result<JObject<'j>, Error> {
+        let wasm_alloc =
+            wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc is required for this return type"))?;
+        let bytes = wasm_alloc.as_mut(self.0.as_bytes()?, &mut store)?;
+        let elements = T::elements_from_le_bytes(bytes);
+        debug!("read {} elements from {:?}", elements.len(), self.0);
+
+        let array = T::new_array(env, self.0.len()).context("Failed to create new array")?;
+        T::set_region(env, array, &elements)?;
+
+        Ok(JObject::from(array))
+    }
+}
+
+/// The WASM value as the boxed Java primitive, e.g. Integer
+struct IntoBoxed(JavaPrimitive, Val);
+impl IntoJavaObject for IntoBoxed {
+    unsafe fn into_java<'j, S: AsContextMut>(
+        self,
+        env: &JNIEnv<'j>,
+        _wasm_alloc: Option<&WasmAlloc>,
+        _store: S,
+    ) -> Result<JObject<'j>, Error> {
+        let jvalue = self.0.to_jvalue(&self.1)?;
+        jni_cache::get(env)?
+            .boxed_class(self.0)
+            .new_object(env, jvalue)
+            .with_context(|| format!("Failed to box Java {}", self.0))
+    }
+}
+
 impl IntoJavaObject for f64 {
     unsafe fn into_java<'j, S: AsContextMut>(
         self,
@@ -438,7 +779,9 @@ impl IntoJavaObject for f64 {
         _store: S,
     ) -> Result<JObject<'j>, Error> {
         let jvalue = JValue::Double(self);
-        env.new_object(F64, "(D)V", &[jvalue])
+        jni_cache::get(env)?
+            .f64
+            .new_object(env, jvalue)
             .context("Failed to create new Double")
     }
 }
@@ -451,7 +794,9 @@ impl IntoJavaObject for f32 {
         _store: S,
     ) -> Result<JObject<'j>, Error> {
         let jvalue = JValue::Float(self);
-        env.new_object(F32, "(F)V", &[jvalue])
+        jni_cache::get(env)?
+            .f32
+            .new_object(env, jvalue)
             .context("Failed to create new Float")
     }
 }
@@ -465,17 +810,44 @@ pub(crate) fn from_java_class<'j>(
         return Ok(None); // FIXME: this should be an exception, right?
     }
 
-    let voidp: JClass = env.get_static_field(VOID, PRIMITIVE, CLASS)?.l()?.into();
+    let cache = jni_cache::get(env)?;
 
     let ty: WasmTy = match clazz {
-        _ if env.is_assignable_from(clazz, I64)? => ValType::I64.into(),
-        _ if env.is_assignable_from(clazz, I32)? => ValType::I32.into(),
-        _ if env.is_assignable_from(clazz, F64)? => ValType::F64.into(),
-        _ if env.is_assignable_from(clazz, F32)? => ValType::F32.into(),
-        _ if env.is_assignable_from(clazz, WASM_VOID)? => return Ok(None),
-        _ if env.is_assignable_from(clazz, VOID)? => return Ok(None),
-        _ if env.is_assignable_from(clazz, voidp)? => return Ok(None),
+        _ if env.is_assignable_from(clazz, cache.i64.as_class())? => ValType::I64.into(),
+        _ if env.is_assignable_from(clazz, cache.i32.as_class())? => ValType::I32.into(),
+        _ if env.is_assignable_from(clazz, cache.u32.as_class())? => WasmTy::U32,
+        _ if env.is_assignable_from(clazz, cache.u64.as_class())? => WasmTy::U64,
+        _ if env.is_assignable_from(clazz, cache.f64.as_class())? => ValType::F64.into(),
+        _ if env.is_assignable_from(clazz, cache.f32.as_class())? => ValType::F32.into(),
+        _ if env.is_assignable_from(clazz, cache.v128.as_class())? => ValType::V128.into(),
+        _ if env.is_assignable_from(clazz, cache.func_ref.as_class())? => ValType::FuncRef.into(),
+        _ if env.is_assignable_from(clazz, cache.extern_ref.as_class())? => {
+            ValType::ExternRef.into()
+        }
+        _ if env.is_assignable_from(clazz, cache.byte_buffer.as_class())? => WasmTy::ByteBuffer,
+        _ if env.is_assignable_from(clazz, cache.byte_array.as_class())? => WasmTy::ByteArray,
+        _ if env.is_assignable_from(clazz, cache.int_array.as_class())? => {
+            WasmTy::Array(JavaPrimitive::Int)
+        }
+        _ if env.is_assignable_from(clazz, cache.long_array.as_class())? => {
+            WasmTy::Array(JavaPrimitive::Long)
+        }
+        _ if env.is_assignable_from(clazz, cache.float_array.as_class())? => {
+            WasmTy::Array(JavaPrimitive::Float)
+        }
+        _ if env.is_assignable_from(clazz, cache.double_array.as_class())? => {
+            WasmTy::Array(JavaPrimitive::Double)
+        }
+        _ if env.is_assignable_from(clazz, cache.wasm_void.as_class())? => return Ok(None),
+        _ if env.is_assignable_from(clazz, cache.void.as_class())? => return Ok(None),
+        _ if env.is_assignable_from(clazz, cache.void_primitive.as_class())? => return Ok(None),
         _ => {
+            for (boxed, primitive) in cache.boxed() {
+                if boxed.is_class(env, clazz)? {
+                    return Ok(Some(WasmTy::Primitive(primitive)));
+                }
+            }
+
             let name = get_class_name(env, clazz)?;
             if !for_return {
                 return Err(anyhow!("Unsupported Java class as argument: {}", name));
@@ -494,28 +866,77 @@ pub(crate) fn from_java<'j, 'b: 'j>(
 ) -> Result<WasmVal<'j>, Error> {
     //let bytea: JClass = env.find_class("[B")?;
 
-    assert!(!obj.is_null(), "obj should not be null for conversion");
+    ensure!(
+        !obj.is_null(),
+        "null argument can not be converted to a WASM value"
+    );
+    let cache = jni_cache::get(env)?;
+
     match obj {
-        _ if env.is_instance_of(obj, I64)? => {
-            let jvalue = env.call_method(obj, "longValue", "()J", &[])?;
+        _ if cache.i64.is_instance(env, obj)? => {
+            let jvalue = cache.i64.get_value(env, obj)?;
             Ok(Val::I64(jvalue.j()?).into())
         }
-        _ if env.is_instance_of(obj, I32)? => {
-            let jvalue = env.call_method(obj, "intValue", "()I", &[])?;
+        _ if cache.i32.is_instance(env, obj)? => {
+            let jvalue = cache.i32.get_value(env, obj)?;
             Ok(Val::I32(jvalue.i()?).into())
         }
-        _ if env.is_instance_of(obj, F64)? => {
-            let jvalue = env.call_method(obj, "doubleValue", "()D", &[])?;
+        _ if cache.u32.is_instance(env, obj)? => {
+            // the constructor checks the range, the low 32 bits are the value
+            let jvalue = cache.u32.get_value(env, obj)?;
+            Ok(Val::I32(jvalue.j()? as i32).into())
+        }
+        _ if cache.u64.is_instance(env, obj)? => {
+            let jvalue = cache.u64.get_value(env, obj)?;
+            Ok(Val::I64(jvalue.j()?).into())
+        }
+        _ if cache.f64.is_instance(env, obj)? => {
+            let jvalue = cache.f64.get_value(env, obj)?;
             Ok(Val::F64(jvalue.d()?.to_bits()).into())
         }
-        _ if env.is_instance_of(obj, F32)? => {
-            let jvalue = env.call_method(obj, "floatValue", "()F", &[])?;
+        _ if cache.f32.is_instance(env, obj)? => {
+            let jvalue = cache.f32.get_value(env, obj)?;
             Ok(Val::F32(jvalue.f()?.to_bits()).into())
         }
-        // _ if env.is_instance_of(obj, BYTE_BUFFER)? => Ok(WasmVal::from(JByteBuffer::from(obj))),
-        // _ if env.is_instance_of(obj, BYTE_ARRAY)? => Ok(WasmVal::from_byte_array(env, *obj)),
+        _ if cache.v128.is_instance(env, obj)? => {
+            let bytes = cache.v128.get_value(env, obj)?.l()?;
+            let bytes = env.convert_byte_array(bytes.into_inner())?;
+            let bytes = <[u8; 16]>::try_from(bytes.as_slice())
+                .map_err(|_| anyhow!("V128 requires 16 bytes, got: {}", bytes.len()))?;
+
+            Ok(Val::V128(u128::from_le_bytes(bytes)).into())
+        }
+        _ if cache.func_ref.is_instance(env, obj)? => {
+            Ok(Val::FuncRef(func_from_java(env, obj)?).into())
+        }
+        _ if cache.extern_ref.is_instance(env, obj)? => {
+            Ok(Val::ExternRef(extern_from_java(env, obj)?).into())
+        }
+        _ if cache.byte_buffer.is_instance(env, obj)? => Ok(WasmVal::from(JByteBuffer::from(obj))),
+        _ if env.is_instance_of(obj, cache.byte_array.as_class())? => {
+            Ok(WasmVal::from_byte_array(env, *obj))
+        }
+        _ if env.is_instance_of(obj, cache.int_array.as_class())? => {
+            Ok(WasmVal::from_array(*obj, JavaPrimitive::Int))
+        }
+        _ if env.is_instance_of(obj, cache.long_array.as_class())? => {
+            Ok(WasmVal::from_array(*obj, JavaPrimitive::Long))
+        }
+        _ if env.is_instance_of(obj, cache.float_array.as_class())? => {
+            Ok(WasmVal::from_array(*obj, JavaPrimitive::Float))
+        }
+        _ if env.is_instance_of(obj, cache.double_array.as_class())? => {
+            Ok(WasmVal::from_array(*obj, JavaPrimitive::Double))
+        }
         // _ if env.is_instance_of(obj, STRING)? => Ok(WasmVal::from(JString::from(obj))),
         _ => {
+            for (boxed, _) in cache.boxed() {
+                if boxed.is_instance(env, obj)? {
+                    let jvalue = boxed.get_value(env, obj)?;
+                    return Ok(primitive_to_val(jvalue)?.into());
+                }
+            }
+
             let clazz = env.get_object_class(obj)?;
             let name = get_class_name(env, clazz)?;
             Err(anyhow!("Unsupported Java object: {}", name))
@@ -527,47 +948,140 @@ pub(crate) fn from_jvalue<'j, 'b: 'j>(
     env: &'b JNIEnv<'j>,
     val: JValue<'j>,
 ) -> Result<Option<WasmVal<'j>>, Error> {
-    let val = match val {
+    match val {
         JValue::Object(obj) => {
             if obj.is_null() {
-                return Ok(None);
+                Ok(None)
             } else {
-                return from_java(env, obj).map(Some);
+                from_java(env, obj).map(Some)
             }
         }
-        JValue::Long(v) => Val::I64(v),
+        _ => Ok(Some(primitive_to_val(val)?.into())),
+    }
+}
+
+/// Converts a Java primitive to the natural WASM value, the smaller types are widened to an i32
+pub(crate) fn primitive_to_val(val: JValue<'_>) -> Result<Val, Error> {
+    let val = match val {
+        JValue::Bool(v) => Val::I32(i32::from(v != 0)),
+        JValue::Byte(v) => Val::I32(i32::from(v)),
+        JValue::Char(v) => Val::I32(i32::from(v)),
+        JValue::Short(v) => Val::I32(i32::from(v)),
         JValue::Int(v) => Val::I32(v),
-        JValue::Double(v) => Val::F64(f64::to_bits(v)),
+        JValue::Long(v) => Val::I64(v),
         JValue::Float(v) => Val::F32(f32::to_bits(v)),
+        JValue::Double(v) => Val::F64(f64::to_bits(v)),
         _ => return Err(anyhow!("Unsuppored return type: {}", val.type_name())),
     };
 
-    Ok(Some(val.into()))
-}
-
-// pub(crate) fn to_java<'j, 'w: 'j>(env: &JNIEnv<'j>, val: &'w Val) -> Result<JObject<'j>, Error> {
-//     let obj = match val {
-//         Val::I64(val) => {
-//             let jvalue = JValue::Long(*val);
-//             env.new_object(LONG, "(J)V", &[jvalue])
-//         }
-//         Val::I32(val) => {
-//             let jvalue = JValue::Int(*val);
-//             env.new_object(INTEGER, "(I)V", &[jvalue])
-//         }
-//         Val::F64(val) => {
-//             let jvalue = JValue::Double(f64::from_bits(*val));
-//             env.new_object(DOUBLE, "(D)V", &[jvalue])
-//         }
-//         Val::F32(val) => {
-//             let jvalue = JValue::Float(f32::from_bits(*val));
-//             env.new_object(FLOAT, "(F)V", &[jvalue])
-//         }
-//         _ => return Err(anyhow!("Unsupported WASM type: {}", val.ty())),
-//     };
-
-//     obj.with_context(|| format!("failed to convert {:?} to java", val))
-// }
+    Ok(val)
+}
+
+pub(crate) fn to_java<'j>(env: &JNIEnv<'j>, val: &Val) -> Result<JObject<'j>, Error> {
+    let cache = jni_cache::get(env)?;
+
+    let obj = match val {
+        Val::I64(val) => cache.i64.new_object(env, JValue::Long(*val)),
+        Val::I32(val) => cache.i32.new_object(env, JValue::Int(*val)),
+        Val::F64(val) => cache
+            .f64
+            .new_object(env, JValue::Double(f64::from_bits(*val))),
+        Val::F32(val) => cache
+            .f32
+            .new_object(env, JValue::Float(f32::from_bits(*val))),
+        Val::V128(val) => {
+            let bytes = env.byte_array_from_slice(&val.to_le_bytes())?;
+            cache.v128.new_object(env, JObject::from(bytes).into())
+        }
+        Val::FuncRef(func) => func_to_java(env, cache, *func),
+        Val::ExternRef(externref) => {
+            let value = if let Some(externref) = externref {
+                extern_value(externref)?
+            } else {
+                JObject::null()
+            };
+
+            cache.extern_ref.new_object(env, JValue::Object(value))
+        }
+        _ => return Err(anyhow!("Unsupported WASM type: {}", val.ty())),
+    };
+
+    obj.with_context(|| format!("failed to convert {:?} to java", val))
+}
+
+/// The FuncRef holds a WasmFunction, or null for a null reference
+fn func_from_java<'j>(env: &JNIEnv<'j>, obj: JObject<'j>) -> Result<Option<Func>, Error> {
+    let cache = jni_cache::get(env)?;
+    let function = cache.func_ref.get_value(env, obj)?.l()?;
+    if function.is_null() {
+        return Ok(None);
+    }
+
+    let ptr = env
+        .call_method_unchecked(
+            function,
+            cache.wasm_function_get_ptr.get(),
+            JavaType::Primitive(Primitive::Long),
+            &[],
+        )?
+        .j()?;
+
+    // dereferencing a null pointer would panic, aborting the JVM
+    ensure!(ptr != 0, "the WasmFunction of the FuncRef is closed");
+    let func = OpaquePtr::<Func>::from_raw(ptr);
+    Ok(Some(*func))
+}
+
+fn func_to_java<'j>(
+    env: &JNIEnv<'j>,
+    cache: &JniCache,
+    func: Option<Func>,
+) -> jni::errors::Result<JObject<'j>> {
+    let function = if let Some(func) = func {
+        let ptr = OpaquePtr::from(func).make_opaque();
+        env.new_object_unchecked(
+            cache.wasm_function.as_class(),
+            cache.wasm_function_ctor.get(),
+            &[JValue::Long(ptr)],
+        )?
+    } else {
+        JObject::null()
+    };
+
+    cache.func_ref.new_object(env, JValue::Object(function))
+}
+
+/// The ExternRef holds any Java object, the object is kept alive by a GlobalRef for as long as WASM references it
+fn extern_from_java<'j>(env: &JNIEnv<'j>, obj: JObject<'j>) -> Result<Option<ExternRef>, Error> {
+    let value = jni_cache::get(env)?.extern_ref.get_value(env, obj)?.l()?;
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let value = env.new_global_ref(value)?;
+    Ok(Some(ExternRef::new(value)))
+}
+
+/// Returns the original Java object passed to WASM
+fn extern_value(externref: &ExternRef) -> Result<JObject<'_>, Error> {
+    externref
+        .data()
+        .downcast_ref::<GlobalRef>()
+        .map(GlobalRef::as_obj)
+        .ok_or_else(|| anyhow!("externref was not created from a Java object"))
+}
+
+/// Converts a Java WasmType to a plain WASM value, e.g. for globals
+pub(crate) fn val_from_java<'j>(env: &JNIEnv<'j>, obj: JObject<'j>) -> Result<Val, Error> {
+    if obj.is_null() {
+        return Err(anyhow!("null is not a WASM value"));
+    }
+
+    match from_java(env, obj)? {
+        WasmVal::Val(val) => Ok(val),
+        val => Err(anyhow!("Not a WASM value type: {}", val.ty())),
+    }
+}
 
 pub(crate) unsafe fn return_or_load_or_from_arg<'a, 'w>(
     env: &JNIEnv<'a>,
@@ -578,18 +1092,18 @@ pub(crate) unsafe fn return_or_load_or_from_arg<'a, 'w>(
     store: &mut Store<JavaState>,
 ) -> Result<JObject<'a>, anyhow::Error> {
     match ty {
-        WasmTy::ByteBuffer => Err(anyhow!("ByteBuffer unsupported as return Java return type")),
-        // WasmTy::ByteBuffer => {
-        //     let wasm_slice =
-        //         WasmSlice::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc)?;
-        //     // we need to drop this returned slice...
-        //     // TODO: would be nice to do this in the return_or_load_or_from_args, move that to WasmSliceWrapper?
-        //     let wasm_slice = WasmSliceWrapper::new(
-        //         wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc required"))?,
-        //         wasm_slice,
-        //     );
-        //     IntoByteBuffer(wasm_slice.wasm_slice()).into_java(env, wasm_alloc)
-        // }
+        WasmTy::ByteBuffer => {
+            let wasm_slice =
+                WasmSlice::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?;
+            let wasm_slice = WasmSliceWrapper::new(
+                wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc required"))?,
+                wasm_slice,
+            );
+
+            // the slice is freed after this, so the bytes are copied into a heap buffer
+            let bytes = IntoByteArray(wasm_slice.wasm_slice()).into_java(env, wasm_alloc, store)?;
+            jni_cache::get(env)?.byte_buffer.wrap(env, bytes)
+        }
         WasmTy::ByteArray => {
             let wasm_slice =
                 WasmSlice::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?;
@@ -612,6 +1126,20 @@ pub(crate) unsafe fn return_or_load_or_from_arg<'a, 'w>(
             i32::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?
                 .into_java(env, wasm_alloc, store)
         }
+        WasmTy::U32 => IntoU32(i32::return_or_load_or_from_args(
+            ret,
+            ret_by_ref_ptr,
+            wasm_alloc,
+            store,
+        )?)
+        .into_java(env, wasm_alloc, store),
+        WasmTy::U64 => IntoU64(i64::return_or_load_or_from_args(
+            ret,
+            ret_by_ref_ptr,
+            wasm_alloc,
+            store,
+        )?)
+        .into_java(env, wasm_alloc, store),
         WasmTy::ValType(ValType::I64) => {
             i64::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?
                 .into_java(env, wasm_alloc, store)
@@ -624,6 +1152,50 @@ pub(crate) unsafe fn return_or_load_or_from_arg<'a, 'w>(
             f64::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?
                 .into_java(env, wasm_alloc, store)
         }
-        WasmTy::ValType(v) => Err(anyhow!("{} unsupported as return Java return type", v)),
+        WasmTy::Array(JavaPrimitive::Int) => {
+            return_array::<i32>(env, ret, ret_by_ref_ptr, wasm_alloc, store)
+        }
+        WasmTy::Array(JavaPrimitive::Long) => {
+            return_array::<i64>(env, ret, ret_by_ref_ptr, wasm_alloc, store)
+        }
+        WasmTy::Array(JavaPrimitive::Float) => {
+            return_array::<f32>(env, ret, ret_by_ref_ptr, wasm_alloc, store)
+        }
+        WasmTy::Array(JavaPrimitive::Double) => {
+            return_array::<f64>(env, ret, ret_by_ref_ptr, wasm_alloc, store)
+        }
+        WasmTy::Array(element) => Err(anyhow!("Unsupported Java array: {}[]", element)),
+        WasmTy::Primitive(primitive) => {
+            let val =
+                ret.ok_or_else(|| anyhow!("Return Val not present, expected: {}", primitive))?;
+            IntoBoxed(primitive, val.clone()).into_java(env, wasm_alloc, store)
+        }
+        WasmTy::ValType(ty) => {
+            let val = ret.ok_or_else(|| anyhow!("Return Val not present, expected: {}", ty))?;
+            to_java(env, val)
+        }
     }
 }
+
+/// Reads the elements returned by reference into a new Java array
+unsafe fn return_array<'a, 'w, T: JavaArrayElement>(
+    env: &JNIEnv<'a>,
+    ret: Option<&Val>,
+    ret_by_ref_ptr: Option<WasmSliceWrapper<'w>>,
+    wasm_alloc: Option<&'w WasmAlloc>,
+    store: &mut Store<JavaState>,
+) -> Result<JObject<'a>, anyhow::Error> {
+    let elements =
+        WasmElementSlice::<T>::return_or_load_or_from_args(ret, ret_by_ref_ptr, wasm_alloc, store)?;
+    let wasm_alloc = wasm_alloc.ok_or_else(|| anyhow!("WasmAlloc required"))?;
+    let array = IntoArray(elements).into_java(env, Some(wasm_alloc), &mut *store);
+
+    // the elements were allocated with the alignment of the element, e.g. as a Vec<i32>,
+    //   they are freed even if the Java array could not be created
+    let dealloc = elements
+        .as_bytes()
+        .and_then(|bytes| wasm_alloc.dealloc_aligned_bytes(bytes, T::SIZE, store));
+    let array = array?;
+    dealloc?;
+    Ok(array)
+}
@@ -1,14 +1,17 @@
 use std::ffi::c_void;
 
+use anyhow::Error;
 //use env_logger::{self, Target};
 use flexi_logger::{opt_format, Logger};
 use jni::objects::JClass;
 use jni::sys::{jint, jlong, JavaVM, JNI_VERSION_1_8};
 use jni::JNIEnv;
-use log::info;
-use wasmtime::Engine;
+use log::{info, warn};
+use wasmtime::{Config, Engine};
 
+use crate::jni_cache;
 use crate::opaque_ptr::OpaquePtr;
+use crate::wasm_exception;
 
 /// Optional function defined by dynamically linked libraries. The VM calls JNI_OnLoad when the native library is loaded (for example, through System.loadLibrary).
 ///
@@ -26,7 +29,7 @@ use crate::opaque_ptr::OpaquePtr;
 /// Return the required JNI_VERSION constant (see also GetVersion).
 /// jint JNI_OnLoad(JavaVM *vm, void *reserved);
 #[no_mangle]
-pub extern "system" fn JNI_OnLoad(_vm: JavaVM, _reserved: *mut c_void) -> jint {
+pub extern "system" fn JNI_OnLoad(vm: *mut JavaVM, _reserved: *mut c_void) -> jint {
     Logger::with_env_or_str("wasmtime=info,wasmtime-jni=info")
         .log_to_file()
         .directory("target/wasm-logs")
@@ -36,10 +39,24 @@ pub extern "system" fn JNI_OnLoad(_vm: JavaVM, _reserved: *mut c_void) -> jint {
 
     // env_logger::builder().target(Target::Stdout).init();
 
+    // the classes of this library are only found by the class loader of the library, which is in use here
+    if let Err(err) = init_jni_cache(vm) {
+        warn!(
+            "failed to cache JNI classes, will retry on first use: {}",
+            err
+        );
+    }
+
     info!("wasmtime JNI loaded");
     JNI_VERSION_1_8
 }
 
+fn init_jni_cache(vm: *mut JavaVM) -> Result<(), Error> {
+    let vm = unsafe { jni::JavaVM::from_raw(vm)? };
+    let env = vm.get_env()?;
+    jni_cache::init(&env)
+}
+
 /// /*
 ///  * Class:     net_bluejekyll_wasmtime_Wasmtime
 ///  * Method:    newWasmEngineNtv
@@ -58,3 +75,27 @@ pub extern "system" fn Java_net_bluejekyll_wasmtime_Wasmtime_newWasmEngineNtv(
     let engine = Engine::default();
     OpaquePtr::from(engine).make_opaque()
 }
+
+/// /*
+///  * Class:     net_bluejekyll_wasmtime_Wasmtime
+///  * Method:    newWasmEngineWithConfigNtv
+///  * Signature: (J)J
+///  */
+///  JNIEXPORT jlong JNICALL Java_net_bluejekyll_wasmtime_Wasmtime_newWasmEngineWithConfigNtv
+///  (JNIEnv *, jclass, jlong);
+#[no_mangle]
+pub extern "system" fn Java_net_bluejekyll_wasmtime_Wasmtime_newWasmEngineWithConfigNtv<'j>(
+    env: JNIEnv<'j>,
+    _input: JClass<'j>,
+    config: OpaquePtr<'j, Config>,
+) -> jlong {
+    info!("wasmtime-jni: getting engine from config");
+
+    // the config is owned by the engine constructor, Java no longer frees it
+    let config = config.take();
+
+    wasm_exception::attempt(&env, |_env| {
+        let engine = Engine::new(&config)?;
+        Ok(OpaquePtr::from(engine).make_opaque())
+    })
+}